@@ -0,0 +1,685 @@
+//! Matches between bots, to measure their relative strength.
+//!
+//! [`run_arena`] plays every pair of the given [`YBotRegistry`] bots against
+//! each other on each board size, alternating who opens, with several games
+//! running in parallel. The resulting [`ArenaReport`] holds a [`GameRecord`]
+//! for every game, which can be written as JSON lines, and sums them up into
+//! win rates, Elo estimates and average move times.
+//!
+//! Elo differences are estimated from the score alone, with a 95% confidence
+//! interval from its standard error. A bot's rating in the standings is its
+//! difference against the average of the opponents it met.
+
+use crate::{
+    Coordinates, GameStatus, GameY, GameYError, Movement, Result, SearchLimits, YBot, YBotRegistry,
+    YEN,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+/// Which bots play, on which boards and how many games.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArenaConfig {
+    /// Registry names of the bots; every pair of them plays a match.
+    pub bots: Vec<String>,
+    /// Board sizes every match is played on.
+    pub board_sizes: Vec<u32>,
+    /// Games of each match on each board size.
+    pub games: u32,
+    /// Search limits given to the bots on every move.
+    pub limits: SearchLimits,
+    /// Number of games played at the same time. Every game gets fresh copies
+    /// of its bots that search on a single thread (see [`YBot::reseeded`]).
+    pub threads: usize,
+    /// Seed of the first game; game `i` uses `seed + i`.
+    pub seed: u64,
+}
+
+impl ArenaConfig {
+    /// Creates a configuration for `bots`: 10 games per match on a board of
+    /// size 7, one game per available core, with the bots' own search limits.
+    pub fn new(bots: Vec<String>) -> Self {
+        Self {
+            bots,
+            board_sizes: vec![7],
+            games: 10,
+            limits: SearchLimits::new(),
+            threads: std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1),
+            seed: 0,
+        }
+    }
+
+    /// Sets the board sizes every match is played on.
+    pub fn with_board_sizes(mut self, board_sizes: Vec<u32>) -> Self {
+        self.board_sizes = board_sizes;
+        self
+    }
+
+    /// Sets the number of games of each match on each board size.
+    pub fn with_games(mut self, games: u32) -> Self {
+        self.games = games;
+        self
+    }
+
+    /// Sets the search limits of every move.
+    pub fn with_limits(mut self, limits: SearchLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Sets how many games are played at the same time (at least one).
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Sets the seed of the first game.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+/// One move of a recorded game.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MoveRecord {
+    /// The player who moved (0 opens the game).
+    pub player: u32,
+    /// The cell played, or `None` for an action.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coords: Option<Coordinates>,
+    /// The action played (`Swap` or `Resign`), or `None` for a placement.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+    /// Time the bot took to decide, in microseconds.
+    pub time_us: u64,
+}
+
+/// A game played in the arena.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameRecord {
+    /// Index of the game in the arena.
+    pub game: usize,
+    /// Size of the board.
+    pub board_size: u32,
+    /// The bot playing each player; the first one opens.
+    pub players: [String; 2],
+    /// Seed the bots were given.
+    pub seed: u64,
+    /// The player who won.
+    pub winner: u32,
+    /// True if the loser could not move or tried an illegal move.
+    pub forfeit: bool,
+    /// The moves of the game, in order.
+    pub moves: Vec<MoveRecord>,
+    /// The final position.
+    pub position: YEN,
+}
+
+impl GameRecord {
+    /// Returns the name of the bot that won.
+    pub fn winner_bot(&self) -> &str {
+        &self.players[self.winner as usize]
+    }
+}
+
+/// An Elo difference with its 95% confidence interval.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EloEstimate {
+    /// Most likely difference.
+    pub difference: f64,
+    /// Lower end of the confidence interval.
+    pub low: f64,
+    /// Upper end of the confidence interval.
+    pub high: f64,
+}
+
+impl EloEstimate {
+    /// Estimates the Elo difference of a player who won `wins` of `games`.
+    ///
+    /// Scores of 0% and 100% are pulled in by half a game, so that the
+    /// estimate stays finite.
+    pub fn from_score(wins: u32, games: u32) -> Self {
+        if games == 0 {
+            return Self {
+                difference: 0.0,
+                low: 0.0,
+                high: 0.0,
+            };
+        }
+        let n = games as f64;
+        let margin = 0.5 / n;
+        let clamp = |score: f64| score.clamp(margin, 1.0 - margin);
+        let score = clamp(wins as f64 / n);
+        let error = 1.96 * (score * (1.0 - score) / n).sqrt();
+        Self {
+            difference: elo_difference(score),
+            low: elo_difference(clamp(score - error)),
+            high: elo_difference(clamp(score + error)),
+        }
+    }
+}
+
+impl Display for EloEstimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:+.0} [{:+.0}, {:+.0}]",
+            self.difference, self.low, self.high
+        )
+    }
+}
+
+/// Elo difference that makes `score` the expected score.
+fn elo_difference(score: f64) -> f64 {
+    400.0 * (score / (1.0 - score)).log10()
+}
+
+/// Results of one bot over all its games.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BotStanding {
+    /// Registry name of the bot.
+    pub bot: String,
+    /// Games played.
+    pub games: u32,
+    /// Games won.
+    pub wins: u32,
+    /// Elo difference against the average opponent.
+    pub elo: EloEstimate,
+    /// Average time per move, in milliseconds.
+    pub average_move_ms: f64,
+}
+
+impl BotStanding {
+    /// Fraction of the games won.
+    pub fn win_rate(&self) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            self.wins as f64 / self.games as f64
+        }
+    }
+}
+
+/// Results of a match between two bots, over every board size.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchResult {
+    /// The two bots.
+    pub bots: [String; 2],
+    /// Games played.
+    pub games: u32,
+    /// Games won by each bot.
+    pub wins: [u32; 2],
+    /// Elo difference of the first bot over the second.
+    pub elo: EloEstimate,
+}
+
+/// Everything played in an arena.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArenaReport {
+    /// Every game, in the order they were scheduled.
+    pub records: Vec<GameRecord>,
+    /// One entry per bot, the best first.
+    pub standings: Vec<BotStanding>,
+    /// One entry per pair of bots.
+    pub matches: Vec<MatchResult>,
+}
+
+impl ArenaReport {
+    /// Sums up a set of game records.
+    pub fn from_records(records: Vec<GameRecord>) -> Self {
+        let mut standings: Vec<BotStanding> = Vec::new();
+        let mut move_times: Vec<(u64, u64)> = Vec::new();
+        let mut matches: Vec<MatchResult> = Vec::new();
+
+        for record in &records {
+            for (player, bot) in record.players.iter().enumerate() {
+                let index = match standings.iter().position(|s| &s.bot == bot) {
+                    Some(index) => index,
+                    None => {
+                        standings.push(BotStanding {
+                            bot: bot.clone(),
+                            games: 0,
+                            wins: 0,
+                            elo: EloEstimate::from_score(0, 0),
+                            average_move_ms: 0.0,
+                        });
+                        move_times.push((0, 0));
+                        standings.len() - 1
+                    }
+                };
+                standings[index].games += 1;
+                standings[index].wins += (record.winner as usize == player) as u32;
+                for m in record.moves.iter().filter(|m| m.player as usize == player) {
+                    move_times[index].0 += m.time_us;
+                    move_times[index].1 += 1;
+                }
+            }
+
+            let mut pair = record.players.clone();
+            pair.sort();
+            let index = match matches.iter().position(|m| m.bots == pair) {
+                Some(index) => index,
+                None => {
+                    matches.push(MatchResult {
+                        bots: pair.clone(),
+                        games: 0,
+                        wins: [0, 0],
+                        elo: EloEstimate::from_score(0, 0),
+                    });
+                    matches.len() - 1
+                }
+            };
+            matches[index].games += 1;
+            let winner = (record.winner_bot() != pair[0]) as usize;
+            matches[index].wins[winner] += 1;
+        }
+
+        for (standing, (total_us, moves)) in standings.iter_mut().zip(move_times) {
+            standing.elo = EloEstimate::from_score(standing.wins, standing.games);
+            if moves > 0 {
+                standing.average_move_ms = total_us as f64 / moves as f64 / 1000.0;
+            }
+        }
+        for result in &mut matches {
+            result.elo = EloEstimate::from_score(result.wins[0], result.games);
+        }
+        standings.sort_by(|a, b| {
+            b.win_rate()
+                .total_cmp(&a.win_rate())
+                .then(a.bot.cmp(&b.bot))
+        });
+
+        Self {
+            records,
+            standings,
+            matches,
+        }
+    }
+
+    /// Writes every game record as one line of JSON.
+    pub fn write_records<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let filename = path.as_ref().display().to_string();
+        let io_error = |e: std::io::Error| GameYError::IoError {
+            message: format!("Failed to write file: {}", filename),
+            error: e.to_string(),
+        };
+        let mut file = std::io::BufWriter::new(std::fs::File::create(&path).map_err(io_error)?);
+        for record in &self.records {
+            let line =
+                serde_json::to_string(record).map_err(|e| GameYError::SerdeError { error: e })?;
+            writeln!(file, "{}", line).map_err(io_error)?;
+        }
+        file.flush().map_err(io_error)
+    }
+}
+
+impl Display for ArenaReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{:<22} {:>6} {:>6} {:>9} {:>22} {:>12}",
+            "Bot", "Games", "Wins", "Win rate", "Elo (95% CI)", "Avg move"
+        )?;
+        for s in &self.standings {
+            writeln!(
+                f,
+                "{:<22} {:>6} {:>6} {:>8.1}% {:>22} {:>9.1} ms",
+                s.bot,
+                s.games,
+                s.wins,
+                s.win_rate() * 100.0,
+                s.elo.to_string(),
+                s.average_move_ms
+            )?;
+        }
+        for m in &self.matches {
+            writeln!(
+                f,
+                "{} vs {}: {}-{} ({})",
+                m.bots[0], m.bots[1], m.wins[0], m.wins[1], m.elo
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Plays every pair of `config.bots` against each other and reports the results.
+///
+/// Each match plays `config.games` games on every board size, and the bots
+/// take turns to open. Returns [`GameYError::ArenaError`] if fewer than two
+/// bots are given or one of them is not in the registry.
+pub fn run_arena(registry: &YBotRegistry, config: &ArenaConfig) -> Result<ArenaReport> {
+    let arena_error = |message: String| GameYError::ArenaError { message };
+    if config.bots.len() < 2 {
+        return Err(arena_error("at least two bots are needed".to_string()));
+    }
+    if let Some(missing) = config.bots.iter().find(|b| registry.find(b).is_none()) {
+        return Err(arena_error(format!("unknown bot: {}", missing)));
+    }
+    if let Some(&size) = config.board_sizes.iter().find(|&&s| s == 0) {
+        return Err(arena_error(format!("invalid board size: {}", size)));
+    }
+
+    let mut schedule = Vec::new();
+    for &size in &config.board_sizes {
+        for (i, first) in config.bots.iter().enumerate() {
+            for second in &config.bots[i + 1..] {
+                for g in 0..config.games {
+                    let players = if g % 2 == 0 {
+                        [first.clone(), second.clone()]
+                    } else {
+                        [second.clone(), first.clone()]
+                    };
+                    schedule.push((size, players));
+                }
+            }
+        }
+    }
+
+    let next = AtomicUsize::new(0);
+    let mut records: Vec<GameRecord> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..config.threads.clamp(1, schedule.len().max(1)))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut played = Vec::new();
+                    loop {
+                        let game = next.fetch_add(1, Ordering::Relaxed);
+                        let Some((size, players)) = schedule.get(game) else {
+                            break;
+                        };
+                        let seed = config.seed.wrapping_add(game as u64);
+                        played.push(play_game(
+                            registry,
+                            game,
+                            *size,
+                            players,
+                            &config.limits,
+                            seed,
+                        ));
+                    }
+                    played
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("arena worker panicked"))
+            .collect()
+    });
+    records.sort_by_key(|r| r.game);
+    Ok(ArenaReport::from_records(records))
+}
+
+/// Plays one game until it is won, or until a bot fails to give a legal move.
+///
+/// Each side gets its own copy of its bot, so no search state (trees or
+/// transposition tables) is shared with the other side or with other games.
+fn play_game(
+    registry: &YBotRegistry,
+    game: usize,
+    board_size: u32,
+    players: &[String; 2],
+    limits: &SearchLimits,
+    seed: u64,
+) -> GameRecord {
+    let bots: Vec<Arc<dyn YBot>> = players
+        .iter()
+        .map(|name| {
+            registry
+                .find_seeded(name, seed)
+                .expect("bots are checked before playing")
+        })
+        .collect();
+
+    let mut board = GameY::new(board_size);
+    let mut moves = Vec::new();
+    let mut forfeit = None;
+    while let Some(player) = board.next_player() {
+        let start = Instant::now();
+        let decision = bots[player.id() as usize].decide(&board, limits);
+        let time_us = start.elapsed().as_micros() as u64;
+
+        let Some(decision) = decision else {
+            forfeit = Some(player);
+            break;
+        };
+        let record = match &decision.movement {
+            Movement::Placement { coords, .. } => MoveRecord {
+                player: player.id(),
+                coords: Some(*coords),
+                action: None,
+                time_us,
+            },
+            Movement::Action { action, .. } => MoveRecord {
+                player: player.id(),
+                coords: None,
+                action: Some(action.to_string()),
+                time_us,
+            },
+        };
+        if board.add_move(decision.movement).is_err() {
+            forfeit = Some(player);
+            break;
+        }
+        moves.push(record);
+    }
+
+    let winner = match (forfeit, board.status()) {
+        (Some(loser), _) => loser.opponent(),
+        (None, GameStatus::Finished { winner }) => *winner,
+        (None, GameStatus::Ongoing { next_player }) => next_player.opponent(),
+    };
+    GameRecord {
+        game,
+        board_size,
+        players: players.clone(),
+        seed,
+        winner: winner.id(),
+        forfeit: forfeit.is_some(),
+        moves,
+        position: YEN::from(&board),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BotDecision, GameAction, RandomBot};
+
+    // Bot that resigns as soon as it has to move
+    struct Resigner;
+
+    impl YBot for Resigner {
+        fn name(&self) -> &str {
+            "resigner"
+        }
+
+        fn choose_move(&self, _board: &GameY) -> Option<Coordinates> {
+            None
+        }
+
+        fn decide(&self, board: &GameY, limits: &SearchLimits) -> Option<BotDecision> {
+            Some(BotDecision {
+                movement: Movement::Action {
+                    player: board.next_player()?,
+                    action: GameAction::Resign,
+                },
+                analysis: self.search(board, limits),
+            })
+        }
+    }
+
+    // Bot that never finds a move
+    struct Silent;
+
+    impl YBot for Silent {
+        fn name(&self) -> &str {
+            "silent"
+        }
+
+        fn choose_move(&self, _board: &GameY) -> Option<Coordinates> {
+            None
+        }
+    }
+
+    fn registry() -> YBotRegistry {
+        YBotRegistry::new_empty()
+            .with_bot(Arc::new(RandomBot::new(None)))
+            .with_bot(Arc::new(Resigner))
+            .with_bot(Arc::new(Silent))
+    }
+
+    fn names(bots: &[&str]) -> Vec<String> {
+        bots.iter().map(|b| b.to_string()).collect()
+    }
+
+    #[test]
+    fn test_plays_every_pair_on_every_size() {
+        let config = ArenaConfig::new(names(&["random_bot", "resigner", "silent"]))
+            .with_board_sizes(vec![3, 4])
+            .with_games(4)
+            .with_threads(3);
+        let report = run_arena(&registry(), &config).unwrap();
+
+        assert_eq!(report.records.len(), 2 * 3 * 4);
+        assert_eq!(report.matches.len(), 3);
+        assert!(report.records.iter().enumerate().all(|(i, r)| r.game == i));
+        for standing in &report.standings {
+            assert_eq!(standing.games, 16);
+        }
+    }
+
+    #[test]
+    fn test_alternates_who_opens() {
+        let config = ArenaConfig::new(names(&["random_bot", "resigner"]))
+            .with_games(4)
+            .with_board_sizes(vec![3]);
+        let report = run_arena(&registry(), &config).unwrap();
+
+        let openers: Vec<&str> = report
+            .records
+            .iter()
+            .map(|r| r.players[0].as_str())
+            .collect();
+        assert_eq!(
+            openers,
+            vec!["random_bot", "resigner", "random_bot", "resigner"]
+        );
+    }
+
+    #[test]
+    fn test_resignation_and_forfeit_lose() {
+        let config = ArenaConfig::new(names(&["random_bot", "resigner", "silent"]))
+            .with_games(2)
+            .with_board_sizes(vec![4]);
+        let report = run_arena(&registry(), &config).unwrap();
+
+        // The random bot wins everything; between the other two the opener loses
+        assert_eq!(report.standings[0].bot, "random_bot");
+        assert_eq!(report.standings[0].wins, 4);
+        assert_eq!(report.standings[1].wins, 1);
+        assert_eq!(report.standings[2].wins, 1);
+
+        // Only the bot that never moves loses by failing to give a move
+        let forfeits: Vec<&GameRecord> = report.records.iter().filter(|r| r.forfeit).collect();
+        assert_eq!(forfeits.len(), 3);
+        assert!(forfeits.iter().all(|r| r.winner_bot() != "silent"));
+        let resigned = report
+            .records
+            .iter()
+            .find(|r| r.players == ["resigner".to_string(), "random_bot".to_string()])
+            .unwrap();
+        assert!(!resigned.forfeit);
+        assert_eq!(resigned.moves[0].action.as_deref(), Some("Resign"));
+    }
+
+    #[test]
+    fn test_games_are_reproducible() {
+        let bots = YBotRegistry::new();
+        let config = ArenaConfig::new(names(&["random_bot", "heuristic_bot"]))
+            .with_games(4)
+            .with_board_sizes(vec![5])
+            .with_seed(7);
+        let first = run_arena(&bots, &config).unwrap();
+        let second = run_arena(&bots, &config.clone().with_threads(1)).unwrap();
+
+        let positions = |r: &ArenaReport| {
+            r.records
+                .iter()
+                .map(|g| g.position.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(positions(&first), positions(&second));
+        assert_eq!(first.records[0].seed, 7);
+        assert_eq!(first.records[3].seed, 10);
+    }
+
+    #[test]
+    fn test_rejects_bad_configurations() {
+        let bots = registry();
+        assert!(matches!(
+            run_arena(&bots, &ArenaConfig::new(names(&["random_bot"]))),
+            Err(GameYError::ArenaError { .. })
+        ));
+        let unknown = run_arena(&bots, &ArenaConfig::new(names(&["random_bot", "nobody"])));
+        assert!(unknown.unwrap_err().to_string().contains("nobody"));
+        let no_board = ArenaConfig::new(names(&["random_bot", "silent"])).with_board_sizes(vec![0]);
+        assert!(run_arena(&bots, &no_board).is_err());
+    }
+
+    #[test]
+    fn test_elo_estimate() {
+        let even = EloEstimate::from_score(10, 20);
+        assert_eq!(even.difference, 0.0);
+        assert!(even.low < 0.0 && even.high > 0.0);
+        assert!((even.low + even.high).abs() < 1e-9);
+
+        // A 75% score is about 191 points
+        let better = EloEstimate::from_score(15, 20);
+        assert!((better.difference - 190.85).abs() < 0.1);
+        assert!(better.low < better.difference && better.difference < better.high);
+
+        // A 100% score does not give an infinite difference
+        let sweep = EloEstimate::from_score(20, 20);
+        assert!(sweep.difference.is_finite() && sweep.difference > better.difference);
+        assert_eq!(sweep.high, sweep.difference);
+        assert_eq!(EloEstimate::from_score(0, 0).difference, 0.0);
+    }
+
+    #[test]
+    fn test_write_records_as_json_lines() {
+        let config = ArenaConfig::new(names(&["random_bot", "resigner"]))
+            .with_games(2)
+            .with_board_sizes(vec![3]);
+        let report = run_arena(&registry(), &config).unwrap();
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        report.write_records(file.path()).unwrap();
+        let content = std::fs::read_to_string(file.path()).unwrap();
+        let lines: Vec<GameRecord> = content
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines, report.records);
+    }
+
+    #[test]
+    fn test_report_display() {
+        let config = ArenaConfig::new(names(&["random_bot", "resigner"]))
+            .with_games(2)
+            .with_board_sizes(vec![3]);
+        let text = run_arena(&registry(), &config).unwrap().to_string();
+
+        assert!(text.contains("Elo (95% CI)"));
+        assert!(text.contains("random_bot"));
+        assert!(text.contains("random_bot vs resigner: 2-0"));
+    }
+}
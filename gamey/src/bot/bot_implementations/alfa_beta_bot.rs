@@ -1,6 +1,8 @@
-use crate::{Coordinates, GameY, PlayerId};
+use crate::{BotDecision, Coordinates, DEFAULT_ENDGAME_CELLS, DEFAULT_RESIGN_THRESHOLD, GameStatus, GameY, MoveValidation, PlayerId, SearchLimits, SearchResult, SolveOutcome, decide_with_search, solve};
 use crate::bot::YBot;
-use std::collections::{HashSet, HashMap};
+use super::transposition_table::{NodeType, TranspositionEntry, TranspositionTable};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 // ============================================================
 // CONSTANTES DE PRIORIDAD
@@ -19,65 +21,122 @@ const PROXIMITY_TO_OPPONENT: i32 = 800;
 
 // Constantes del bot
 const DEFAULT_MAX_DEPTH: u32 = 4;
-const BOT_PLAYER_ID: u32 = 1;
-const HUMAN_PLAYER_ID: u32 = 0;
+const DEFAULT_TIME_LIMIT_MS: u64 = 2000;
+// Cada cuántos nodos se mira el reloj durante la búsqueda
+const TIME_CHECK_INTERVAL: u64 = 1024;
 const TRANSPOSITION_TABLE_SIZE: usize = 500000;
+// Nodos del solver de final si la búsqueda no tiene límite de nodos
+const ENDGAME_SOLVER_NODES: u64 = 200000;
 
 pub struct AlfaBetaBot {
     max_depth: u32,
-    transposition_table: HashMap<u64, TranspositionEntry>,
+    time_limit_ms: u64,
+    // Límite opcional de nodos por búsqueda
+    max_nodes: Option<u64>,
+    // Se rinde si su probabilidad de ganar baja de este valor
+    resign_threshold: f64,
+    // Con este número de casillas libres o menos se intenta resolver la
+    // posición con el solver antes de buscar
+    endgame_cells: Option<u32>,
+    // Compartida entre todas las llamadas a choose_move (y con otros bots si
+    // se pasa con with_transposition_table)
+    transposition_table: Arc<TranspositionTable>,
     pub nodes_evaluated: u64,
-}
-
-#[derive(Clone)]
-struct TranspositionEntry {
-    score: i32,
-    depth: u32,
-    flag: NodeType,
-    _best_move: Option<Coordinates>,
-}
-
-#[derive(Clone, PartialEq)]
-enum NodeType {
-    Exact,
-    Lower,
-    Upper,
+    // Momento en el que la búsqueda en curso debe parar
+    deadline: Option<Instant>,
+    // Se activa al pasar el deadline: los resultados de esa profundidad se descartan
+    aborted: bool,
+    // Última profundidad completada por la profundización iterativa
+    completed_depth: u32,
 }
 
 impl AlfaBetaBot {
     pub fn new(depth: Option<u32>) -> Self {
         Self {
             max_depth: depth.unwrap_or(DEFAULT_MAX_DEPTH),
-            transposition_table: HashMap::with_capacity(TRANSPOSITION_TABLE_SIZE),
+            time_limit_ms: DEFAULT_TIME_LIMIT_MS,
+            max_nodes: None,
+            resign_threshold: DEFAULT_RESIGN_THRESHOLD,
+            endgame_cells: Some(DEFAULT_ENDGAME_CELLS),
+            transposition_table: Arc::new(TranspositionTable::new(TRANSPOSITION_TABLE_SIZE)),
             nodes_evaluated: 0,
+            deadline: None,
+            aborted: false,
+            completed_depth: 0,
         }
     }
 
-    fn evaluate_board(&self, board: &GameY, for_player_id: u32) -> i32 {
-        // IMPORTANTE: for_player_id es el jugador PARA EL QUE evaluamos
-        let player_cells = if for_player_id == BOT_PLAYER_ID {
-            // Si evaluamos para el bot (player 1), sus celdas son las del oponente
-            // porque el bot SIEMPRE juega como oponente
-            board.get_opponent_positions_coords()
-        } else {
-            // Si evaluamos para el humano (player 0), sus celdas son las del jugador
-            board.get_player_positions_coords()
-        };
+    /// Cambia el tiempo máximo por jugada. La búsqueda profundiza de uno en uno
+    /// hasta `max_depth` y, si se acaba el tiempo, juega la mejor jugada de la
+    /// última profundidad completada.
+    pub fn with_time_limit(mut self, time_limit_ms: u64) -> Self {
+        self.time_limit_ms = time_limit_ms;
+        self
+    }
 
-        let opponent_cells = if for_player_id == BOT_PLAYER_ID {
-            // El oponente del bot es el humano
-            board.get_player_positions_coords()
-        } else {
-            board.get_opponent_positions_coords()
-        };
+    /// Usa `table` como tabla de transposición. Varios bots pueden compartir
+    /// la misma tabla a través del `Arc`.
+    pub fn with_transposition_table(mut self, table: Arc<TranspositionTable>) -> Self {
+        self.transposition_table = table;
+        self
+    }
+
+    /// Limita el número de nodos que puede evaluar cada búsqueda. Igual que
+    /// con el tiempo, al llegar al límite se juega la mejor jugada de la
+    /// última profundidad completada.
+    pub fn with_max_nodes(mut self, max_nodes: u64) -> Self {
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    /// Cambia la probabilidad de ganar por debajo de la cual el bot se rinde.
+    /// La búsqueda solo da una probabilidad cuando ve una victoria o una
+    /// derrota forzadas, así que se rinde ante derrotas seguras; con 0 no se
+    /// rinde nunca.
+    pub fn with_resign_threshold(mut self, resign_threshold: f64) -> Self {
+        self.resign_threshold = resign_threshold;
+        self
+    }
+
+    /// Resuelve con el solver de final las posiciones con `max_empty_cells`
+    /// casillas libres o menos antes de buscarlas; `None` lo desactiva. Si la
+    /// posición está ganada juega la jugada ganadora, y si está perdida busca
+    /// como siempre pero informa de la derrota.
+    pub fn with_endgame_solver(mut self, max_empty_cells: Option<u32>) -> Self {
+        self.endgame_cells = max_empty_cells;
+        self
+    }
+
+    // Comprueba el límite de nodos y, cada TIME_CHECK_INTERVAL nodos, el reloj
+    fn out_of_time(&mut self) -> bool {
+        if !self.aborted
+            && let Some(max_nodes) = self.max_nodes
+            && self.nodes_evaluated > max_nodes
+        {
+            self.aborted = true;
+        }
+        if !self.aborted
+            && self.nodes_evaluated.is_multiple_of(TIME_CHECK_INTERVAL)
+            && let Some(deadline) = self.deadline
+            && Instant::now() >= deadline
+        {
+            self.aborted = true;
+        }
+        self.aborted
+    }
+
+    fn evaluate_board(&self, board: &GameY, player: PlayerId) -> i32 {
+        // IMPORTANTE: player es el jugador PARA EL QUE evaluamos
+        let player_cells = board.stones_of(player);
+        let opponent_cells = board.stones_of(player.opponent());
 
         let mut score = 0;
 
         // VICTORIA INMEDIATA
-        if self.check_winner(board, BOT_PLAYER_ID) {
+        if self.check_winner(board, player) {
             return WIN_NOW;
         }
-        if self.check_winner(board, HUMAN_PLAYER_ID) {
+        if self.check_winner(board, player.opponent()) {
             return -WIN_NOW;
         }
 
@@ -112,26 +171,34 @@ impl AlfaBetaBot {
     }
 
     fn find_all_connected_groups(&self, cells: &[Coordinates], board: &GameY) -> Vec<Vec<Coordinates>> {
-        let mut visited = HashSet::new();
-        let mut groups = Vec::new();
+        let board_size = board.board_size();
+        let topology = board.topology();
+        // Celdas del grupo candidato, indexadas por índice de celda
+        let mut pending = vec![false; topology.total_cells() as usize];
+        for cell in cells {
+            pending[cell.to_index(board_size) as usize] = true;
+        }
 
+        let mut groups = Vec::new();
         for &cell in cells {
-            if !visited.contains(&cell) {
-                let mut group = Vec::new();
-                let mut stack = vec![cell];
-                visited.insert(cell);
-
-                while let Some(current) = stack.pop() {
-                    group.push(current);
-                    for &other in cells {
-                        if !visited.contains(&other) && board.manhattan_distance(current, other) == 1 {
-                            visited.insert(other);
-                            stack.push(other);
-                        }
+            let start = cell.to_index(board_size);
+            if !pending[start as usize] {
+                continue;
+            }
+            pending[start as usize] = false;
+            let mut group = Vec::new();
+            let mut stack = vec![start];
+
+            while let Some(current) = stack.pop() {
+                group.push(Coordinates::from_index(current, board_size));
+                for &other in topology.neighbors(current) {
+                    if pending[other as usize] {
+                        pending[other as usize] = false;
+                        stack.push(other);
                     }
                 }
-                groups.push(group);
             }
+            groups.push(group);
         }
         groups
     }
@@ -160,11 +227,11 @@ impl AlfaBetaBot {
     fn virtual_connections_score(&self, cells: &[Coordinates], board: &GameY) -> i32 {
         let mut score = 0;
         let board_size = board.board_size();
+        let topology = board.topology();
 
         for &cell in cells {
-            let neighbors = self.get_virtual_neighbors(cell, board_size);
-            for &neighbor in &neighbors {
-                if self.is_cell_empty(board, neighbor) {
+            for &bridge in topology.bridges(cell.to_index(board_size)) {
+                if board.stone_at(bridge).is_none() {
                     score += 1;
                 }
             }
@@ -172,32 +239,6 @@ impl AlfaBetaBot {
         score
     }
 
-    fn get_virtual_neighbors(&self, coords: Coordinates, board_size: u32) -> Vec<Coordinates> {
-        let mut neighbors = Vec::new();
-        let x = coords.x() as i32;
-        let y = coords.y() as i32;
-        let z = coords.z() as i32;
-        let n = (board_size - 1) as i32;
-
-        let jumps = [
-            (2, -1, -1), (1, -2, 1), (-1, -1, 2),
-            (-2, 1, 1), (-1, 2, -1), (1, 1, -2),
-        ];
-
-        for &(dx, dy, dz) in &jumps {
-            let new_x = x + dx;
-            let new_y = y + dy;
-            let new_z = z + dz;
-
-            if new_x >= 0 && new_y >= 0 && new_z >= 0 &&
-                new_x <= n && new_y <= n && new_z <= n &&
-                new_x + new_y + new_z == n {
-                neighbors.push(Coordinates::new(new_x as u32, new_y as u32, new_z as u32));
-            }
-        }
-        neighbors
-    }
-
     fn center_control_score(&self, cells: &[Coordinates], board_size: u32) -> i32 {
         let n = board_size as i32 - 1;
         let center = n as f32 / 3.0;
@@ -264,16 +305,17 @@ impl AlfaBetaBot {
         score
     }
 
-    fn detect_winning_threat(&self, board: &GameY, player_id: u32) -> Option<Coordinates> {
-        let available = board.available_cells();
+    // Sólo lo usan las pruebas: la búsqueda detecta las amenazas al evaluar
+    #[cfg(test)]
+    fn detect_winning_threat(&self, board: &GameY, player: PlayerId) -> Option<Coordinates> {
+        // Recorremos las celdas en orden de índice: el orden de available_cells
+        // cambia según los movimientos jugados
+        let mut available = board.available_cells().clone();
+        available.sort_unstable();
 
         for &cell_idx in available.iter() {
             let coords = Coordinates::from_index(cell_idx, board.board_size());
-            let mut temp_cells = if player_id == BOT_PLAYER_ID {
-                board.get_opponent_positions_coords()
-            } else {
-                board.get_player_positions_coords()
-            };
+            let mut temp_cells = board.stones_of(player);
             temp_cells.push(coords);
 
             let groups = self.find_all_connected_groups(&temp_cells, board);
@@ -290,16 +332,20 @@ impl AlfaBetaBot {
         None
     }
 
-    fn order_moves(&self, board: &GameY, moves: &[u32], is_bot_turn: bool) -> Vec<u32> {
+    // Ordena las jugadas de `player`, el jugador al que le toca mover
+    fn order_moves(&self, board: &GameY, moves: &[u32], player: PlayerId) -> Vec<u32> {
+        let my_cells = board.stones_of(player);
+        let opponent_cells = board.stones_of(player.opponent());
+
         let mut move_scores: Vec<(u32, i32)> = moves.iter()
             .map(|&cell_idx| {
                 let coords = Coordinates::from_index(cell_idx, board.board_size());
                 let mut score = 0;
 
                 // Victoria inmediata
-                let mut temp_bot_cells = board.get_opponent_positions_coords();
-                temp_bot_cells.push(coords);
-                let groups = self.find_all_connected_groups(&temp_bot_cells, board);
+                let mut temp_my_cells = my_cells.clone();
+                temp_my_cells.push(coords);
+                let groups = self.find_all_connected_groups(&temp_my_cells, board);
                 for group in groups {
                     let touches_a = group.iter().any(|c| c.touches_side_a());
                     let touches_b = group.iter().any(|c| c.touches_side_b());
@@ -310,10 +356,10 @@ impl AlfaBetaBot {
                 }
 
                 // Bloquear victoria del oponente
-                let mut temp_user_cells = board.get_player_positions_coords();
-                temp_user_cells.push(coords);
-                let groups_user = self.find_all_connected_groups(&temp_user_cells, board);
-                for group in groups_user {
+                let mut temp_opponent_cells = opponent_cells.clone();
+                temp_opponent_cells.push(coords);
+                let groups_opponent = self.find_all_connected_groups(&temp_opponent_cells, board);
+                for group in groups_opponent {
                     let touches_a = group.iter().any(|c| c.touches_side_a());
                     let touches_b = group.iter().any(|c| c.touches_side_b());
                     let touches_c = group.iter().any(|c| c.touches_side_c());
@@ -339,22 +385,28 @@ impl AlfaBetaBot {
             })
             .collect();
 
-        move_scores.sort_by(|a, b| b.1.cmp(&a.1));
+        move_scores.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
         move_scores.into_iter().map(|(cell_idx, _)| cell_idx).collect()
     }
 
     fn alphabeta(
         &mut self,
-        board: &GameY,
+        board: &mut GameY,
         depth: u32,
         mut alpha: i32,
         mut beta: i32,
+        bot_player: PlayerId,
         is_bot_turn: bool,
     ) -> i32 {
         self.nodes_evaluated += 1;
+        if self.out_of_time() {
+            return 0;
+        }
 
-        let hash = self.hash_board(board);
-        if let Some(entry) = self.transposition_table.get(&hash) {
+        let hash = board.zobrist_hash();
+        let board_size = board.board_size();
+        let mut tt_move = None;
+        if let Some(entry) = self.transposition_table.probe(hash, board_size, bot_player) {
             if entry.depth >= depth {
                 match entry.flag {
                     NodeType::Exact => return entry.score,
@@ -363,16 +415,18 @@ impl AlfaBetaBot {
                     _ => {}
                 }
             }
+            // Aunque la entrada no sirva como resultado, su mejor jugada se prueba primero
+            tt_move = entry.best_move;
         }
 
         if depth == 0 {
-            return self.evaluate_board(board, BOT_PLAYER_ID);
+            return self.evaluate_board(board, bot_player);
         }
 
-        if self.check_winner(board, BOT_PLAYER_ID) {
+        if self.check_winner(board, bot_player) {
             return WIN_NOW;
         }
-        if self.check_winner(board, HUMAN_PLAYER_ID) {
+        if self.check_winner(board, bot_player.opponent()) {
             return -WIN_NOW;
         }
 
@@ -381,28 +435,31 @@ impl AlfaBetaBot {
             return 0;
         }
 
-        let ordered_moves = self.order_moves(board, available, is_bot_turn);
-        let current_player_id = if is_bot_turn { BOT_PLAYER_ID } else { HUMAN_PLAYER_ID };
+        let current_player = if is_bot_turn { bot_player } else { bot_player.opponent() };
+        let mut ordered_moves = self.order_moves(board, available, current_player);
+        if let Some(cell_idx) = tt_move {
+            move_to_front(&mut ordered_moves, cell_idx);
+        }
 
         let mut best_score = if is_bot_turn { i32::MIN } else { i32::MAX };
+        let mut best_move = None;
         let original_alpha = alpha;
         let original_beta = beta;
 
         if is_bot_turn {
             for &cell_idx in &ordered_moves {
                 let coords = Coordinates::from_index(cell_idx, board.board_size());
-                let mut board_copy = board.clone();
-
-                let movement = crate::Movement::Placement {
-                    player: PlayerId::new(current_player_id),
-                    coords,
-                };
 
-                if board_copy.add_move(movement).is_ok() {
-                    let eval = self.alphabeta(&board_copy, depth - 1, alpha, beta, false);
+                if board.make_move(current_player, coords).is_ok() {
+                    let eval = self.alphabeta(board, depth - 1, alpha, beta, bot_player, false);
+                    board.unmake_move();
+                    if self.aborted {
+                        break;
+                    }
 
                     if eval > best_score {
                         best_score = eval;
+                        best_move = Some(cell_idx);
                     }
 
                     alpha = alpha.max(eval);
@@ -414,18 +471,17 @@ impl AlfaBetaBot {
         } else {
             for &cell_idx in &ordered_moves {
                 let coords = Coordinates::from_index(cell_idx, board.board_size());
-                let mut board_copy = board.clone();
-
-                let movement = crate::Movement::Placement {
-                    player: PlayerId::new(current_player_id),
-                    coords,
-                };
 
-                if board_copy.add_move(movement).is_ok() {
-                    let eval = self.alphabeta(&board_copy, depth - 1, alpha, beta, true);
+                if board.make_move(current_player, coords).is_ok() {
+                    let eval = self.alphabeta(board, depth - 1, alpha, beta, bot_player, true);
+                    board.unmake_move();
+                    if self.aborted {
+                        break;
+                    }
 
                     if eval < best_score {
                         best_score = eval;
+                        best_move = Some(cell_idx);
                     }
 
                     beta = beta.min(eval);
@@ -436,6 +492,11 @@ impl AlfaBetaBot {
             }
         }
 
+        // Un resultado cortado por el reloj no es fiable: no se guarda
+        if self.aborted {
+            return best_score;
+        }
+
         let flag = if best_score <= original_alpha {
             NodeType::Upper
         } else if best_score >= original_beta {
@@ -444,49 +505,25 @@ impl AlfaBetaBot {
             NodeType::Exact
         };
 
-        self.transposition_table.insert(hash, TranspositionEntry {
+        self.transposition_table.store(hash, board_size, bot_player, TranspositionEntry {
             score: best_score,
             depth,
             flag,
-            _best_move: None,
+            best_move,
         });
 
         best_score
     }
 
-    fn hash_board(&self, board: &GameY) -> u64 {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-
-        let mut hasher = DefaultHasher::new();
-        let state: Vec<u32> = board.available_cells().iter().copied().collect();
-        state.hash(&mut hasher);
-        hasher.finish()
-    }
-
-    fn check_winner(&self, board: &GameY, player_id: u32) -> bool {
-        let cells = if player_id == BOT_PLAYER_ID {
-            board.get_opponent_positions_coords()
-        } else {
-            board.get_player_positions_coords()
-        };
-
-        let groups = self.find_all_connected_groups(&cells, board);
-        for group in groups {
-            let touches_a = group.iter().any(|c| c.touches_side_a());
-            let touches_b = group.iter().any(|c| c.touches_side_b());
-            let touches_c = group.iter().any(|c| c.touches_side_c());
-
-            if touches_a && touches_b && touches_c {
-                return true;
-            }
-        }
-        false
+    fn check_winner(&self, board: &GameY, player: PlayerId) -> bool {
+        // El tablero ya mantiene el estado con su union-find: no hace falta recorrer grupos
+        matches!(board.status(), GameStatus::Finished { winner } if *winner == player)
     }
 
+    // Sólo lo usan las pruebas: la búsqueda recorre available_cells
+    #[cfg(test)]
     fn is_cell_empty(&self, board: &GameY, coords: Coordinates) -> bool {
-        let idx = coords.to_index(board.board_size());
-        board.available_cells().contains(&idx)
+        board.stone_at(coords.to_index(board.board_size())).is_none()
     }
 }
 
@@ -496,46 +533,218 @@ impl YBot for AlfaBetaBot {
     }
 
     fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
-        let mut bot = AlfaBetaBot::new(Some(self.max_depth));
-        bot.choose_move_internal(board)
+        self.search(board, &SearchLimits::default()).best_move
+    }
+
+    fn search(&self, board: &GameY, limits: &SearchLimits) -> SearchResult {
+        // La búsqueda necesita estado mutable propio, pero la tabla se comparte
+        // para aprovechar lo calculado en jugadas anteriores
+        let mut bot = AlfaBetaBot::new(Some(limits.max_depth.unwrap_or(self.max_depth)))
+            .with_time_limit(limits.max_time_ms.unwrap_or(self.time_limit_ms))
+            .with_transposition_table(Arc::clone(&self.transposition_table))
+            .with_endgame_solver(self.endgame_cells);
+        bot.max_nodes = limits.max_nodes.or(self.max_nodes);
+        bot.search_internal(board)
+    }
+
+    fn decide(&self, board: &GameY, limits: &SearchLimits) -> Option<BotDecision> {
+        decide_with_search(self, board, limits, self.time_limit_ms, self.resign_threshold)
+    }
+
+    // Copia con la misma configuración y una tabla propia y vacía, para que
+    // no dependa de lo que hayan buscado otras partidas. La búsqueda no usa
+    // el azar, así que la semilla no cambia nada
+    fn reseeded(&self, _seed: u64) -> Option<Arc<dyn YBot>> {
+        let table = TranspositionTable::new(self.transposition_table.capacity());
+        let mut bot = AlfaBetaBot::new(Some(self.max_depth))
+            .with_time_limit(self.time_limit_ms)
+            .with_resign_threshold(self.resign_threshold)
+            .with_endgame_solver(self.endgame_cells)
+            .with_transposition_table(Arc::new(table));
+        bot.max_nodes = self.max_nodes;
+        Some(Arc::new(bot))
     }
 }
 
 impl AlfaBetaBot {
+    #[cfg(test)]
     fn choose_move_internal(&mut self, board: &GameY) -> Option<Coordinates> {
+        self.search_internal(board).best_move
+    }
+
+    fn search_internal(&mut self, board: &GameY) -> SearchResult {
+        let start = Instant::now();
+        let Some(bot_player) = board.next_player() else {
+            return SearchResult::from_move(None, start.elapsed());
+        };
+
+        // La búsqueda alterna los colores por su cuenta, así que trabaja sobre
+        // una copia en modo permisivo. Las jugadas se prueban y se deshacen
+        // sobre esa única copia
+        let board = &mut board.clone().with_validation(MoveValidation::Permissive);
+        if board.available_cells().is_empty() {
+            return SearchResult::from_move(None, start.elapsed());
+        }
+
+        // FINAL DE PARTIDA: con pocas casillas libres se intenta demostrar el
+        // resultado antes de buscar. El solver tiene la mitad del tiempo y sus
+        // nodos no cuentan para el límite de la búsqueda
+        let mut proven_loss = false;
+        let mut solver_nodes = 0;
+        if let Some(max_empty_cells) = self.endgame_cells
+            && board.available_cells().len() as u32 <= max_empty_cells
+        {
+            let solver_limits = SearchLimits::new()
+                .with_max_nodes(self.max_nodes.unwrap_or(ENDGAME_SOLVER_NODES))
+                .with_max_time_ms(self.time_limit_ms / 2);
+            let solution = solve(board, &solver_limits);
+            solver_nodes = solution.nodes;
+            match solution.outcome {
+                SolveOutcome::Win => {
+                    return SearchResult {
+                        score: Some(WIN_NOW),
+                        elapsed_ms: start.elapsed().as_millis() as u64,
+                        ..solution.to_search_result()
+                    };
+                }
+                SolveOutcome::Loss => proven_loss = true,
+                SolveOutcome::Unknown => {}
+            }
+        }
+
         let available = board.available_cells();
-        if available.is_empty() {
-            return None;
+
+        let mut ordered_moves = self.order_moves(board, available, bot_player);
+        // Una búsqueda anterior de esta posición indica por dónde empezar
+        if let Some(entry) = self.transposition_table.probe(board.zobrist_hash(), board.board_size(), bot_player)
+            && let Some(cell_idx) = entry.best_move
+        {
+            move_to_front(&mut ordered_moves, cell_idx);
+        }
+        // Si no da tiempo a completar ni la profundidad 1, se juega la mejor
+        // según la ordenación
+        let mut best_move = ordered_moves[0];
+        let mut best_score = None;
+
+        self.transposition_table.new_search();
+        self.deadline = Some(Instant::now() + Duration::from_millis(self.time_limit_ms));
+        self.aborted = false;
+        self.completed_depth = 0;
+
+        // PROFUNDIZACIÓN ITERATIVA: cada profundidad empieza por la mejor
+        // jugada de la anterior
+        for depth in 1..=self.max_depth {
+            let Some((cell_idx, score)) = self.search_root(board, &ordered_moves, depth, bot_player) else {
+                break;
+            };
+            best_move = cell_idx;
+            best_score = Some(score);
+            self.completed_depth = depth;
+
+            move_to_front(&mut ordered_moves, cell_idx);
+
+            // Con la victoria o la derrota ya decididas no hace falta mirar más hondo
+            if score.abs() >= WIN_NOW {
+                break;
+            }
+        }
+
+        self.deadline = None;
+        let best_coords = Coordinates::from_index(best_move, board.board_size());
+        let win_probability = best_score
+            .filter(|score| score.abs() >= WIN_NOW)
+            .map(|score| if score > 0 { 1.0 } else { 0.0 })
+            .or(proven_loss.then_some(0.0));
+
+        SearchResult {
+            best_move: Some(best_coords),
+            score: best_score,
+            win_probability,
+            principal_variation: self.principal_variation(board, best_move, bot_player),
+            depth: Some(self.completed_depth),
+            nodes: self.nodes_evaluated + solver_nodes,
+            elapsed_ms: start.elapsed().as_millis() as u64,
         }
+    }
+
+    // Reconstruye la variante principal siguiendo las mejores jugadas guardadas
+    // en la tabla, hasta la profundidad completada
+    fn principal_variation(&self, board: &mut GameY, first_move: u32, bot_player: PlayerId) -> Vec<Coordinates> {
+        let board_size = board.board_size();
+        let mut pv = Vec::new();
+        let mut next = Some(first_move);
+        let mut player = bot_player;
+
+        while let Some(cell_idx) = next
+            && pv.len() < self.completed_depth.max(1) as usize
+            && board.stone_at(cell_idx).is_none()
+        {
+            let coords = Coordinates::from_index(cell_idx, board_size);
+            if board.make_move(player, coords).is_err() {
+                break;
+            }
+            pv.push(coords);
+            player = player.opponent();
+            next = self
+                .transposition_table
+                .probe(board.zobrist_hash(), board_size, bot_player)
+                .and_then(|entry| entry.best_move);
+        }
+
+        for _ in 0..pv.len() {
+            board.unmake_move();
+        }
+        pv
+    }
 
+    // Busca a profundidad `depth` desde la raíz y devuelve la mejor jugada y
+    // su puntuación, o None si se acabó el tiempo antes de terminar
+    fn search_root(
+        &mut self,
+        board: &mut GameY,
+        ordered_moves: &[u32],
+        depth: u32,
+        bot_player: PlayerId,
+    ) -> Option<(u32, i32)> {
         let mut best_score = i32::MIN;
         let mut best_move = None;
         let mut alpha = i32::MIN;
         let beta = i32::MAX;
 
-        let ordered_moves = self.order_moves(board, available, true);
-
-        for &cell_idx in &ordered_moves {
+        for &cell_idx in ordered_moves {
             let coords = Coordinates::from_index(cell_idx, board.board_size());
-            let mut board_copy = board.clone();
 
-            let movement = crate::Movement::Placement {
-                player: PlayerId::new(BOT_PLAYER_ID),
-                coords,
-            };
+            if board.make_move(bot_player, coords).is_ok() {
+                let score = self.alphabeta(board, depth - 1, alpha, beta, bot_player, false);
+                board.unmake_move();
 
-            if board_copy.add_move(movement).is_ok() {
-                let score = self.alphabeta(&board_copy, self.max_depth - 1, alpha, beta, false);
+                if self.aborted {
+                    return None;
+                }
 
                 if score > best_score {
                     best_score = score;
-                    best_move = Some(coords);
+                    best_move = Some(cell_idx);
                     alpha = alpha.max(score);
                 }
             }
         }
 
-        best_move
+        let cell_idx = best_move?;
+        self.transposition_table.store(board.zobrist_hash(), board.board_size(), bot_player, TranspositionEntry {
+            score: best_score,
+            depth,
+            flag: NodeType::Exact,
+            best_move: Some(cell_idx),
+        });
+        Some((cell_idx, best_score))
+    }
+}
+
+// Mueve `cell_idx` al principio de `moves` sin alterar el orden del resto
+fn move_to_front(moves: &mut [u32], cell_idx: u32) {
+    if let Some(pos) = moves.iter().position(|&m| m == cell_idx) {
+        moves[..=pos].rotate_right(1);
     }
 }
 
@@ -546,10 +755,17 @@ mod tests {
     // GRUPO 1: CONSTRUCTOR Y PROPIEDADES BÁSICAS
     // ============================================================
 
-    use crate::{AlfaBetaBot, Coordinates, GameY, Movement, PlayerId, YBot};
-    use crate::bot_implementations::alfa_beta_bot::{NodeType, TranspositionEntry, BLOCK_OPPONENT_WIN, BOT_PLAYER_ID, BRIDGE_PATTERN, CONNECT_ONE_SIDE, CONNECT_TWO_SIDES, DEFAULT_MAX_DEPTH, GROUP_SIZE_BONUS, HUMAN_PLAYER_ID, TRANSPOSITION_TABLE_SIZE, WIN_NOW};
+    use crate::{AlfaBetaBot, Coordinates, GameAction, GameY, MoveValidation, Movement, PlayerId, SearchLimits, Topology, TranspositionTable, YBot};
+    use std::sync::Arc;
+    use crate::bot_implementations::alfa_beta_bot::{BLOCK_OPPONENT_WIN, BRIDGE_PATTERN, CONNECT_ONE_SIDE, CONNECT_TWO_SIDES, DEFAULT_MAX_DEPTH, DEFAULT_TIME_LIMIT_MS, GROUP_SIZE_BONUS, TRANSPOSITION_TABLE_SIZE, WIN_NOW};
+
+    // Colores usados en las posiciones de prueba
+    const BOT_PLAYER_ID: u32 = 1;
+    const HUMAN_PLAYER_ID: u32 = 0;
+
     fn create_test_game(size: u32, moves: Vec<(u32, u32, u32, u32)>) -> GameY {
-        let mut game = GameY::new(size);
+        // Las posiciones de prueba no siguen el orden de turnos
+        let mut game = GameY::new(size).with_validation(MoveValidation::Permissive);
         for (x, y, z, player_id) in moves {
             let coords = Coordinates::new(x, y, z);
             let movement = Movement::Placement {
@@ -1057,7 +1273,7 @@ mod tests {
             Coordinates::new(3, 2, 1),  // Celda interior
         ];
 
-        let neighbors = bot.get_virtual_neighbors(cells[0], 7);
+        let neighbors = get_virtual_neighbors(cells[0], 7);
         let score = bot.virtual_connections_score(&cells, &game);
 
         println!("Número de vecinos virtuales: {}", neighbors.len());
@@ -1101,17 +1317,28 @@ mod tests {
     }
 
     // ============================================================
-    // GRUPO 5: VECINOS VIRTUALES (get_virtual_neighbors)
+    // GRUPO 5: VECINOS VIRTUALES (puentes de Topology)
     // ============================================================
 
+    /// Vecinos virtuales (puentes) de una coordenada según la topología compartida
+    fn get_virtual_neighbors(coords: Coordinates, board_size: u32) -> Vec<Coordinates> {
+        if !coords.is_valid_for(board_size) {
+            return vec![];
+        }
+        Topology::for_size(board_size)
+            .bridges(coords.to_index(board_size))
+            .iter()
+            .map(|&idx| Coordinates::from_index(idx, board_size))
+            .collect()
+    }
+
     #[test]
     fn test_get_virtual_neighbors_corner() {
         // TEST : Esquina del tablero
-        let bot = AlfaBetaBot::new(None);
         let board_size = 5;
         let coords = Coordinates::new(4, 0, 0); // Esquina superior
 
-        let neighbors = bot.get_virtual_neighbors(coords, board_size);
+        let neighbors = get_virtual_neighbors(coords, board_size);
 
         println!("Vecinos virtuales de (4,0,0) en tamaño {}: {:?}", board_size, neighbors);
 
@@ -1138,11 +1365,10 @@ mod tests {
     #[test]
     fn test_get_virtual_neighbors_edge() {
         // TEST : Borde del tablero (no esquina)
-        let bot = AlfaBetaBot::new(None);
         let board_size = 6;
         let coords = Coordinates::new(2, 3, 0); // En un borde (z=0)
 
-        let neighbors = bot.get_virtual_neighbors(coords, board_size);
+        let neighbors = get_virtual_neighbors(coords, board_size);
 
         println!("Vecinos virtuales de (2,3,0) en tamaño {}: {:?}", board_size, neighbors);
 
@@ -1158,11 +1384,10 @@ mod tests {
     #[test]
     fn test_get_virtual_neighbors_center() {
         // TEST : Centro del tablero
-        let bot = AlfaBetaBot::new(None);
         let board_size = 7;
         let coords = Coordinates::new(2, 2, 2); // Centro aproximado
 
-        let neighbors = bot.get_virtual_neighbors(coords, board_size);
+        let neighbors = get_virtual_neighbors(coords, board_size);
 
         println!("Vecinos virtuales de (2,2,2) en tamaño {}: {:?}", board_size, neighbors);
 
@@ -1176,7 +1401,7 @@ mod tests {
 
         // El centro debería tener más vecinos virtuales que la esquina
         let corner = Coordinates::new(6, 0, 0);
-        let corner_neighbors = bot.get_virtual_neighbors(corner, board_size);
+        let corner_neighbors = get_virtual_neighbors(corner, board_size);
 
         println!("Vecinos virtuales del centro: {}", neighbors.len());
         println!("Vecinos virtuales de esquina: {}", corner_neighbors.len());
@@ -1187,11 +1412,10 @@ mod tests {
     #[test]
     fn test_get_virtual_neighbors_all_directions() {
         // TEST : Verificar que se generan vecinos en todas las direcciones
-        let bot = AlfaBetaBot::new(None);
         let board_size = 8;
         let coords = Coordinates::new(3, 3, 1); // Celda interior
 
-        let neighbors = bot.get_virtual_neighbors(coords, board_size);
+        let neighbors = get_virtual_neighbors(coords, board_size);
 
         println!("Todos los vecinos virtuales de (3,3,1):");
         for (i, &n) in neighbors.iter().enumerate() {
@@ -1213,7 +1437,6 @@ mod tests {
     #[test]
     fn test_get_virtual_neighbors_boundaries() {
         // TEST : Verificar que no se generan vecinos fuera del tablero
-        let bot = AlfaBetaBot::new(None);
         let board_size = 4;
 
         // Probar con coordenadas cerca de los límites
@@ -1225,7 +1448,7 @@ mod tests {
         ];
 
         for &coords in &test_coords {
-            let neighbors = bot.get_virtual_neighbors(coords, board_size);
+            let neighbors = get_virtual_neighbors(coords, board_size);
 
             for &n in &neighbors {
                 // Verificar que está dentro del tablero
@@ -1249,13 +1472,12 @@ mod tests {
     #[test]
     fn test_get_virtual_neighbors_consistency() {
         // TEST : Misma coordenada debe dar mismos vecinos siempre
-        let bot = AlfaBetaBot::new(None);
         let board_size = 6;
         let coords = Coordinates::new(2, 2, 1);
 
-        let neighbors1 = bot.get_virtual_neighbors(coords, board_size);
-        let neighbors2 = bot.get_virtual_neighbors(coords, board_size);
-        let neighbors3 = bot.get_virtual_neighbors(coords, board_size);
+        let neighbors1 = get_virtual_neighbors(coords, board_size);
+        let neighbors2 = get_virtual_neighbors(coords, board_size);
+        let neighbors3 = get_virtual_neighbors(coords, board_size);
 
         assert_eq!(
             neighbors1.len(), neighbors2.len(),
@@ -1280,14 +1502,13 @@ mod tests {
     #[test]
     fn test_get_virtual_neighbors_size_effect() {
         // TEST : A mayor tamaño del tablero, más vecinos virtuales posibles
-        let bot = AlfaBetaBot::new(None);
         let coords = Coordinates::new(3, 3, 3); // Centro para varios tamaños
 
         let sizes = vec![5, 6, 7, 8, 9];
         let mut prev_count = 0;
 
         for &size in &sizes {
-            let neighbors = bot.get_virtual_neighbors(coords, size);
+            let neighbors = get_virtual_neighbors(coords, size);
             let count = neighbors.len();
 
             println!("Tamaño {}: {} vecinos virtuales", size, count);
@@ -1449,7 +1670,7 @@ mod tests {
         let center = Coordinates::new(2, 2, 2);
 
         // Celdas a diferentes distancias del centro
-        let cells = vec![
+        let cells = [
             center,                                           // Distancia 0
             Coordinates::new(3, 2, 1),                        // Distancia 1
             Coordinates::new(4, 2, 0),                        // Distancia 2
@@ -1837,7 +2058,7 @@ mod tests {
             (2, 0, 0, 0), // Solo una pieza
         ]);
 
-        let threat = bot.detect_winning_threat(&game, HUMAN_PLAYER_ID);
+        let threat = bot.detect_winning_threat(&game, PlayerId::new(HUMAN_PLAYER_ID));
 
         assert!(
             threat.is_none(),
@@ -1859,7 +2080,7 @@ mod tests {
             // La celda (1,0,1) le daría la victoria (conecta con lado C)
         ]);
 
-        let threat = bot.detect_winning_threat(&game, HUMAN_PLAYER_ID);
+        let threat = bot.detect_winning_threat(&game, PlayerId::new(HUMAN_PLAYER_ID));
 
         println!("Amenaza detectada: {:?}", threat);
 
@@ -1896,7 +2117,7 @@ mod tests {
             // Puede ganar en (0,2,1) o (0,1,2) o (1,1,1)
         ]);
 
-        let threat = bot.detect_winning_threat(&game, HUMAN_PLAYER_ID);
+        let threat = bot.detect_winning_threat(&game, PlayerId::new(HUMAN_PLAYER_ID));
 
         println!("Amenaza múltiple detectada: {:?}", threat);
 
@@ -1917,7 +2138,7 @@ mod tests {
             (0, 2, 0, 0),
         ]);
 
-        let threat = bot.detect_winning_threat(&game, HUMAN_PLAYER_ID);
+        let threat = bot.detect_winning_threat(&game, PlayerId::new(HUMAN_PLAYER_ID));
 
         // Según la ejecución, la amenaza real es (1,0,1)
         assert_eq!(
@@ -1945,12 +2166,12 @@ mod tests {
         ]);
 
         assert!(
-            bot.check_winner(&game, HUMAN_PLAYER_ID),
+            bot.check_winner(&game, PlayerId::new(HUMAN_PLAYER_ID)),
             "El humano debería haber ganado"
         );
 
         assert!(
-            !bot.check_winner(&game, BOT_PLAYER_ID),
+            !bot.check_winner(&game, PlayerId::new(BOT_PLAYER_ID)),
             "El bot no debería haber ganado"
         );
     }
@@ -1967,12 +2188,12 @@ mod tests {
         ]);
 
         assert!(
-            !bot.check_winner(&game, BOT_PLAYER_ID),
+            !bot.check_winner(&game, PlayerId::new(BOT_PLAYER_ID)),
             "El bot no debería haber ganado"
         );
 
         assert!(
-            !bot.check_winner(&game, HUMAN_PLAYER_ID),
+            !bot.check_winner(&game, PlayerId::new(HUMAN_PLAYER_ID)),
             "El humano no debería haber ganado"
         );
     }
@@ -1988,7 +2209,7 @@ mod tests {
         let game = GameY::new(5);
 
         // Probar varias celdas vacías
-        let test_coords = vec![
+        let test_coords = [
             Coordinates::new(4, 0, 0), // Esquina
             Coordinates::new(2, 2, 0), // Borde
             Coordinates::new(1, 1, 2), // Centro
@@ -2011,7 +2232,7 @@ mod tests {
         let mut game = GameY::new(5);
 
         // Ocupar varias celdas
-        let occupied_coords = vec![
+        let occupied_coords = [
             Coordinates::new(4, 0, 0),
             Coordinates::new(2, 2, 0),
             Coordinates::new(1, 1, 2),
@@ -2113,7 +2334,7 @@ mod tests {
         let game = GameY::new(5);
         let moves: Vec<u32> = vec![];
 
-        let ordered = bot.order_moves(&game, &moves, true);
+        let ordered = bot.order_moves(&game, &moves, PlayerId::new(BOT_PLAYER_ID));
 
         assert!(
             ordered.is_empty(),
@@ -2126,10 +2347,10 @@ mod tests {
         // TEST : El ordenamiento no debe perder movimientos
         let bot = AlfaBetaBot::new(None);
         let game = GameY::new(3);
-        let moves: Vec<u32> = game.available_cells().iter().copied().collect();
+        let moves: Vec<u32> = game.available_cells().to_vec();
         let original_len = moves.len();
 
-        let ordered = bot.order_moves(&game, &moves, true);
+        let ordered = bot.order_moves(&game, &moves, PlayerId::new(BOT_PLAYER_ID));
 
         assert_eq!(
             ordered.len(), original_len,
@@ -2159,10 +2380,10 @@ mod tests {
             // Si juega en (0,0,2) gana
         ]);
 
-        let moves: Vec<u32> = game.available_cells().iter().copied().collect();
+        let moves: Vec<u32> = game.available_cells().to_vec();
         let winning_idx = Coordinates::new(0, 0, 2).to_index(3);
 
-        let ordered = bot.order_moves(&game, &moves, true);
+        let ordered = bot.order_moves(&game, &moves, PlayerId::new(BOT_PLAYER_ID));
 
         println!("Primeros 3 movimientos ordenados: {:?}", &ordered[..3]);
         println!("Índice ganador esperado: {}", winning_idx);
@@ -2188,13 +2409,13 @@ mod tests {
             (1, 0, 1, 1), // Una pieza del bot
         ]);
 
-        let moves: Vec<u32> = game.available_cells().iter().copied().collect();
+        let moves: Vec<u32> = game.available_cells().to_vec();
         let blocking_idx = Coordinates::new(0, 0, 2).to_index(3);
 
         println!("Movimientos disponibles: {:?}", moves);
         println!("Índice bloqueador esperado: {}", blocking_idx);
 
-        let ordered = bot.order_moves(&game, &moves, true);
+        let ordered = bot.order_moves(&game, &moves, PlayerId::new(BOT_PLAYER_ID));
 
         println!("Orden completo: {:?}", ordered);
 
@@ -2229,7 +2450,7 @@ mod tests {
         let bot = AlfaBetaBot::new(None);
         let game = GameY::new(4);
 
-        let score = bot.evaluate_board(&game, BOT_PLAYER_ID);
+        let score = bot.evaluate_board(&game, PlayerId::new(BOT_PLAYER_ID));
 
         println!("Score tablero vacío: {}", score);
 
@@ -2251,7 +2472,7 @@ mod tests {
             (0, 0, 2, 0),
         ]);
 
-        let score = bot.evaluate_board(&game, BOT_PLAYER_ID);
+        let score = bot.evaluate_board(&game, PlayerId::new(BOT_PLAYER_ID));
 
         assert_eq!(score, -WIN_NOW);
     }
@@ -2269,8 +2490,8 @@ mod tests {
             (0, 2, 1, 0), // Humano
         ]);
 
-        let score_bot = bot.evaluate_board(&game, BOT_PLAYER_ID);
-        let score_human = bot.evaluate_board(&game, HUMAN_PLAYER_ID);
+        let score_bot = bot.evaluate_board(&game, PlayerId::new(BOT_PLAYER_ID));
+        let score_human = bot.evaluate_board(&game, PlayerId::new(HUMAN_PLAYER_ID));
 
         println!("Score bot: {}", score_bot);
         println!("Score humano: {}", score_human);
@@ -2288,9 +2509,9 @@ mod tests {
     fn test_alphabeta_depth_zero() {
         // TEST : Profundidad 0 debe llamar a evaluate_board
         let mut bot = AlfaBetaBot::new(None);
-        let game = GameY::new(3);
+        let mut game = GameY::new(3);
 
-        let score = bot.alphabeta(&game, 0, i32::MIN, i32::MAX, true);
+        let score = bot.alphabeta(&mut game, 0, i32::MIN, i32::MAX, PlayerId::new(BOT_PLAYER_ID), true);
 
         println!("Alpha-beta depth 0 score: {}", score);
 
@@ -2298,13 +2519,30 @@ mod tests {
         assert!(score > -100000 && score < 100000);
     }
 
+    #[test]
+    fn test_alphabeta_restores_board() {
+        // TEST : La búsqueda deshace todas sus jugadas sobre el tablero
+        let mut bot = AlfaBetaBot::new(None);
+        let mut game = create_test_game(4, vec![
+            (3, 0, 0, BOT_PLAYER_ID),
+            (0, 3, 0, HUMAN_PLAYER_ID),
+        ]);
+        let before = game.clone();
+
+        let _ = bot.alphabeta(&mut game, 3, i32::MIN, i32::MAX, PlayerId::new(BOT_PLAYER_ID), true);
+
+        assert_eq!(game.zobrist_hash(), before.zobrist_hash());
+        assert_eq!(game.available_cells(), before.available_cells());
+        assert_eq!(game.history(), before.history());
+    }
+
     #[test]
     fn test_alphabeta_human_wins() {
         // TEST : Humano gana (malo para el bot)
         let mut bot = AlfaBetaBot::new(None);
 
         // Configurar victoria del humano (player_id = 0)
-        let game = create_test_game(3, vec![
+        let mut game = create_test_game(3, vec![
             (2, 0, 0, 0),
             (1, 1, 0, 0),
             (0, 2, 0, 0),
@@ -2314,11 +2552,11 @@ mod tests {
 
         // Verificar primero que check_winner funciona
         assert!(
-            bot.check_winner(&game, HUMAN_PLAYER_ID),
+            bot.check_winner(&game, PlayerId::new(HUMAN_PLAYER_ID)),
             "check_winner debería detectar victoria del humano"
         );
 
-        let score = bot.alphabeta(&game, 2, i32::MIN, i32::MAX, true);
+        let score = bot.alphabeta(&mut game, 2, i32::MIN, i32::MAX, PlayerId::new(BOT_PLAYER_ID), true);
 
         assert_eq!(
             score, -WIN_NOW,
@@ -2331,10 +2569,10 @@ mod tests {
     fn test_alphabeta_pruning_effect() {
         // TEST : Verificar que la poda funciona
         let mut bot = AlfaBetaBot::new(Some(3));
-        let game = GameY::new(4);
+        let mut game = GameY::new(4);
 
         let start_nodes = bot.nodes_evaluated;
-        let score = bot.alphabeta(&game, 3, i32::MIN, i32::MAX, true);
+        let score = bot.alphabeta(&mut game, 3, i32::MIN, i32::MAX, PlayerId::new(BOT_PLAYER_ID), true);
         let end_nodes = bot.nodes_evaluated;
         let nodes_evaluated = end_nodes - start_nodes;
 
@@ -2351,14 +2589,14 @@ mod tests {
     fn test_alphabeta_different_depths() {
         // TEST : Diferentes profundidades dan resultados
         let mut bot = AlfaBetaBot::new(None);
-        let game = create_test_game(4, vec![
+        let mut game = create_test_game(4, vec![
             (3, 0, 0, 1),
             (2, 1, 0, 1),
             (0, 3, 0, 0),
         ]);
 
-        let score_depth1 = bot.alphabeta(&game, 1, i32::MIN, i32::MAX, true);
-        let score_depth2 = bot.alphabeta(&game, 2, i32::MIN, i32::MAX, true);
+        let score_depth1 = bot.alphabeta(&mut game, 1, i32::MIN, i32::MAX, PlayerId::new(BOT_PLAYER_ID), true);
+        let score_depth2 = bot.alphabeta(&mut game, 2, i32::MIN, i32::MAX, PlayerId::new(BOT_PLAYER_ID), true);
 
         println!("Score depth 1: {}", score_depth1);
         println!("Score depth 2: {}", score_depth2);
@@ -2367,18 +2605,17 @@ mod tests {
     }
 
     // ============================================================
-    // GRUPO 16: HASH BOARD (hash_board)
+    // GRUPO 16: HASH DEL TABLERO (zobrist_hash)
     // ============================================================
 
     #[test]
     fn test_hash_board_consistency() {
         // TEST : Mismo estado debe producir mismo hash
-        let bot = AlfaBetaBot::new(None);
         let game1 = GameY::new(4);
         let game2 = GameY::new(4);
 
-        let hash1 = bot.hash_board(&game1);
-        let hash2 = bot.hash_board(&game2);
+        let hash1 = game1.zobrist_hash();
+        let hash2 = game2.zobrist_hash();
 
         assert_eq!(
             hash1, hash2,
@@ -2399,8 +2636,8 @@ mod tests {
             coords: Coordinates::new(3, 0, 0),
         }).unwrap();
 
-        let hash3 = bot.hash_board(&game3);
-        let hash4 = bot.hash_board(&game4);
+        let hash3 = game3.zobrist_hash();
+        let hash4 = game4.zobrist_hash();
 
         assert_eq!(
             hash3, hash4,
@@ -2411,7 +2648,6 @@ mod tests {
     #[test]
     fn test_hash_board_different() {
         // TEST : Estados diferentes deben producir hashes diferentes
-        let bot = AlfaBetaBot::new(None);
 
         let mut game1 = GameY::new(4);
         let mut game2 = GameY::new(4);
@@ -2427,8 +2663,8 @@ mod tests {
             coords: Coordinates::new(2, 1, 0),
         }).unwrap();
 
-        let hash1 = bot.hash_board(&game1);
-        let hash2 = bot.hash_board(&game2);
+        let hash1 = game1.zobrist_hash();
+        let hash2 = game2.zobrist_hash();
 
         assert_ne!(
             hash1, hash2,
@@ -2446,7 +2682,7 @@ mod tests {
             coords: Coordinates::new(2, 1, 0),
         }).unwrap();
 
-        let hash3 = bot.hash_board(&game3);
+        let hash3 = game3.zobrist_hash();
 
         assert_ne!(
             hash1, hash3,
@@ -2457,7 +2693,6 @@ mod tests {
     #[test]
     fn test_hash_board_deterministic() {
         // TEST : Múltiples llamadas al mismo tablero dan el mismo hash
-        let bot = AlfaBetaBot::new(None);
         let mut game = GameY::new(5);
 
         // Añadir algunos movimientos
@@ -2477,10 +2712,10 @@ mod tests {
         }).unwrap();
 
         // Calcular hash varias veces
-        let hash_a = bot.hash_board(&game);
-        let hash_b = bot.hash_board(&game);
-        let hash_c = bot.hash_board(&game);
-        let hash_d = bot.hash_board(&game);
+        let hash_a = game.zobrist_hash();
+        let hash_b = game.zobrist_hash();
+        let hash_c = game.zobrist_hash();
+        let hash_d = game.zobrist_hash();
 
         assert_eq!(hash_a, hash_b, "Primera y segunda llamada deben ser iguales");
         assert_eq!(hash_b, hash_c, "Segunda y tercera llamada deben ser iguales");
@@ -2493,13 +2728,12 @@ mod tests {
     #[test]
     fn test_hash_board_collision_resistance() {
         // TEST : Probabilidad de colisión muy baja (test conceptual)
-        let bot = AlfaBetaBot::new(None);
         let mut hashes = std::collections::HashSet::new();
 
         // Generar muchos tableros diferentes y verificar que no hay colisiones
         for i in 0..10 {
             for j in 0..10 {
-                let mut game = GameY::new(5);
+                let mut game = GameY::new(5).with_validation(MoveValidation::Permissive);
 
                 // Crear un tablero único
                 if i % 2 == 0 {
@@ -2521,7 +2755,7 @@ mod tests {
                     }).unwrap_or(());
                 }
 
-                let hash = bot.hash_board(&game);
+                let hash = game.zobrist_hash();
                 hashes.insert(hash);
             }
         }
@@ -2533,6 +2767,20 @@ mod tests {
         assert!(hashes.len() > 5, "Debería haber al menos 5 hashes únicos");
     }
 
+    #[test]
+    fn test_hash_board_distinguishes_owner() {
+        // TEST : Mismas celdas libres pero piedras de distinto color
+        let game1 = create_test_game(4, vec![(3, 0, 0, 0), (0, 3, 0, 1)]);
+        let game2 = create_test_game(4, vec![(3, 0, 0, 1), (0, 3, 0, 0)]);
+
+        assert_eq!(game1.available_cells(), game2.available_cells());
+        assert_ne!(
+            game1.zobrist_hash(),
+            game2.zobrist_hash(),
+            "El hash debe depender del dueño de cada piedra"
+        );
+    }
+
     // ============================================================
     // GRUPO 17: TRANSPOSITION TABLE
     // ============================================================
@@ -2541,11 +2789,11 @@ mod tests {
     fn test_transposition_table_reuse() {
         // TEST : Segunda llamada debe usar la tabla (más rápida)
         let mut bot = AlfaBetaBot::new(Some(3));
-        let game = GameY::new(3);
+        let mut game = GameY::new(3);
 
         // Primera llamada - debe llenar la tabla
         let start_nodes1 = bot.nodes_evaluated;
-        let score1 = bot.alphabeta(&game, 2, i32::MIN, i32::MAX, true);
+        let score1 = bot.alphabeta(&mut game, 2, i32::MIN, i32::MAX, PlayerId::new(BOT_PLAYER_ID), true);
         let end_nodes1 = bot.nodes_evaluated;
         let nodes1 = end_nodes1 - start_nodes1;
 
@@ -2554,7 +2802,7 @@ mod tests {
 
         // Segunda llamada - debería usar la tabla
         let start_nodes2 = bot.nodes_evaluated;
-        let score2 = bot.alphabeta(&game, 2, i32::MIN, i32::MAX, true);
+        let score2 = bot.alphabeta(&mut game, 2, i32::MIN, i32::MAX, PlayerId::new(BOT_PLAYER_ID), true);
         let end_nodes2 = bot.nodes_evaluated;
         let nodes2 = end_nodes2 - start_nodes2;
 
@@ -2580,17 +2828,17 @@ mod tests {
     fn test_transposition_table_different_depths() {
         // TEST : Diferentes profundidades deben tener entradas diferentes
         let mut bot = AlfaBetaBot::new(None);
-        let game = GameY::new(3);
+        let mut game = GameY::new(3);
 
         // Llamada con profundidad 2
-        let _ = bot.alphabeta(&game, 2, i32::MIN, i32::MAX, true);
+        let _ = bot.alphabeta(&mut game, 2, i32::MIN, i32::MAX, PlayerId::new(BOT_PLAYER_ID), true);
         let entries_depth2 = bot.transposition_table.len();
 
         // Resetear el bot para empezar limpio
         let mut bot2 = AlfaBetaBot::new(None);
 
         // Llamada con profundidad 3
-        let _ = bot2.alphabeta(&game, 3, i32::MIN, i32::MAX, true);
+        let _ = bot2.alphabeta(&mut game, 3, i32::MIN, i32::MAX, PlayerId::new(BOT_PLAYER_ID), true);
         let entries_depth3 = bot2.transposition_table.len();
 
         println!("Entradas con profundidad 2: {}", entries_depth2);
@@ -2617,13 +2865,13 @@ mod tests {
         // TEST : Diferentes tableros deben tener diferentes entradas
         let mut bot = AlfaBetaBot::new(Some(2));
 
-        let game1 = GameY::new(3);
-        let game2 = GameY::new(4); // Tamaño diferente
+        let mut game1 = GameY::new(3);
+        let mut game2 = GameY::new(4); // Tamaño diferente
 
-        let _ = bot.alphabeta(&game1, 1, i32::MIN, i32::MAX, true);
+        let _ = bot.alphabeta(&mut game1, 1, i32::MIN, i32::MAX, PlayerId::new(BOT_PLAYER_ID), true);
         let entries_before = bot.transposition_table.len();
 
-        let _ = bot.alphabeta(&game2, 1, i32::MIN, i32::MAX, true);
+        let _ = bot.alphabeta(&mut game2, 1, i32::MIN, i32::MAX, PlayerId::new(BOT_PLAYER_ID), true);
         let entries_after = bot.transposition_table.len();
 
         println!("Entradas después de game1: {}", entries_before);
@@ -2649,11 +2897,11 @@ mod tests {
         }).unwrap();
 
         // Evaluar con turno del bot
-        let score_bot_turn = bot.alphabeta(&game, 2, i32::MIN, i32::MAX, true);
+        let score_bot_turn = bot.alphabeta(&mut game, 2, i32::MIN, i32::MAX, PlayerId::new(BOT_PLAYER_ID), true);
         let entries_before = bot.transposition_table.len();
 
         // Evaluar con turno del humano (mismo tablero)
-        let score_human_turn = bot.alphabeta(&game, 2, i32::MIN, i32::MAX, false);
+        let score_human_turn = bot.alphabeta(&mut game, 2, i32::MIN, i32::MAX, PlayerId::new(BOT_PLAYER_ID), false);
         let entries_after = bot.transposition_table.len();
 
         println!("Score turno bot: {}", score_bot_turn);
@@ -2671,10 +2919,10 @@ mod tests {
     fn test_transposition_table_node_count() {
         // TEST : Verificar que nodes_evaluated se incrementa correctamente
         let mut bot = AlfaBetaBot::new(Some(3));
-        let game = GameY::new(3);
+        let mut game = GameY::new(3);
 
         let start_nodes = bot.nodes_evaluated;
-        let _ = bot.alphabeta(&game, 2, i32::MIN, i32::MAX, true);
+        let _ = bot.alphabeta(&mut game, 2, i32::MIN, i32::MAX, PlayerId::new(BOT_PLAYER_ID), true);
         let end_nodes = bot.nodes_evaluated;
 
         println!("Nodos evaluados: {}", end_nodes - start_nodes);
@@ -2685,6 +2933,95 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_transposition_table_persists_across_choose_move() {
+        // TEST : choose_move toma &self, pero la tabla no se pierde entre llamadas
+        let bot = AlfaBetaBot::new(Some(2));
+        let game = GameY::new(4);
+
+        assert!(bot.transposition_table.is_empty());
+        let first = bot.choose_move(&game).unwrap();
+        let entries = bot.transposition_table.len();
+        assert!(entries > 0, "La tabla debería conservar las entradas de la búsqueda");
+
+        let second = bot.choose_move(&game).unwrap();
+        assert_eq!(first, second);
+        assert!(bot.transposition_table.len() >= entries);
+    }
+
+    #[test]
+    fn test_transposition_table_stores_root_best_move() {
+        // TEST : La mejor jugada de la raíz queda guardada para ordenar la siguiente búsqueda
+        let bot = AlfaBetaBot::new(Some(2));
+        let game = GameY::new(4);
+
+        let chosen = bot.choose_move(&game).unwrap();
+        let entry = bot
+            .transposition_table
+            .probe(game.zobrist_hash(), 4, game.next_player().unwrap())
+            .expect("La raíz debería estar en la tabla");
+
+        assert_eq!(entry.best_move, Some(chosen.to_index(4)));
+    }
+
+    #[test]
+    fn test_transposition_table_best_move_reduces_search() {
+        // TEST : Con la tabla ya llena, repetir la búsqueda cuesta menos nodos
+        let table = Arc::new(TranspositionTable::new(TRANSPOSITION_TABLE_SIZE));
+        let game = GameY::new(5);
+
+        let mut first = AlfaBetaBot::new(Some(3)).with_transposition_table(Arc::clone(&table));
+        let move1 = first.choose_move_internal(&game);
+        let mut second = AlfaBetaBot::new(Some(3)).with_transposition_table(Arc::clone(&table));
+        let move2 = second.choose_move_internal(&game);
+
+        assert_eq!(move1, move2);
+        assert!(
+            second.nodes_evaluated < first.nodes_evaluated,
+            "La segunda búsqueda debería evaluar menos nodos ({} vs {})",
+            second.nodes_evaluated, first.nodes_evaluated
+        );
+    }
+
+    #[test]
+    fn test_transposition_table_is_bounded() {
+        // TEST : La tabla nunca pasa de su capacidad
+        let table = Arc::new(TranspositionTable::new(64));
+        let mut bot = AlfaBetaBot::new(Some(3)).with_transposition_table(Arc::clone(&table));
+        let _ = bot.choose_move_internal(&GameY::new(5));
+
+        assert!(!table.is_empty());
+        assert!(table.len() <= 64);
+    }
+
+    #[test]
+    fn test_transposition_table_shared_between_bots() {
+        // TEST : Dos bots con la misma tabla ven las entradas del otro
+        let table = Arc::new(TranspositionTable::new(1024));
+        let bot1 = AlfaBetaBot::new(Some(2))
+            .with_transposition_table(Arc::clone(&table))
+            .with_endgame_solver(None);
+        let bot2 = AlfaBetaBot::new(Some(2)).with_transposition_table(Arc::clone(&table));
+
+        let _ = bot1.choose_move(&GameY::new(3));
+        assert!(!bot2.transposition_table.is_empty());
+    }
+
+    #[test]
+    fn test_reseeded_copy_has_its_own_table() {
+        // TEST : La copia juega igual pero no escribe en la tabla del original
+        let bot = AlfaBetaBot::new(Some(2)).with_endgame_solver(None);
+        let game = GameY::new(4);
+        let expected = bot.choose_move(&game);
+        let entries = bot.transposition_table.len();
+
+        let copy = bot.reseeded(7).unwrap();
+        assert_eq!(copy.name(), "alfa_beta_bot");
+        assert_eq!(copy.choose_move(&game), expected);
+        let _ = copy.choose_move(&GameY::new(5));
+        assert_eq!(bot.transposition_table.len(), entries);
+    }
+
     // ============================================================
     // GRUPO 18: CHOOSE MOVE (choose_move y choose_move_internal)
     // ============================================================
@@ -2754,12 +3091,13 @@ mod tests {
         let mut bot = AlfaBetaBot::new(Some(3));
 
         // Configurar partida donde el bot (player 1) puede ganar en (0,0,2)
+        // (la partida no puede estar terminada: le toca mover al bot)
         let game = create_test_game(3, vec![
-            (2, 0, 0, 1), // Lado A (bot)
-            (1, 1, 0, 1), // Centro (bot)
-            (0, 2, 0, 1), // Lado B (bot)
+            (2, 0, 0, 1), // Lados B y C (bot)
+            (1, 1, 0, 0), // Pieza del humano (para que no sea tan trivial)
+            (1, 0, 1, 1), // Lado B (bot)
+            (0, 1, 1, 0), // El humano tapa la otra salida al lado A
             // Si juega en (0,0,2) gana
-            (1, 0, 1, 0), // Pieza del humano (para que no sea tan trivial)
         ]);
 
         let winning_move = Coordinates::new(0, 0, 2);
@@ -2789,12 +3127,12 @@ mod tests {
         let mut bot = AlfaBetaBot::new(Some(3));
 
         // Configurar partida donde el humano (player 0) puede ganar en (0,0,2)
+        // (la partida no puede estar terminada: le toca mover al bot)
         let game = create_test_game(3, vec![
-            (2, 0, 0, 0), // Lado A (humano)
-            (1, 1, 0, 0), // Centro (humano)
-            (0, 2, 0, 0), // Lado B (humano)
+            (2, 0, 0, 0), // Lados B y C (humano)
+            (0, 1, 1, 1), // Pieza del bot (para que tenga que decidir)
+            (1, 0, 1, 0), // Lado B (humano)
             // Si el humano juega en (0,0,2) gana
-            (1, 0, 1, 1), // Pieza del bot (para que tenga que decidir)
         ]);
 
         let blocking_move = Coordinates::new(0, 0, 2);
@@ -2818,6 +3156,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_choose_move_plays_either_colour() {
+        // TEST : El bot juega con el color al que le toca mover
+        for bot_id in [HUMAN_PLAYER_ID, BOT_PLAYER_ID] {
+            let rival_id = 1 - bot_id;
+
+            // El bot gana en (0,0,2); el rival tapa (0,1,1)
+            let game = create_test_game(3, vec![
+                (2, 0, 0, bot_id),
+                (1, 1, 0, rival_id),
+                (1, 0, 1, bot_id),
+                (0, 1, 1, rival_id),
+            ]);
+            let chosen = AlfaBetaBot::new(Some(3)).choose_move(&game);
+            assert_eq!(chosen, Some(Coordinates::new(0, 0, 2)), "ganar con {}", bot_id);
+
+            // El rival gana en (0,0,2) si el bot no bloquea
+            let game = create_test_game(3, vec![
+                (2, 0, 0, rival_id),
+                (0, 1, 1, bot_id),
+                (1, 0, 1, rival_id),
+            ]);
+            let chosen = AlfaBetaBot::new(Some(3)).choose_move(&game);
+            assert_eq!(chosen, Some(Coordinates::new(0, 0, 2)), "bloquear con {}", bot_id);
+        }
+    }
+
     #[test]
     fn test_choose_move_strategic_vs_random() {
         // TEST : En posición equilibrada, debe elegir un movimiento estratégico
@@ -3012,7 +3377,7 @@ mod tests {
             (3, 0, 0, 1), // Lado A
             (2, 1, 0, 1), // Centro
             (1, 2, 0, 1), // Centro
-            (0, 3, 0, 1), // Lado B - bot conecta A y B
+            // Le falta el lado A para ganar
 
             // Humano (player 0) tiene otros 2 lados
             (0, 0, 3, 0), // Lado C
@@ -3083,15 +3448,14 @@ mod tests {
 
         // Bot va perdiendo (humano tiene ventaja)
         let game = create_test_game(4, vec![
-            // Humano (player 0) tiene 2 lados
-            (3, 0, 0, 0), // Lado A
-            (2, 1, 0, 0), // Centro
-            (1, 2, 0, 0), // Centro
-            (0, 3, 0, 0), // Lado B
-
             // Bot (player 1) solo tiene 1 lado
             (0, 0, 3, 1), // Lado C
             (1, 0, 2, 1), // Centro
+
+            // Humano (player 0) tiene 2 lados y mueve el último
+            (3, 0, 0, 0), // Lado A
+            (2, 1, 0, 0), // Centro
+            (1, 2, 0, 0), // Centro
         ]);
 
         let chosen = bot.choose_move_internal(&game).unwrap();
@@ -3110,6 +3474,7 @@ mod tests {
     // ============================================================
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_constants_logic() {
         // TEST : Verificar la lógica de las constantes (WIN_NOW > BLOCK > ...)
 
@@ -3171,6 +3536,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_depth_constants() {
         // TEST : Verificar constantes de profundidad
         assert!(
@@ -3196,6 +3562,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_transposition_table_size() {
         // TEST : Verificar tamaño de la tabla de transposición
         assert!(
@@ -3211,6 +3578,342 @@ mod tests {
         );
     }
 
+    // ============================================================
+    // GRUPO 21: PROFUNDIZACIÓN ITERATIVA CON LÍMITE DE TIEMPO
+    // ============================================================
+
+    #[test]
+    fn test_with_time_limit() {
+        // TEST : El límite de tiempo es configurable y tiene un valor por defecto
+        let bot = AlfaBetaBot::new(None);
+        assert_eq!(bot.time_limit_ms, DEFAULT_TIME_LIMIT_MS);
+
+        let bot = AlfaBetaBot::new(Some(6)).with_time_limit(150);
+        assert_eq!(bot.time_limit_ms, 150);
+        assert_eq!(bot.max_depth, 6);
+    }
+
+    #[test]
+    fn test_time_limit_on_large_board() {
+        // TEST : En un tablero grande la búsqueda se corta a tiempo y juega
+        // la mejor jugada de la última profundidad completada
+        let mut bot = AlfaBetaBot::new(Some(20)).with_time_limit(200);
+        let game = create_test_game(11, vec![
+            (10, 0, 0, BOT_PLAYER_ID),
+            (5, 3, 2, HUMAN_PLAYER_ID),
+        ]);
+
+        let start = std::time::Instant::now();
+        let chosen = bot.choose_move_internal(&game).unwrap();
+        let elapsed = start.elapsed().as_millis();
+
+        assert!(game.available_cells().contains(&chosen.to_index(11)));
+        assert!(elapsed < 2000, "la búsqueda tardó {} ms", elapsed);
+        assert!(bot.completed_depth < 20);
+    }
+
+    #[test]
+    fn test_deepening_reaches_max_depth_on_small_board() {
+        // TEST : Con tiempo de sobra se completan todas las profundidades
+        let mut bot = AlfaBetaBot::new(Some(3)).with_time_limit(60_000);
+        let game = create_test_game(4, vec![
+            (3, 0, 0, BOT_PLAYER_ID),
+            (1, 1, 1, HUMAN_PLAYER_ID),
+        ]);
+
+        bot.choose_move_internal(&game).unwrap();
+        assert_eq!(bot.completed_depth, 3);
+        assert!(!bot.aborted);
+    }
+
+    #[test]
+    fn test_deepening_stops_at_forced_win() {
+        // TEST : Una victoria inmediata se ve a profundidad 1 y no se sigue buscando
+        let mut bot = AlfaBetaBot::new(Some(6)).with_time_limit(60_000).with_endgame_solver(None);
+        let game = create_test_game(3, vec![
+            (2, 0, 0, BOT_PLAYER_ID),
+            (1, 1, 0, HUMAN_PLAYER_ID),
+            (1, 0, 1, BOT_PLAYER_ID),
+            (0, 1, 1, HUMAN_PLAYER_ID),
+        ]);
+
+        let chosen = bot.choose_move_internal(&game).unwrap();
+        assert_eq!(chosen, Coordinates::new(0, 0, 2));
+        assert_eq!(bot.completed_depth, 1);
+    }
+
+    #[test]
+    fn test_zero_time_limit_still_returns_a_move() {
+        // TEST : Sin tiempo la búsqueda se corta en cuanto mira el reloj,
+        // pero sigue devolviendo una jugada
+        let mut bot = AlfaBetaBot::new(Some(20)).with_time_limit(0);
+        let game = GameY::new(15);
+
+        let chosen = bot.choose_move_internal(&game);
+        assert!(chosen.is_some());
+        assert!(bot.aborted);
+        assert!(bot.completed_depth < 20);
+    }
+
+    // ============================================================
+    // GRUPO 22: BÚSQUEDA CON LÍMITES Y ANÁLISIS
+    // ============================================================
+
+    #[test]
+    fn test_search_reports_analysis() {
+        // TEST : search devuelve la jugada junto con puntuación, PV, nodos y profundidad
+        let bot = AlfaBetaBot::new(Some(3));
+        let game = GameY::new(4);
+
+        let result = bot.search(&game, &SearchLimits::default());
+
+        assert_eq!(result.depth, Some(3));
+        assert!(result.score.is_some());
+        assert!(result.nodes > 0);
+        assert_eq!(result.principal_variation.first(), result.best_move.as_ref());
+        assert!(result.principal_variation.len() <= 3);
+    }
+
+    #[test]
+    fn test_search_pv_is_playable() {
+        // TEST : La variante principal es una secuencia de jugadas legales
+        let bot = AlfaBetaBot::new(Some(3));
+        let mut game = GameY::new(5);
+
+        let result = bot.search(&game, &SearchLimits::default());
+        assert!(!result.principal_variation.is_empty());
+        for coords in result.principal_variation {
+            let player = game.next_player().unwrap();
+            game.add_move(Movement::Placement { player, coords }).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_search_respects_depth_limit() {
+        // TEST : El límite de profundidad de la petición manda sobre el del bot
+        let bot = AlfaBetaBot::new(Some(4));
+        let game = GameY::new(4);
+
+        let result = bot.search(&game, &SearchLimits::new().with_max_depth(1));
+        assert_eq!(result.depth, Some(1));
+        assert_eq!(result.principal_variation.len(), 1);
+    }
+
+    #[test]
+    fn test_search_respects_node_limit() {
+        // TEST : Con un límite de nodos la búsqueda se corta, pero hay jugada
+        let bot = AlfaBetaBot::new(Some(20));
+        let game = GameY::new(9);
+
+        let result = bot.search(&game, &SearchLimits::new().with_max_nodes(500));
+        assert!(result.best_move.is_some());
+        assert!(result.nodes <= 501);
+        assert!(result.depth.unwrap() < 20);
+    }
+
+    #[test]
+    fn test_search_reports_forced_win() {
+        // TEST : Una victoria vista por la búsqueda se refleja en la probabilidad
+        let bot = AlfaBetaBot::new(Some(2));
+        let game = create_test_game(3, vec![
+            (2, 0, 0, BOT_PLAYER_ID),
+            (1, 1, 0, HUMAN_PLAYER_ID),
+            (1, 0, 1, BOT_PLAYER_ID),
+            (0, 1, 1, HUMAN_PLAYER_ID),
+        ]);
+
+        let result = bot.search(&game, &SearchLimits::default());
+        assert_eq!(result.best_move, Some(Coordinates::new(0, 0, 2)));
+        assert_eq!(result.win_probability, Some(1.0));
+        assert!(result.score.unwrap() >= WIN_NOW);
+    }
+
+    #[test]
+    fn test_search_on_finished_game() {
+        // TEST : Sin turno no hay jugada, pero search no falla
+        let bot = AlfaBetaBot::new(Some(2));
+        let game = create_test_game(2, vec![
+            (1, 0, 0, HUMAN_PLAYER_ID),
+            (0, 0, 1, BOT_PLAYER_ID),
+            (0, 1, 0, HUMAN_PLAYER_ID),
+        ]);
+        assert!(game.check_game_over());
+
+        let result = bot.search(&game, &SearchLimits::default());
+        assert!(result.best_move.is_none());
+        assert!(result.principal_variation.is_empty());
+        assert_eq!(result.nodes, 0);
+    }
+
+    // ============================================================
+    // GRUPO 23: CAMBIO Y RENDICIÓN
+    // ============================================================
+
+    // Posición perdida para el bot: el rival amenaza ganar en (0,0,2) y en (0,1,1)
+    fn lost_position() -> GameY {
+        create_test_game(3, vec![
+            (2, 0, 0, HUMAN_PLAYER_ID),
+            (1, 1, 0, BOT_PLAYER_ID),
+            (1, 0, 1, HUMAN_PLAYER_ID),
+        ])
+    }
+
+    #[test]
+    fn test_decide_resigns_forced_loss() {
+        // TEST : Ante una derrota segura el bot se rinde
+        let bot = AlfaBetaBot::new(Some(2));
+        let decision = bot.decide(&lost_position(), &SearchLimits::default()).unwrap();
+
+        assert_eq!(decision.action(), Some(&GameAction::Resign));
+        assert_eq!(decision.analysis.win_probability, Some(0.0));
+    }
+
+    #[test]
+    fn test_decide_with_zero_threshold_never_resigns() {
+        // TEST : Con umbral 0 sigue jugando aunque esté perdido
+        let bot = AlfaBetaBot::new(Some(2)).with_resign_threshold(0.0);
+        let decision = bot.decide(&lost_position(), &SearchLimits::default()).unwrap();
+
+        assert!(matches!(decision.movement, Movement::Placement { .. }));
+    }
+
+    #[test]
+    fn test_decide_places_in_open_position() {
+        // TEST : En una posición abierta coloca la misma jugada que choose_move
+        let bot = AlfaBetaBot::new(Some(2));
+        let game = GameY::new(4);
+        let decision = bot.decide(&game, &SearchLimits::default()).unwrap();
+
+        assert_eq!(
+            decision.movement,
+            Movement::Placement { player: PlayerId::new(0), coords: decision.analysis.best_move.unwrap() }
+        );
+    }
+
+    #[test]
+    fn test_decide_after_opening_is_playable() {
+        // TEST : Tras la apertura puede cambiar o colocar, y la decisión es legal
+        let bot = AlfaBetaBot::new(Some(2)).with_time_limit(500);
+        let mut game = GameY::new(5);
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::new(2, 1, 1),
+        }).unwrap();
+
+        let decision = bot.decide(&game, &SearchLimits::default()).unwrap();
+        assert_ne!(decision.action(), Some(&GameAction::Resign));
+        game.add_move(decision.movement).unwrap();
+    }
+
+    // ============================================================
+    // GRUPO 24: SOLVER DE FINAL DE PARTIDA
+    // ============================================================
+
+    // B (humano) a mover gana en dos: juega (1,1,0) o (0,1,1) y amenaza dos casillas
+    fn mate_in_two() -> GameY {
+        GameY::try_from(crate::YEN::new(3, 0, vec!['B', 'R'], "B/../R..".to_string())).unwrap()
+    }
+
+    #[test]
+    fn test_endgame_solver_finds_win_beyond_depth() {
+        // TEST : A profundidad 1 no se ve la victoria, pero el solver la demuestra
+        let bot = AlfaBetaBot::new(Some(1));
+        let result = bot.search(&mate_in_two(), &SearchLimits::default());
 
+        let best = result.best_move.unwrap();
+        assert!(best == Coordinates::new(1, 1, 0) || best == Coordinates::new(0, 1, 1), "best: {:?}", best);
+        assert_eq!(result.win_probability, Some(1.0));
+        assert_eq!(result.score, Some(WIN_NOW));
+        assert_eq!(result.principal_variation.len(), 3);
+        assert!(result.nodes > 0);
+    }
+
+    #[test]
+    fn test_endgame_solver_can_be_disabled() {
+        // TEST : Sin solver, la búsqueda a profundidad 1 no sabe que gana
+        let bot = AlfaBetaBot::new(Some(1)).with_endgame_solver(None);
+        let result = bot.search(&mate_in_two(), &SearchLimits::default());
+
+        assert_eq!(result.depth, Some(1));
+        assert_eq!(result.win_probability, None);
+    }
+
+    #[test]
+    fn test_endgame_solver_reports_proven_loss() {
+        // TEST : Perdida según el solver: juega una jugada, pero con probabilidad 0
+        let bot = AlfaBetaBot::new(Some(1)).with_resign_threshold(0.0);
+        let game = create_test_game(4, vec![
+            (3, 0, 0, HUMAN_PLAYER_ID),
+            (2, 1, 0, HUMAN_PLAYER_ID),
+            (1, 2, 0, HUMAN_PLAYER_ID),
+            (2, 0, 1, HUMAN_PLAYER_ID),
+            (1, 1, 1, HUMAN_PLAYER_ID),
+        ]);
+        assert_eq!(crate::solve(&game, &SearchLimits::default()).outcome, crate::SolveOutcome::Loss);
+
+        let result = bot.search(&game, &SearchLimits::default());
+        assert!(result.best_move.is_some());
+        assert_eq!(result.win_probability, Some(0.0));
+        assert_eq!(result.depth, Some(1));
+    }
 
+    #[test]
+    fn test_endgame_solver_skips_open_positions() {
+        // TEST : Con más casillas libres que el umbral se busca como siempre
+        let bot = AlfaBetaBot::new(Some(2)).with_endgame_solver(Some(3));
+        let result = bot.search(&mate_in_two(), &SearchLimits::default());
+        assert_eq!(result.depth, Some(2));
+    }
+
+    // ============================================================
+    // GRUPO 25: JUEGO ÓPTIMO EN TABLEROS PEQUEÑOS
+    // ============================================================
+
+    // Posiciones aleatorias de tamaño 4 en las que gana quien mueve, según la tabla resuelta
+    fn winning_positions(table: &crate::SolvedTable, count: usize) -> Vec<GameY> {
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand::rngs::StdRng::seed_from_u64(25);
+        let mut positions = Vec::new();
+        while positions.len() < count {
+            let mut game = GameY::new(4).with_swap_rule(crate::SwapRule::Disabled);
+            for _ in 0..rng.random_range(1..6) {
+                let cells = game.available_cells().clone();
+                let cell = cells[rng.random_range(0..cells.len())];
+                let player = game.next_player().unwrap();
+                game.make_move(player, Coordinates::from_index(cell, 4)).unwrap();
+                if game.check_game_over() {
+                    break;
+                }
+            }
+            if game.perfect_play(table).is_some_and(|p| p.outcome == crate::SolveOutcome::Win) {
+                positions.push(game);
+            }
+        }
+        positions
+    }
+
+    #[test]
+    fn test_full_depth_search_plays_perfectly() {
+        // TEST : Sin solver, la búsqueda completa elige siempre una jugada ganadora
+        let table = crate::SolvedTable::solve(4).unwrap();
+        let bot = AlfaBetaBot::new(Some(10)).with_endgame_solver(None);
+
+        for game in winning_positions(&table, 20) {
+            let chosen = bot.choose_move(&game).unwrap();
+            let winning_moves = game.perfect_play(&table).unwrap().winning_moves;
+            assert!(winning_moves.contains(&chosen), "{:?} not in {:?}", chosen, winning_moves);
+        }
+    }
+
+    #[test]
+    fn test_endgame_solver_plays_perfectly() {
+        // TEST : Con el solver por defecto y poca profundidad también juega perfecto
+        let table = crate::SolvedTable::solve(4).unwrap();
+        let bot = AlfaBetaBot::new(Some(1));
+
+        for game in winning_positions(&table, 20) {
+            let chosen = bot.choose_move(&game).unwrap();
+            assert!(game.perfect_play(&table).unwrap().winning_moves.contains(&chosen));
+        }
+    }
 }
@@ -1,5 +1,6 @@
-use crate::{Coordinates, GameY};
+use crate::{Coordinates, GameY, PlayerId};
 use crate::bot::YBot;
+use crate::core::topology::ALL_SIDES;
 use std::collections::HashSet;
 
 pub struct HeuristicBot;
@@ -10,6 +11,8 @@ impl YBot for HeuristicBot {
     }
 
     fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        // The bot plays the color whose turn it is
+        let player = board.next_player()?;
         let my_cells = board.available_cells();
 
         if my_cells.is_empty() {
@@ -18,7 +21,7 @@ impl YBot for HeuristicBot {
 
         let best_cell = my_cells
             .iter()
-            .max_by_key(|&&cell| self.evaluate_cell(board, cell as usize))
+            .max_by_key(|&&cell| self.evaluate_cell(board, player, cell as usize))
             .copied()?;
 
         let coordinates = Coordinates::from_index(best_cell, board.board_size());
@@ -27,30 +30,30 @@ impl YBot for HeuristicBot {
 }
 
 impl HeuristicBot {
-    fn evaluate_cell(&self, board: &GameY, cell_index: usize) -> i32 {
+    fn evaluate_cell(&self, board: &GameY, player: PlayerId, cell_index: usize) -> i32 {
         let board_size = board.board_size();
         let coords = Coordinates::from_index(cell_index as u32, board_size);
 
         let mut score = 0;
 
         score += self.calculate_center_balance_score(board_size, coords);
-        score += self.calculate_proximity_to_bot_cells(board, coords);
-        score += self.calculate_blocking_score(board, coords);
+        score += self.calculate_proximity_to_bot_cells(board, player, coords);
+        score += self.calculate_blocking_score(board, player, coords);
         score += self.calculate_edge_bonus(coords);
-        score += self.calculate_winning_potential(board, coords);
-        score += self.calculate_edge_connection_bonus(board, coords);
-        score -= self.calculate_opponent_threat_penalty(board, coords);
+        score += self.calculate_winning_potential(board, player, coords);
+        score += self.calculate_edge_connection_bonus(board, player, coords);
+        score -= self.calculate_opponent_threat_penalty(board, player, coords);
 
-        score += self.calculate_side_connection_bonus(board, coords) * 3;
-        score += self.calculate_bridge_potential(board, coords);
+        score += self.calculate_side_connection_bonus(board, player, coords) * 3;
+        score += self.calculate_bridge_potential(board, player, coords);
         score += self.calculate_central_control(board_size, coords);
 
-        if self.check_side_connection_completed(board, coords) {
+        if self.check_side_connection_completed(board, player, coords) {
             score += 200;
         }
 
-        score += self.calculate_block_opponent_connection(board, coords) * 4;
-        score += self.calculate_winning_block_bonus(board, coords);
+        score += self.calculate_block_opponent_connection(board, player, coords) * 4;
+        score += self.calculate_winning_block_bonus(board, player, coords);
 
         score
     }
@@ -72,9 +75,9 @@ impl HeuristicBot {
         balance_score.max(0) + (n * 3 - center_distance) * 3
     }
 
-    fn calculate_proximity_to_bot_cells(&self, board: &GameY, coords: Coordinates) -> i32 {
+    fn calculate_proximity_to_bot_cells(&self, board: &GameY, player: PlayerId, coords: Coordinates) -> i32 {
         let mut score = 0;
-        let bot_cells = board.get_player_positions_coords();
+        let bot_cells = board.stones_of(player);
 
         for &bot_cell in &bot_cells {
             let dist = board.manhattan_distance(coords, bot_cell);
@@ -88,9 +91,9 @@ impl HeuristicBot {
         score
     }
 
-    fn calculate_blocking_score(&self, board: &GameY, coords: Coordinates) -> i32 {
+    fn calculate_blocking_score(&self, board: &GameY, player: PlayerId, coords: Coordinates) -> i32 {
         let mut score = 0;
-        let opponent_cells = board.get_opponent_positions_coords();
+        let opponent_cells = board.stones_of(player.opponent());
 
         for &opp_cell in &opponent_cells {
             let dist = board.manhattan_distance(coords, opp_cell);
@@ -118,9 +121,9 @@ impl HeuristicBot {
         }
     }
 
-    fn calculate_winning_potential(&self, board: &GameY, coords: Coordinates) -> i32 {
+    fn calculate_winning_potential(&self, board: &GameY, player: PlayerId, coords: Coordinates) -> i32 {
         let mut score = 0;
-        let bot_cells = board.get_player_positions_coords();
+        let bot_cells = board.stones_of(player);
 
         for &bot_cell in &bot_cells {
             let dist = board.manhattan_distance(coords, bot_cell);
@@ -132,9 +135,9 @@ impl HeuristicBot {
         score
     }
 
-    fn calculate_edge_connection_bonus(&self, board: &GameY, coords: Coordinates) -> i32 {
+    fn calculate_edge_connection_bonus(&self, board: &GameY, player: PlayerId, coords: Coordinates) -> i32 {
         let mut score = 0;
-        let bot_cells = board.get_player_positions_coords();
+        let bot_cells = board.stones_of(player);
 
         let touches_a = coords.touches_side_a();
         let touches_b = coords.touches_side_b();
@@ -167,18 +170,16 @@ impl HeuristicBot {
             board.manhattan_distance(coords, bot_cell) == 1
         });
 
-        if is_connected_to_bot {
-            if touches_a || touches_b || touches_c {
-                score += 15;
-            }
+        if is_connected_to_bot && (touches_a || touches_b || touches_c) {
+            score += 15;
         }
 
         score
     }
 
-    fn calculate_opponent_threat_penalty(&self, board: &GameY, coords: Coordinates) -> i32 {
+    fn calculate_opponent_threat_penalty(&self, board: &GameY, player: PlayerId, coords: Coordinates) -> i32 {
         let mut opponent_threat = 0;
-        let opponent_cells = board.get_opponent_positions_coords();
+        let opponent_cells = board.stones_of(player.opponent());
 
         for &opp_cell in &opponent_cells {
             let dist = board.manhattan_distance(coords, opp_cell);
@@ -194,10 +195,10 @@ impl HeuristicBot {
         }
     }
 
-    fn calculate_winning_block_bonus(&self, board: &GameY, coords: Coordinates) -> i32 {
+    fn calculate_winning_block_bonus(&self, board: &GameY, player: PlayerId, coords: Coordinates) -> i32 {
         let mut score = 0;
-        let opponent_cells = board.get_opponent_positions_coords();
-        let bot_cells = board.get_player_positions_coords();
+        let opponent_cells = board.stones_of(player.opponent());
+        let bot_cells = board.stones_of(player);
 
         for &_opp_cell in &opponent_cells {
             let mut temp_opponent_cells = opponent_cells.clone();
@@ -218,14 +219,14 @@ impl HeuristicBot {
         score
     }
 
-    fn calculate_side_connection_bonus(&self, board: &GameY, coords: Coordinates) -> i32 {
+    fn calculate_side_connection_bonus(&self, board: &GameY, player: PlayerId, coords: Coordinates) -> i32 {
         let mut score = 0;
 
         if coords.touches_side_a() { score += 30; }
         if coords.touches_side_b() { score += 30; }
         if coords.touches_side_c() { score += 30; }
 
-        let bot_cells = board.get_player_positions_coords();
+        let bot_cells = board.stones_of(player);
         for &bot_cell in &bot_cells {
             if board.manhattan_distance(coords, bot_cell) == 1 {
                 if coords.touches_side_a() { score += 20; }
@@ -247,9 +248,9 @@ impl HeuristicBot {
         score
     }
 
-    fn calculate_bridge_potential(&self, board: &GameY, coords: Coordinates) -> i32 {
+    fn calculate_bridge_potential(&self, board: &GameY, player: PlayerId, coords: Coordinates) -> i32 {
         let mut score = 0;
-        let bot_cells = board.get_player_positions_coords();
+        let bot_cells = board.stones_of(player);
 
         for &bot_cell in &bot_cells {
             let dist = board.manhattan_distance(coords, bot_cell);
@@ -283,8 +284,8 @@ impl HeuristicBot {
         (n * 3 - (dx + dy + dz)) * 5
     }
 
-    fn check_side_connection_completed(&self, board: &GameY, coords: Coordinates) -> bool {
-        let bot_cells = board.get_player_positions_coords();
+    fn check_side_connection_completed(&self, board: &GameY, player: PlayerId, coords: Coordinates) -> bool {
+        let bot_cells = board.stones_of(player);
         let mut temp_cells = bot_cells.clone();
         temp_cells.push(coords);
 
@@ -295,9 +296,9 @@ impl HeuristicBot {
         touches_a && touches_b && touches_c
     }
 
-    fn calculate_block_opponent_connection(&self, board: &GameY, coords: Coordinates) -> i32 {
+    fn calculate_block_opponent_connection(&self, board: &GameY, player: PlayerId, coords: Coordinates) -> i32 {
         let mut score = 0;
-        let opponent_cells = board.get_opponent_positions_coords();
+        let opponent_cells = board.stones_of(player.opponent());
 
         if opponent_cells.is_empty() {
             return 0;
@@ -354,34 +355,41 @@ impl HeuristicBot {
             return false;
         }
 
-        let mut visited = HashSet::new();
-        let mut stack = vec![cells[0]];
-        visited.insert(cells[0]);
+        let board_size = board.board_size();
+        let topology = board.topology();
+        let mut pending = vec![false; topology.total_cells() as usize];
+        for cell in cells {
+            pending[cell.to_index(board_size) as usize] = true;
+        }
+
+        // The last cell is the candidate: start from it so only the group it joins is checked
+        let start = cells[cells.len() - 1].to_index(board_size);
+        pending[start as usize] = false;
+        let mut stack = vec![start];
+        let mut sides = 0;
 
         while let Some(current) = stack.pop() {
-            for &other in cells {
-                if !visited.contains(&other) && board.manhattan_distance(current, other) == 1 {
-                    visited.insert(other);
+            sides |= topology.sides(current);
+            for &other in topology.neighbors(current) {
+                if pending[other as usize] {
+                    pending[other as usize] = false;
                     stack.push(other);
                 }
             }
         }
 
-        let touches_a = visited.iter().any(|c| c.touches_side_a());
-        let touches_b = visited.iter().any(|c| c.touches_side_b());
-        let touches_c = visited.iter().any(|c| c.touches_side_c());
-
-        touches_a && touches_b && touches_c
+        sides == ALL_SIDES
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{Movement, PlayerId};
+    use crate::{GameStatus, MoveValidation, Movement, PlayerId, YEN};
 
     fn create_test_game(size: u32, moves: Vec<(u32, u32, u32, u32)>) -> GameY {
-        let mut game = GameY::new(size);
+        // Test positions do not follow the turn order
+        let mut game = GameY::new(size).with_validation(MoveValidation::Permissive);
         for (x, y, z, player_id) in moves {
             let coords = Coordinates::new(x, y, z);
             let movement = Movement::Placement {
@@ -465,9 +473,9 @@ mod tests {
         let side_a = Coordinates::new(3, 0, 1);
         let interior = Coordinates::new(1, 1, 1);
 
-        let side_b_score = bot.calculate_side_connection_bonus(&game, side_b);
-        let side_a_score = bot.calculate_side_connection_bonus(&game, side_a);
-        let interior_score = bot.calculate_side_connection_bonus(&game, interior);
+        let side_b_score = bot.calculate_side_connection_bonus(&game, PlayerId::new(1), side_b);
+        let side_a_score = bot.calculate_side_connection_bonus(&game, PlayerId::new(1), side_a);
+        let interior_score = bot.calculate_side_connection_bonus(&game, PlayerId::new(1), interior);
 
         assert!(side_b_score > interior_score);
         assert!(side_a_score > interior_score);
@@ -503,8 +511,8 @@ mod tests {
         let blocking = Coordinates::new(1, 1, 2);
         let far = Coordinates::new(0, 0, 4);
 
-        let blocking_score = bot.calculate_block_opponent_connection(&game, blocking);
-        let far_score = bot.calculate_block_opponent_connection(&game, far);
+        let blocking_score = bot.calculate_block_opponent_connection(&game, PlayerId::new(0), blocking);
+        let far_score = bot.calculate_block_opponent_connection(&game, PlayerId::new(0), far);
 
         assert!(blocking_score > far_score);
     }
@@ -542,11 +550,47 @@ mod tests {
         let game = GameY::new(3);
 
         let cell_idx = 2;
-        let score1 = bot.evaluate_cell(&game, cell_idx);
-        let score2 = bot.evaluate_cell(&game, cell_idx);
-        let score3 = bot.evaluate_cell(&game, cell_idx);
+        let score1 = bot.evaluate_cell(&game, PlayerId::new(0), cell_idx);
+        let score2 = bot.evaluate_cell(&game, PlayerId::new(0), cell_idx);
+        let score3 = bot.evaluate_cell(&game, PlayerId::new(0), cell_idx);
 
         assert_eq!(score1, score2);
         assert_eq!(score2, score3);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_choose_move_wins_with_either_colour() {
+        // Same board with the colors swapped: the player to move holds
+        // (2,0,0) and (1,0,1) and wins at (0,0,2) or (0,1,1)
+        for (player_id, turn, layout) in [(0, 0, "B/BR/..R"), (1, 1, "R/RB/..B")] {
+            let yen = YEN::new(3, turn, vec!['B', 'R'], layout.to_string());
+            let mut game = GameY::try_from(yen).unwrap();
+
+            let coords = HeuristicBot.choose_move(&game).unwrap();
+            game.add_move(Movement::Placement {
+                player: PlayerId::new(player_id),
+                coords,
+            }).unwrap();
+
+            assert_eq!(
+                *game.status(),
+                GameStatus::Finished { winner: PlayerId::new(player_id) }
+            );
+        }
+    }
+
+    #[test]
+    fn test_evaluation_is_colour_symmetric() {
+        // Swapping the colors and the turn does not change the score of any cell
+        let blue = GameY::try_from(YEN::new(4, 0, vec!['B', 'R'], "B/RB/.R./B..R".to_string())).unwrap();
+        let red = GameY::try_from(YEN::new(4, 1, vec!['B', 'R'], "R/BR/.B./R..B".to_string())).unwrap();
+
+        for &cell in blue.available_cells() {
+            assert_eq!(
+                HeuristicBot.evaluate_cell(&blue, PlayerId::new(0), cell as usize),
+                HeuristicBot.evaluate_cell(&red, PlayerId::new(1), cell as usize)
+            );
+        }
+        assert_eq!(HeuristicBot.choose_move(&blue), HeuristicBot.choose_move(&red));
+    }
+}
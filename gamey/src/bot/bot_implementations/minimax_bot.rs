@@ -1,6 +1,6 @@
-use crate::{Coordinates, GameY, PlayerId};
+use crate::{Coordinates, GameY, MoveValidation, PlayerId, SearchLimits, SearchResult};
 use crate::bot::YBot;
-use std::collections::HashSet;
+use std::time::Instant;
 
 // Constantes para la evaluación heurística
 const WIN_SCORE: i32 = 10000;
@@ -12,8 +12,6 @@ const BLOCKING_BONUS: i32 = 5;
 
 // Constantes para el bot
 const DEFAULT_MAX_DEPTH: u32 = 3;
-const BOT_PLAYER_ID: u32 = 1;  // El bot juega como Player 1 (oponente)
-const HUMAN_PLAYER_ID: u32 = 0; // El humano juega como Player 0
 
 pub struct MinimaxBot {
     max_depth: u32,
@@ -27,20 +25,12 @@ impl MinimaxBot {
     }
 
     // Evaluación heurística del tablero para un jugador específico
-    fn evaluate_board(&self, board: &GameY, for_player_id: u32) -> i32 {
+    fn evaluate_board(&self, board: &GameY, player: PlayerId) -> i32 {
         // Obtener celdas del jugador que estamos evaluando
-        let player_cells = if for_player_id == BOT_PLAYER_ID {
-            board.get_opponent_positions_coords()  // El bot es el oponente
-        } else {
-            board.get_player_positions_coords()     // El humano es el jugador
-        };
+        let player_cells = board.stones_of(player);
 
         // Obtener celdas del otro jugador
-        let opponent_cells = if for_player_id == BOT_PLAYER_ID {
-            board.get_player_positions_coords()
-        } else {
-            board.get_opponent_positions_coords()
-        };
+        let opponent_cells = board.stones_of(player.opponent());
 
         let mut score = 0;
 
@@ -93,19 +83,26 @@ impl MinimaxBot {
             return None;
         }
 
-        let mut visited = HashSet::new();
+        let board_size = board.board_size();
+        let topology = board.topology();
+        // Celdas aún no visitadas, indexadas por índice de celda
+        let mut pending = vec![false; topology.total_cells() as usize];
+        for cell in cells {
+            pending[cell.to_index(board_size) as usize] = true;
+        }
         let mut max_size = 0;
 
         for &cell in cells {
-            if !visited.contains(&cell) {
-                let mut stack = vec![cell];
-                visited.insert(cell);
+            let start = cell.to_index(board_size);
+            if pending[start as usize] {
+                pending[start as usize] = false;
+                let mut stack = vec![start];
                 let mut group_size = 1;
 
                 while let Some(current) = stack.pop() {
-                    for &other in cells {
-                        if !visited.contains(&other) && board.manhattan_distance(current, other) == 1 {
-                            visited.insert(other);
+                    for &other in topology.neighbors(current) {
+                        if pending[other as usize] {
+                            pending[other as usize] = false;
                             stack.push(other);
                             group_size += 1;
                         }
@@ -120,12 +117,8 @@ impl MinimaxBot {
     }
 
     // Verificar si un jugador ha ganado
-    fn check_winner(&self, board: &GameY, player_id: u32) -> bool {
-        let cells = if player_id == BOT_PLAYER_ID {
-            board.get_opponent_positions_coords()  // Bot es oponente
-        } else {
-            board.get_player_positions_coords()
-        };
+    fn check_winner(&self, board: &GameY, player: PlayerId) -> bool {
+        let cells = board.stones_of(player);
 
         let touches_a = cells.iter().any(|c| c.touches_side_a());
         let touches_b = cells.iter().any(|c| c.touches_side_b());
@@ -134,42 +127,37 @@ impl MinimaxBot {
         touches_a && touches_b && touches_c
     }
 
-    // Algoritmo Minimax
-    fn minimax(&self, board: &GameY, depth: u32, is_bot_turn: bool) -> i32 {
+    // Algoritmo Minimax, evaluado desde el punto de vista de `bot_player`
+    fn minimax(&self, board: &mut GameY, depth: u32, bot_player: PlayerId, is_bot_turn: bool) -> i32 {
         // Condiciones de terminación
         if depth == 0 {
-            return self.evaluate_board(board, BOT_PLAYER_ID);
+            return self.evaluate_board(board, bot_player);
         }
 
         // Verificar si alguien ganó
-        if self.check_winner(board, BOT_PLAYER_ID) {
+        if self.check_winner(board, bot_player) {
             return WIN_SCORE;  // Gana el bot
         }
-        if self.check_winner(board, HUMAN_PLAYER_ID) {
-            return LOSE_SCORE;  // Gana el humano (malo para el bot)
+        if self.check_winner(board, bot_player.opponent()) {
+            return LOSE_SCORE;  // Gana el rival (malo para el bot)
         }
 
-        let available = board.available_cells();
+        let available = board.available_cells().clone();
         if available.is_empty() {
             return 0;  // Empate
         }
 
-        let current_player_id = if is_bot_turn { BOT_PLAYER_ID } else { HUMAN_PLAYER_ID };
+        let current_player = if is_bot_turn { bot_player } else { bot_player.opponent() };
 
         if is_bot_turn {
             // Turno del bot (maximizar)
             let mut max_eval = i32::MIN;
-            for cell_idx in available {  // Quitamos el & antes de cell_idx
-                let coords = Coordinates::from_index(*cell_idx, board.board_size());  // Añadimos * para dereferenciar
-                let mut board_copy = board.clone();
+            for &cell_idx in &available {
+                let coords = Coordinates::from_index(cell_idx, board.board_size());
 
-                let movement = crate::Movement::Placement {
-                    player: PlayerId::new(current_player_id),
-                    coords,
-                };
-
-                if board_copy.add_move(movement).is_ok() {
-                    let eval = self.minimax(&board_copy, depth - 1, false);
+                if board.make_move(current_player, coords).is_ok() {
+                    let eval = self.minimax(board, depth - 1, bot_player, false);
+                    board.unmake_move();
                     max_eval = max_eval.max(eval);
                 }
             }
@@ -177,17 +165,12 @@ impl MinimaxBot {
         } else {
             // Turno del humano (minimizar)
             let mut min_eval = i32::MAX;
-            for cell_idx in available {  // Quitamos el & antes de cell_idx
-                let coords = Coordinates::from_index(*cell_idx, board.board_size());  // Añadimos * para dereferenciar
-                let mut board_copy = board.clone();
-
-                let movement = crate::Movement::Placement {
-                    player: PlayerId::new(current_player_id),
-                    coords,
-                };
+            for &cell_idx in &available {
+                let coords = Coordinates::from_index(cell_idx, board.board_size());
 
-                if board_copy.add_move(movement).is_ok() {
-                    let eval = self.minimax(&board_copy, depth - 1, true);
+                if board.make_move(current_player, coords).is_ok() {
+                    let eval = self.minimax(board, depth - 1, bot_player, true);
+                    board.unmake_move();
                     min_eval = min_eval.min(eval);
                 }
             }
@@ -202,10 +185,24 @@ impl YBot for MinimaxBot {
     }
 
     fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
-        let available = board.available_cells();
-        if available.is_empty() {
-            return None;
-        }
+        self.search(board, &SearchLimits::default()).best_move
+    }
+
+    // Minimax no se puede cortar a medias: solo se respeta el límite de profundidad
+    fn search(&self, board: &GameY, limits: &SearchLimits) -> SearchResult {
+        let start = Instant::now();
+        let max_depth = limits.max_depth.unwrap_or(self.max_depth).max(1);
+
+        // El bot juega con el color al que le toca mover
+        let Some(bot_player) = board.next_player() else {
+            return SearchResult::from_move(None, start.elapsed());
+        };
+
+        // La búsqueda alterna los colores por su cuenta, así que trabaja sobre
+        // una copia en modo permisivo. Las jugadas se prueban y se deshacen
+        // sobre esa única copia
+        let board = &mut board.clone().with_validation(MoveValidation::Permissive);
+        let available = board.available_cells().clone();
 
         let mut best_score = i32::MIN;
         let mut best_move = None;
@@ -213,17 +210,12 @@ impl YBot for MinimaxBot {
         // Probar cada movimiento posible
         for &cell_idx in available.iter() {  // .iter() devuelve iterador sobre referencias
             let coords = Coordinates::from_index(cell_idx, board.board_size());  // cell_idx ya es u32
-            let mut board_copy = board.clone();
 
             // Simular nuestro movimiento (como bot, turno actual)
-            let movement = crate::Movement::Placement {
-                player: PlayerId::new(BOT_PLAYER_ID),
-                coords,
-            };
-
-            if board_copy.add_move(movement).is_ok() {
-                // Evaluar con Minimax (siguiente turno es del humano)
-                let score = self.minimax(&board_copy, self.max_depth - 1, false);
+            if board.make_move(bot_player, coords).is_ok() {
+                // Evaluar con Minimax (siguiente turno es del rival)
+                let score = self.minimax(board, max_depth - 1, bot_player, false);
+                board.unmake_move();
 
                 if score > best_score {
                     best_score = score;
@@ -232,17 +224,26 @@ impl YBot for MinimaxBot {
             }
         }
 
-        best_move
+        SearchResult {
+            score: best_move.map(|_| best_score),
+            depth: best_move.map(|_| max_depth),
+            ..SearchResult::from_move(best_move, start.elapsed())
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{Movement, GameY, Coordinates, PlayerId};
+    use crate::{Movement, GameY, GameStatus, Coordinates, PlayerId, YEN};
+
+    // Colores usados en las posiciones de prueba
+    const BOT_PLAYER_ID: u32 = 1;
+    const HUMAN_PLAYER_ID: u32 = 0;
 
     fn create_test_game(size: u32, moves: Vec<(u32, u32, u32, u32)>) -> GameY {
-        let mut game = GameY::new(size);
+        // Las posiciones de prueba no siguen el orden de turnos
+        let mut game = GameY::new(size).with_validation(MoveValidation::Permissive);
         for (x, y, z, player_id) in moves {
             let coords = Coordinates::new(x, y, z);
             let movement = Movement::Placement {
@@ -270,7 +271,7 @@ mod tests {
     fn test_evaluate_board_empty() {
         let bot = MinimaxBot::new(None);
         let game = GameY::new(3);
-        let score = bot.evaluate_board(&game, BOT_PLAYER_ID);
+        let score = bot.evaluate_board(&game, PlayerId::new(BOT_PLAYER_ID));
         // En tablero vacío, la puntuación debería ser 0 o baja
         assert!(score >= 0);
     }
@@ -284,7 +285,7 @@ mod tests {
             (2, 0, 0, BOT_PLAYER_ID),
         ]);
 
-        let score = bot.evaluate_board(&game, BOT_PLAYER_ID);
+        let score = bot.evaluate_board(&game, PlayerId::new(BOT_PLAYER_ID));
         assert!(score > 0);
     }
 
@@ -299,7 +300,7 @@ mod tests {
             (0, 0, 2, BOT_PLAYER_ID), // Lado C
         ]);
 
-        assert!(bot.check_winner(&game, BOT_PLAYER_ID));
+        assert!(bot.check_winner(&game, PlayerId::new(BOT_PLAYER_ID)));
     }
 
     #[test]
@@ -354,4 +355,42 @@ mod tests {
         let largest = bot.find_largest_connected_group(&cells, &game);
         assert_eq!(largest, Some(2)); // Las dos primeras están conectadas
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_choose_move_wins_with_either_colour() {
+        // Mismo tablero con los colores intercambiados: el jugador que mueve
+        // tiene (2,0,0) y (1,0,1) y gana en (0,0,2) o en (0,1,1)
+        for (player_id, turn, layout) in [
+            (HUMAN_PLAYER_ID, 0, "B/BR/..R"),
+            (BOT_PLAYER_ID, 1, "R/RB/..B"),
+        ] {
+            let yen = YEN::new(3, turn, vec!['B', 'R'], layout.to_string());
+            let mut game = GameY::try_from(yen).unwrap();
+            let bot = MinimaxBot::new(Some(2));
+
+            let coords = bot.choose_move(&game).unwrap();
+            game.add_move(Movement::Placement {
+                player: PlayerId::new(player_id),
+                coords,
+            }).unwrap();
+
+            assert_eq!(
+                *game.status(),
+                GameStatus::Finished { winner: PlayerId::new(player_id) }
+            );
+        }
+    }
+
+    #[test]
+    fn test_search_respects_depth_limit() {
+        let bot = MinimaxBot::new(Some(3));
+        let game = GameY::new(3);
+
+        let result = bot.search(&game, &SearchLimits::new().with_max_depth(1));
+
+        assert!(result.best_move.is_some());
+        assert_eq!(result.depth, Some(1));
+        assert!(result.score.is_some());
+        assert_eq!(result.principal_variation.first(), result.best_move.as_ref());
+    }
+}
@@ -4,9 +4,11 @@ pub mod heuristic_bot;
 mod minimax_bot;
 mod alfa_beta_bot;
 mod monte_carlo_bot;
+mod transposition_table;
 
 pub use random::*;
 pub use heuristic_bot::*;
 pub use minimax_bot::*;
 pub use alfa_beta_bot::*;
 pub use monte_carlo_bot::*;
+pub use transposition_table::TranspositionTable;
@@ -1,5 +1,8 @@
-use crate::{Coordinates, GameY, PlayerId};
+use crate::{BotDecision, Coordinates, DEFAULT_ENDGAME_CELLS, DEFAULT_RESIGN_THRESHOLD, GameStatus, GameY, MoveValidation, PlayerId, SearchLimits, SearchResult, SolveOutcome, decide_with_search, solve};
 use crate::bot::YBot;
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 use rand::prelude::*;
 
@@ -15,18 +18,26 @@ const HARD_TIME_LIMIT_MS: u64 = 2800;
 const EXTREME_ITERATIONS: u32 = 30000;
 const EXTREME_TIME_LIMIT_MS: u64 = 4800;
 
+// Dificultad: RAVE (mismo presupuesto que Extrema, con estadísticas AMAF)
+const RAVE_ITERATIONS: u32 = 30000;
+const RAVE_TIME_LIMIT_MS: u64 = 4800;
+// Número de visitas con el que la estimación AMAF y la normal pesan lo mismo
+const RAVE_EQUIVALENCE: f64 = 300.0;
+// Con RAVE se explora mucho menos: las estadísticas AMAF ya reparten las visitas
+const RAVE_EXPLORATION_CONSTANT: f64 = 0.1;
+
 // Constantes compartidas
 const EXPLORATION_CONSTANT: f64 = 1.414;  // √2
-const MAX_SIMULATION_DEPTH: u32 = 30;
+// Jugadas bajo la raíz anterior en las que se busca la posición nueva: la
+// respuesta del bot y la del rival
+const REUSE_MAX_DEPTH: usize = 2;
+// Hilos de búsqueda por defecto como mucho: el servidor atiende varias
+// peticiones a la vez y cada una no debe ocupar todos los núcleos
+const MAX_DEFAULT_THREADS: usize = 4;
 const WIN_SCORE: f64 = 1.0;
 const LOSE_SCORE: f64 = 0.0;
-const DRAW_SCORE: f64 = 0.5;
-
-// ============================================================
-// IDs DE JUGADORES
-// ============================================================
-const BOT_PLAYER_ID: u32 = 1;      // El bot es el oponente (player 1)
-const HUMAN_PLAYER_ID: u32 = 0;    // El humano es player 0
+// Nodos del solver de final si la búsqueda no tiene límite de nodos
+const ENDGAME_SOLVER_NODES: u64 = 200000;
 
 // ============================================================
 // ENUM DE DIFICULTAD
@@ -36,21 +47,37 @@ const HUMAN_PLAYER_ID: u32 = 0;    // El humano es player 0
 pub enum MonteCarloDifficulty {
     Hard,     // 3 segundos
     Extreme,  // 5 segundos
+    Rave,     // 5 segundos, con RAVE
 }
 
 // ============================================================
 // ESTRUCTURAS DE DATOS PARA MCTS
 // ============================================================
 
+// Resultado demostrado de un nodo (MCTS-Solver), desde el punto de vista del
+// jugador que hizo la jugada que lleva a él, como sus victorias
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Proof {
+    Win,
+    Loss,
+}
+
 #[derive(Clone)]
 struct MCTSNode {
     visits: u32,
     wins: f64,
+    // Estadísticas AMAF (all-moves-as-first): simulaciones que pasaron por el
+    // padre en las que su jugador jugó esta casilla en algún momento
+    amaf_visits: u32,
+    amaf_wins: f64,
     move_coords: Option<Coordinates>,
     parent: Option<usize>,
     children: Vec<usize>,
     untried_moves: Vec<Coordinates>,
+    // Jugador al que le toca mover en este nodo
     player: PlayerId,
+    // Resultado exacto, si ya se ha demostrado
+    proven: Option<Proof>,
 }
 
 impl MCTSNode {
@@ -58,11 +85,14 @@ impl MCTSNode {
         Self {
             visits: 0,
             wins: 0.0,
+            amaf_visits: 0,
+            amaf_wins: 0.0,
             move_coords,
             parent,
             children: Vec::new(),
             untried_moves: Vec::new(),
             player,
+            proven: None,
         }
     }
 
@@ -75,6 +105,30 @@ impl MCTSNode {
         }
     }
 
+    // UCB sobre una mezcla de la media normal y la AMAF. El peso de la AMAF
+    // (beta) baja según crecen las visitas reales, y vale 1/2 cuando el nodo
+    // tiene `equivalence` visitas. Un nodo sin visitas se valora solo por su
+    // AMAF, así que las jugadas prometedoras se prueban antes
+    fn rave_score(&self, parent_visits: u32, exploration: f64, equivalence: f64) -> f64 {
+        let parent_log = (parent_visits.max(1) as f64).ln();
+        if self.visits == 0 {
+            return if self.amaf_visits > 0 {
+                self.amaf_wins / self.amaf_visits as f64 + exploration * parent_log.sqrt()
+            } else {
+                f64::INFINITY
+            };
+        }
+        let visits = self.visits as f64;
+        let mean = self.wins / visits;
+        let value = if self.amaf_visits > 0 {
+            let beta = (equivalence / (3.0 * visits + equivalence)).sqrt();
+            (1.0 - beta) * mean + beta * self.amaf_wins / self.amaf_visits as f64
+        } else {
+            mean
+        };
+        value + exploration * (parent_log / visits).sqrt()
+    }
+
     fn is_terminal(&self) -> bool {
         self.untried_moves.is_empty() && self.children.is_empty()
     }
@@ -83,17 +137,20 @@ impl MCTSNode {
 struct MCTSTree {
     nodes: Vec<MCTSNode>,
     root: usize,
-    board: GameY,
+    // Tablero de la raíz. Cada iteración juega sobre él y deshace sus jugadas
+    // al terminar, así que no hace falta clonarlo
+    board: RefCell<GameY>,
+    // Equivalencia de RAVE, o None para usar UCB normal
+    rave_equivalence: Option<f64>,
+    // Generador de las jugadas aleatorias del árbol
+    rng: RefCell<StdRng>,
 }
 
 impl MCTSTree {
     fn new(board: &GameY) -> Self {
         let mut nodes = Vec::new();
 
-        let current_player = match board.status() {
-            crate::GameStatus::Ongoing { next_player } => *next_player,
-            _ => PlayerId::new(0),
-        };
+        let current_player = board.next_player().unwrap_or(PlayerId::new(0));
 
         let root = MCTSNode::new(None, None, current_player);
         nodes.push(root);
@@ -101,29 +158,47 @@ impl MCTSTree {
         Self {
             nodes,
             root: 0,
-            board: board.clone(),
+            // Las simulaciones colocan piedras con el jugador de cada nodo,
+            // así que el árbol trabaja sobre una copia en modo permisivo
+            board: RefCell::new(board.clone().with_validation(MoveValidation::Permissive)),
+            rave_equivalence: None,
+            rng: RefCell::new(StdRng::from_rng(&mut rand::rng())),
         }
     }
 
+    // Las jugadas sin probar de un nodo son las casillas libres, barajadas
+    fn fill_untried_moves(node: &mut MCTSNode, board: &GameY, rng: &mut StdRng) {
+        let mut moves: Vec<Coordinates> = board.available_cells().iter()
+            .map(|&idx| Coordinates::from_index(idx, board.board_size()))
+            .collect();
+        moves.shuffle(rng);
+        node.untried_moves = moves;
+    }
+
     // ============================================================
     // FASE 1: SELECTION
     // ============================================================
     fn select(&mut self) -> usize {
         let mut node_idx = self.root;
 
-        while !self.nodes[node_idx].is_terminal() {
+        // Un nodo demostrado no hace falta explorarlo más: su resultado se
+        // propaga tal cual
+        while !self.nodes[node_idx].is_terminal() && self.nodes[node_idx].proven.is_none() {
             if !self.nodes[node_idx].untried_moves.is_empty() {
                 return node_idx;
             }
 
             let parent_visits = self.nodes[node_idx].visits;
+            let score = |child: usize| match self.rave_equivalence {
+                Some(equivalence) => self.nodes[child]
+                    .rave_score(parent_visits, RAVE_EXPLORATION_CONSTANT, equivalence),
+                None => self.nodes[child].ucb_score(parent_visits, EXPLORATION_CONSTANT),
+            };
+            // Las jugadas que pierden seguro no se eligen
             let best_child = self.nodes[node_idx].children
                 .iter()
-                .max_by(|&&a, &&b| {
-                    let score_a = self.nodes[a].ucb_score(parent_visits, EXPLORATION_CONSTANT);
-                    let score_b = self.nodes[b].ucb_score(parent_visits, EXPLORATION_CONSTANT);
-                    score_a.partial_cmp(&score_b).unwrap()
-                })
+                .filter(|&&child| self.nodes[child].proven != Some(Proof::Loss))
+                .max_by(|&&a, &&b| score(a).partial_cmp(&score(b)).unwrap())
                 .copied();
 
             match best_child {
@@ -138,7 +213,7 @@ impl MCTSTree {
     // ============================================================
     // FASE 2: EXPANSION
     // ============================================================
-    fn expand(&mut self, node_idx: usize, _board: &GameY) -> usize {
+    fn expand(&mut self, node_idx: usize) -> usize {
         let node = &self.nodes[node_idx];
 
         if node.untried_moves.is_empty() {
@@ -147,11 +222,7 @@ impl MCTSTree {
 
         let move_coords = node.untried_moves[0];
 
-        let next_player = if node.player.id() == BOT_PLAYER_ID {
-            PlayerId::new(HUMAN_PLAYER_ID)
-        } else {
-            PlayerId::new(BOT_PLAYER_ID)
-        };
+        let next_player = node.player.opponent();
 
         let new_node = MCTSNode::new(Some(move_coords), Some(node_idx), next_player);
         let new_idx = self.nodes.len();
@@ -164,54 +235,64 @@ impl MCTSTree {
         new_idx
     }
 
+    fn expand_all(&mut self, node_idx: usize) {
+        while !self.nodes[node_idx].untried_moves.is_empty() {
+            self.expand(node_idx);
+        }
+    }
+
     // ============================================================
     // FASE 3: SIMULATION (ROLLOUT)
     // ============================================================
-    fn simulate(&self, mut board: GameY, start_player: PlayerId) -> f64 {
+    // Juega la partida aleatoria sobre `board` hasta el final y devuelve el
+    // resultado desde el punto de vista de `start_player`. En Y no hay empates:
+    // el motor detecta la victoria al colocar la piedra que conecta los tres
+    // lados, y las casillas que quedan ya no pueden cambiar el ganador. Deshace
+    // todas sus jugadas antes de devolverlo, así que el tablero queda como estaba
+    #[cfg(test)]
+    fn simulate(&self, board: &mut GameY, start_player: PlayerId) -> f64 {
+        self.rollout(board, start_player, &mut Vec::new())
+    }
+
+    // Como `simulate`, pero apunta en `moves` las casillas jugadas, en orden:
+    // las pares son de `start_player` y las impares de su rival
+    fn rollout(&self, board: &mut GameY, start_player: PlayerId, moves: &mut Vec<u32>) -> f64 {
         let mut current_player = start_player;
-        let mut depth = 0;
-
-        while depth < MAX_SIMULATION_DEPTH {
-            depth += 1;
-
-            if self.check_winner(&board, BOT_PLAYER_ID) {
-                return if current_player.id() == BOT_PLAYER_ID { WIN_SCORE } else { LOSE_SCORE };
-            }
-
-            if self.check_winner(&board, HUMAN_PLAYER_ID) {
-                return if current_player.id() == HUMAN_PLAYER_ID { WIN_SCORE } else { LOSE_SCORE };
-            }
+        let mut played = 0;
 
+        while !board.check_game_over() {
             let available = board.available_cells();
             if available.is_empty() {
-                return DRAW_SCORE;
+                break;
             }
 
-            let chosen = self.heuristic_random_move(&board, &available);
-
+            let chosen = self.heuristic_random_move(board, available);
             let coords = Coordinates::from_index(chosen, board.board_size());
-            let movement = crate::Movement::Placement {
-                player: current_player,
-                coords,
-            };
 
-            if board.add_move(movement).is_err() {
+            if board.make_move(current_player, coords).is_err() {
                 break;
             }
+            moves.push(chosen);
+            played += 1;
 
-            current_player = if current_player.id() == BOT_PLAYER_ID {
-                PlayerId::new(HUMAN_PLAYER_ID)
-            } else {
-                PlayerId::new(BOT_PLAYER_ID)
-            };
+            current_player = current_player.opponent();
         }
 
-        DRAW_SCORE
+        let result = match board.status() {
+            GameStatus::Finished { winner } if *winner == start_player => WIN_SCORE,
+            _ => LOSE_SCORE,
+        };
+
+        for _ in 0..played {
+            board.unmake_move();
+        }
+        result
     }
 
     fn heuristic_random_move(&self, board: &GameY, available: &[u32]) -> u32 {
-        if rand::random::<f64>() < 0.8 {
-            let idx = rand::rng().random_range(0..available.len());
+        let mut rng = self.rng.borrow_mut();
+        if rng.random::<f64>() < 0.8 {
+            let idx = rng.random_range(0..available.len());
             available[idx]
         } else {
             let mut best_move = available[0];
@@ -249,30 +330,47 @@ impl MCTSTree {
     // ============================================================
     // FASE 4: BACKPROPAGATION
     // ============================================================
+    // `result` es el resultado para el jugador que mueve en `node_idx`. Cada
+    // nodo acumula las victorias del jugador que hizo la jugada que lleva a él,
+    // que es el que elige ese hijo en la selección. De paso sube las
+    // demostraciones: si un hijo está demostrado se intenta demostrar el padre
     fn backpropagate(&mut self, mut node_idx: usize, result: f64) {
         let mut current_result = result;
 
-        while let Some(parent_idx) = self.nodes[node_idx].parent {
-            {
-                let node = &mut self.nodes[node_idx];
-                node.visits += 1;
-                if node.player.id() == BOT_PLAYER_ID {
-                    node.wins += current_result;
-                } else {
-                    node.wins += 1.0 - current_result;
-                }
-            }
+        loop {
+            let node = &mut self.nodes[node_idx];
+            node.visits += 1;
+            node.wins += 1.0 - current_result;
 
             current_result = 1.0 - current_result;
-            node_idx = parent_idx;
+            let proven = node.proven.is_some();
+            match node.parent {
+                Some(parent_idx) => {
+                    if proven && self.nodes[parent_idx].proven.is_none() {
+                        self.nodes[parent_idx].proven = self.proof_from_children(parent_idx);
+                    }
+                    node_idx = parent_idx;
+                }
+                None => break,
+            }
         }
+    }
 
-        let root = &mut self.nodes[node_idx];
-        root.visits += 1;
-        if root.player.id() == BOT_PLAYER_ID {
-            root.wins += current_result;
+    // Un nodo está perdido para quien jugó hacia él si el que mueve en él tiene
+    // una jugada ganadora, y ganado si todas sus jugadas pierden
+    fn proof_from_children(&self, node_idx: usize) -> Option<Proof> {
+        let node = &self.nodes[node_idx];
+        let mut proofs = node.children.iter().map(|&child| self.nodes[child].proven);
+
+        if proofs.clone().any(|proof| proof == Some(Proof::Win)) {
+            Some(Proof::Loss)
+        } else if node.untried_moves.is_empty()
+            && !node.children.is_empty()
+            && proofs.all(|proof| proof == Some(Proof::Loss))
+        {
+            Some(Proof::Win)
         } else {
-            root.wins += 1.0 - current_result;
+            None
         }
     }
 
@@ -282,23 +380,104 @@ impl MCTSTree {
     fn iterate(&mut self) {
         let selected = self.select();
 
-        let mut board_copy = self.board.clone();
-        self.play_to_node(&mut board_copy, selected);
-
-        let to_simulate = if !self.nodes[selected].untried_moves.is_empty() {
-            self.expand(selected, &board_copy)
+        let to_simulate = if self.nodes[selected].proven.is_none()
+            && !self.nodes[selected].untried_moves.is_empty()
+        {
+            self.expand(selected)
         } else {
             selected
         };
 
         let start_player = self.nodes[to_simulate].player;
-        self.play_to_node(&mut board_copy, to_simulate);
-        let result = self.simulate(board_copy, start_player);
+        let mut board = self.board.borrow_mut();
+        let played = self.play_to_node(&mut board, to_simulate);
+
+        // En su primera visita el nodo apunta sus jugadas posibles, para que el
+        // árbol pueda seguir creciendo por debajo. Si la partida ya está
+        // decidida se queda sin ellas y es terminal
+        if to_simulate != self.root
+            && self.nodes[to_simulate].visits == 0
+            && !board.check_game_over()
+        {
+            Self::fill_untried_moves(&mut self.nodes[to_simulate], &board, &mut self.rng.borrow_mut());
+        }
+
+        // Una partida terminada la ha ganado quien hizo la última jugada
+        if let GameStatus::Finished { winner } = board.status() {
+            let proof = if *winner == start_player { Proof::Loss } else { Proof::Win };
+            self.nodes[to_simulate].proven = Some(proof);
+        }
+
+        // Con el resultado demostrado no hace falta simular
+        let mut rollout_moves = Vec::new();
+        let result = match self.nodes[to_simulate].proven {
+            Some(Proof::Win) => LOSE_SCORE,
+            Some(Proof::Loss) => WIN_SCORE,
+            None => self.rollout(&mut board, start_player, &mut rollout_moves),
+        };
+
+        // Volver a la posición de la raíz para la siguiente iteración
+        for _ in 0..played {
+            board.unmake_move();
+        }
+        let board_size = board.board_size();
+        drop(board);
+
+        // Con RAVE se crean todos los hijos a la vez, para que acumulen
+        // estadísticas AMAF desde la primera simulación
+        if self.rave_equivalence.is_some() {
+            self.expand_all(to_simulate);
+        }
 
         self.backpropagate(to_simulate, result);
+
+        if self.rave_equivalence.is_some() {
+            let winner = if result == WIN_SCORE { start_player } else { start_player.opponent() };
+            self.update_amaf(to_simulate, &rollout_moves, start_player, winner, board_size);
+        }
+    }
+
+    // ============================================================
+    // ACTUALIZACIÓN AMAF (RAVE)
+    // ============================================================
+    // Sube desde `node_idx` hasta la raíz. En cada nodo, los hijos cuya casilla
+    // jugó más tarde (en el árbol o en el rollout) el jugador que mueve en ese
+    // nodo suman una visita AMAF, con el resultado para ese jugador
+    fn update_amaf(&mut self, mut node_idx: usize, rollout_moves: &[u32], start_player: PlayerId, winner: PlayerId, board_size: u32) {
+        // Jugador que ocupó cada casilla por debajo del nodo actual
+        let mut owner: Vec<Option<PlayerId>> = vec![None; (board_size * (board_size + 1) / 2) as usize];
+        for (i, &cell) in rollout_moves.iter().enumerate() {
+            let player = if i % 2 == 0 { start_player } else { start_player.opponent() };
+            owner[cell as usize] = Some(player);
+        }
+
+        loop {
+            let player = self.nodes[node_idx].player;
+            let result = if winner == player { WIN_SCORE } else { LOSE_SCORE };
+            for i in 0..self.nodes[node_idx].children.len() {
+                let child = self.nodes[node_idx].children[i];
+                let Some(coords) = self.nodes[child].move_coords else { continue };
+                if owner[coords.to_index(board_size) as usize] == Some(player) {
+                    let child = &mut self.nodes[child];
+                    child.amaf_visits += 1;
+                    child.amaf_wins += result;
+                }
+            }
+
+            let node = &self.nodes[node_idx];
+            if let Some(coords) = node.move_coords {
+                owner[coords.to_index(board_size) as usize] = Some(node.player.opponent());
+            }
+            match node.parent {
+                Some(parent_idx) => node_idx = parent_idx,
+                None => break,
+            }
+        }
     }
 
-    fn play_to_node(&self, board: &mut GameY, node_idx: usize) {
+    // Juega sobre `board` las jugadas del camino hasta el nodo y devuelve
+    // cuántas se han aplicado, para poder deshacerlas después
+    fn play_to_node(&self, board: &mut GameY, node_idx: usize) -> usize {
         let mut path = Vec::new();
         let mut current = node_idx;
 
@@ -307,17 +486,20 @@ impl MCTSTree {
             current = parent;
         }
 
+        let mut played = 0;
         for &node_idx in path.iter().rev() {
-            if let Some(coords) = self.nodes[node_idx].move_coords {
-                let movement = crate::Movement::Placement {
-                    player: self.nodes[node_idx].player,
-                    coords,
-                };
-                let _ = board.add_move(movement);
+            // La jugada del nodo la hace el rival del jugador que mueve en él
+            let node = &self.nodes[node_idx];
+            if let Some(coords) = node.move_coords
+                && board.make_move(node.player.opponent(), coords).is_ok()
+            {
+                played += 1;
             }
         }
+        played
     }
 
+    #[cfg(test)]
     fn best_move(&self) -> Option<Coordinates> {
         let root = &self.nodes[self.root];
 
@@ -325,68 +507,170 @@ impl MCTSTree {
             return None;
         }
 
-        let best_child = root.children
-            .iter()
-            .max_by_key(|&&child| self.nodes[child].visits)
-            .copied()
-            .unwrap();
+        let best_child = self.proven_win_child(self.root).unwrap_or_else(|| {
+            root.children
+                .iter()
+                .max_by_key(|&&child| self.nodes[child].visits)
+                .copied()
+                .unwrap()
+        });
 
         self.nodes[best_child].move_coords
     }
 
-    fn check_winner(&self, board: &GameY, player_id: u32) -> bool {
-        // Obtener las celdas del jugador
-        let cells = if player_id == BOT_PLAYER_ID {
-            board.get_opponent_positions_coords()
-        } else {
-            board.get_player_positions_coords()
-        };
+    // Hijo con victoria demostrada para el jugador que mueve en el nodo
+    fn proven_win_child(&self, node_idx: usize) -> Option<usize> {
+        self.nodes[node_idx].children
+            .iter()
+            .copied()
+            .find(|&child| self.nodes[child].proven == Some(Proof::Win))
+    }
+
+    // Hijo más visitado de un nodo, o None si aún no se ha explorado
+    fn most_visited_child(&self, node_idx: usize) -> Option<usize> {
+        self.nodes[node_idx].children
+            .iter()
+            .copied()
+            .filter(|&child| self.nodes[child].visits > 0)
+            .max_by_key(|&child| self.nodes[child].visits)
+    }
 
-        if cells.len() < 3 {
-            return false;
+    // Busca, en la raíz o hasta REUSE_MAX_DEPTH jugadas por debajo, un nodo ya
+    // visitado cuya posición sea la de `board`
+    fn find_position(&self, board: &GameY) -> Option<usize> {
+        let mut tree_board = self.board.borrow_mut();
+        if tree_board.board_size() != board.board_size() {
+            return None;
+        }
+        let target = board.zobrist_hash();
+        let free_cells = board.available_cells().len();
+
+        let mut frontier = vec![self.root];
+        for _ in 0..=REUSE_MAX_DEPTH {
+            let mut next = Vec::new();
+            for &node_idx in &frontier {
+                let played = self.play_to_node(&mut tree_board, node_idx);
+                let found = tree_board.zobrist_hash() == target
+                    && tree_board.available_cells().len() == free_cells;
+                for _ in 0..played {
+                    tree_board.unmake_move();
+                }
+                if found && self.nodes[node_idx].visits > 0 {
+                    return Some(node_idx);
+                }
+                next.extend_from_slice(&self.nodes[node_idx].children);
+            }
+            frontier = next;
         }
+        None
+    }
 
-        // Creamos un conjunto para visitados
-        let mut visited = std::collections::HashSet::new();
+    // Árbol nuevo formado por el subárbol de `node_idx`, que pasa a ser la raíz
+    // con `board` como tablero
+    fn into_subtree(self, node_idx: usize, board: &GameY) -> MCTSTree {
+        let mut nodes: Vec<MCTSNode> = Vec::new();
+        let mut queue = VecDeque::from([(node_idx, None)]);
 
-        // Probamos cada celda como posible inicio de grupo
-        for start_cell in &cells {
-            if visited.contains(start_cell) {
-                continue;
+        while let Some((old_idx, parent)) = queue.pop_front() {
+            let new_idx = nodes.len();
+            let mut node = self.nodes[old_idx].clone();
+            node.parent = parent;
+            for child in std::mem::take(&mut node.children) {
+                queue.push_back((child, Some(new_idx)));
             }
-
-            // BFS para encontrar el grupo conexo
-            let mut queue = std::collections::VecDeque::new();
-            queue.push_back(*start_cell);
-            visited.insert(*start_cell);
-
-            let mut group_touches_a = false;
-            let mut group_touches_b = false;
-            let mut group_touches_c = false;
-
-            while let Some(current) = queue.pop_front() {
-                // Actualizar lados tocados
-                if current.touches_side_a() { group_touches_a = true; }
-                if current.touches_side_b() { group_touches_b = true; }
-                if current.touches_side_c() { group_touches_c = true; }
-
-                // Buscar vecinos (solo entre las celdas del jugador)
-                for &other in &cells {
-                    if !visited.contains(&other) && board.manhattan_distance(current, other) == 1 {
-                        visited.insert(other);
-                        queue.push_back(other);
-                    }
-                }
+            nodes.push(node);
+            if let Some(parent) = parent {
+                nodes[parent].children.push(new_idx);
             }
+        }
+        nodes[0].move_coords = None;
+
+        MCTSTree {
+            nodes,
+            root: 0,
+            board: RefCell::new(board.clone().with_validation(MoveValidation::Permissive)),
+            rave_equivalence: self.rave_equivalence,
+            rng: self.rng,
+        }
+    }
 
-            // Si este grupo toca los tres lados, el jugador gana
-            if group_touches_a && group_touches_b && group_touches_c {
-                return true;
+    // Variante principal a partir de `node_idx` (sin incluir su propia jugada):
+    // se baja por la jugada ganadora demostrada o, si no la hay, por el hijo
+    // más visitado de cada nodo
+    fn variation_from(&self, mut node_idx: usize) -> Vec<Coordinates> {
+        let mut pv = Vec::new();
+        while let Some(child) = self.proven_win_child(node_idx).or_else(|| self.most_visited_child(node_idx)) {
+            match self.nodes[child].move_coords {
+                Some(coords) => pv.push(coords),
+                None => break,
             }
+            node_idx = child;
         }
+        pv
+    }
+}
+
+// ============================================================
+// PARALELISMO EN LA RAÍZ
+// ============================================================
+
+// Estadísticas de una jugada de la raíz sumadas entre los árboles de todos
+// los hilos. Se recuerda el árbol que más la visitó para sacar de él la PV,
+// salvo que otro la haya demostrado
+struct RootMoveStats {
+    coords: Coordinates,
+    visits: u32,
+    wins: f64,
+    tree: usize,
+    node: usize,
+    best_tree_visits: u32,
+    proven: Option<Proof>,
+}
 
-        false
+// Junta las jugadas de la raíz de cada árbol y las ordena: primero las
+// ganadoras demostradas, después de más a menos visitas y al final las
+// perdedoras demostradas
+fn merge_root_stats(trees: &[MCTSTree]) -> Vec<RootMoveStats> {
+    let mut merged: HashMap<Coordinates, RootMoveStats> = HashMap::new();
+
+    for (tree_idx, tree) in trees.iter().enumerate() {
+        for &child in &tree.nodes[tree.root].children {
+            let node = &tree.nodes[child];
+            let Some(coords) = node.move_coords else { continue };
+            let stats = merged.entry(coords).or_insert(RootMoveStats {
+                coords,
+                visits: 0,
+                wins: 0.0,
+                tree: tree_idx,
+                node: child,
+                best_tree_visits: 0,
+                proven: None,
+            });
+            stats.visits += node.visits;
+            stats.wins += node.wins;
+            if stats.proven.is_none() && (node.proven.is_some() || node.visits > stats.best_tree_visits) {
+                stats.best_tree_visits = node.visits;
+                stats.tree = tree_idx;
+                stats.node = child;
+                stats.proven = node.proven;
+            }
+        }
     }
+
+    let mut merged: Vec<RootMoveStats> = merged.into_values().collect();
+    // A igualdad de visitas se desempata por índice de celda, para que el
+    // resultado no dependa del orden del HashMap
+    let rank = |stats: &RootMoveStats| match stats.proven {
+        Some(Proof::Win) => 0,
+        None => 1,
+        Some(Proof::Loss) => 2,
+    };
+    merged.sort_by(|a, b| {
+        rank(a).cmp(&rank(b))
+            .then_with(|| b.visits.cmp(&a.visits))
+            .then_with(|| (a.coords.x(), a.coords.y()).cmp(&(b.coords.x(), b.coords.y())))
+    });
+    merged
 }
 
 // ============================================================
@@ -397,37 +681,171 @@ pub struct MonteCarloBot {
     difficulty: MonteCarloDifficulty,
     iterations: u32,
     time_limit_ms: u64,
+    // Se rinde si su probabilidad de ganar baja de este valor
+    resign_threshold: f64,
+    // Hilos de búsqueda, cada uno con su propio árbol
+    threads: usize,
+    // Si es true, los árboles de la última búsqueda se guardan para
+    // aprovecharlos en la siguiente
+    reuse_trees: bool,
+    last_trees: Mutex<Vec<MCTSTree>>,
+    // Equivalencia de RAVE, o None para usar UCB normal
+    rave_equivalence: Option<f64>,
+    // Con este número de casillas libres o menos se intenta resolver la
+    // posición con el solver antes de buscar
+    endgame_cells: Option<u32>,
+    // Da la semilla de cada árbol, para que las búsquedas se puedan repetir
+    rng: Mutex<StdRng>,
 }
 
 impl MonteCarloBot {
     pub fn new(difficulty: MonteCarloDifficulty) -> Self {
-        match difficulty {
-            MonteCarloDifficulty::Hard => Self {
-                difficulty,
-                iterations: HARD_ITERATIONS,
-                time_limit_ms: HARD_TIME_LIMIT_MS,
-            },
-            MonteCarloDifficulty::Extreme => Self {
-                difficulty,
-                iterations: EXTREME_ITERATIONS,
-                time_limit_ms: EXTREME_TIME_LIMIT_MS,
-            },
+        let (iterations, time_limit_ms, rave_equivalence) = match difficulty {
+            MonteCarloDifficulty::Hard => (HARD_ITERATIONS, HARD_TIME_LIMIT_MS, None),
+            MonteCarloDifficulty::Extreme => (EXTREME_ITERATIONS, EXTREME_TIME_LIMIT_MS, None),
+            MonteCarloDifficulty::Rave => (RAVE_ITERATIONS, RAVE_TIME_LIMIT_MS, Some(RAVE_EQUIVALENCE)),
+        };
+
+        Self {
+            difficulty,
+            iterations,
+            time_limit_ms,
+            resign_threshold: DEFAULT_RESIGN_THRESHOLD,
+            threads: default_threads(),
+            reuse_trees: true,
+            last_trees: Mutex::new(Vec::new()),
+            rave_equivalence,
+            endgame_cells: Some(DEFAULT_ENDGAME_CELLS),
+            rng: Mutex::new(StdRng::from_rng(&mut rand::rng())),
         }
     }
 
-    fn initialize_untried_moves(&self, node_idx: usize, tree: &mut MCTSTree, board: &GameY) {
-        if let Some(node) = tree.nodes.get_mut(node_idx) {
-            let available = board.available_cells();
-            let mut moves: Vec<Coordinates> = available.iter()
-                .map(|&idx| Coordinates::from_index(idx, board.board_size()))
-                .collect();
+    /// Fija la semilla de las jugadas aleatorias. Con la misma semilla y un
+    /// límite de nodos (no de tiempo), el bot repite las mismas búsquedas en
+    /// la misma secuencia de posiciones.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        *self.rng.get_mut().unwrap_or_else(|e| e.into_inner()) = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /// Cambia la probabilidad de ganar por debajo de la cual el bot se rinde.
+    /// Con 0 no se rinde nunca.
+    pub fn with_resign_threshold(mut self, resign_threshold: f64) -> Self {
+        self.resign_threshold = resign_threshold;
+        self
+    }
+
+    /// Cambia el número de hilos de búsqueda (al menos uno). Por defecto se
+    /// usa uno por núcleo, hasta cuatro. Cada hilo hace
+    /// hasta `iterations` iteraciones sobre su propio árbol dentro del mismo
+    /// límite de tiempo, y al final se suman las visitas de la raíz.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Número de hilos de búsqueda.
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Activa o desactiva la reutilización de árboles. Con ella activa (por
+    /// defecto), si la posición a buscar ya estaba en el árbol de la búsqueda
+    /// anterior (la misma posición, o la respuesta del rival a la jugada
+    /// prevista), ese subárbol pasa a ser la raíz en vez de empezar de cero.
+    pub fn with_tree_reuse(mut self, reuse_trees: bool) -> Self {
+        self.reuse_trees = reuse_trees;
+        self
+    }
+
+    /// Activa RAVE con la equivalencia indicada, o lo desactiva con `None`.
+    /// La equivalencia es el número de visitas de un nodo con el que su media
+    /// AMAF y su media normal pesan lo mismo en la selección: cuanto mayor,
+    /// más tiempo se fía el árbol de la AMAF.
+    pub fn with_rave(mut self, equivalence: Option<f64>) -> Self {
+        self.rave_equivalence = equivalence;
+        self.last_trees.get_mut().unwrap_or_else(|e| e.into_inner()).clear();
+        self
+    }
+
+    /// Resuelve con el solver de final las posiciones con `max_empty_cells`
+    /// casillas libres o menos antes de buscarlas; `None` lo desactiva. Si la
+    /// posición está ganada juega la jugada ganadora, y si está perdida busca
+    /// como siempre pero informa de la derrota.
+    pub fn with_endgame_solver(mut self, max_empty_cells: Option<u32>) -> Self {
+        self.endgame_cells = max_empty_cells;
+        self
+    }
+
+    // Árboles de partida para cada hilo: los subárboles reutilizables de la
+    // búsqueda anterior y, para el resto, árboles nuevos
+    fn starting_trees(&self, board: &GameY) -> Vec<MCTSTree> {
+        let previous = if self.reuse_trees {
+            std::mem::take(&mut *self.last_trees.lock().unwrap_or_else(|e| e.into_inner()))
+        } else {
+            Vec::new()
+        };
+
+        let mut trees: Vec<MCTSTree> = previous
+            .into_iter()
+            .filter_map(|tree| {
+                let node_idx = tree.find_position(board)?;
+                let tree = tree.into_subtree(node_idx, board);
+                *tree.rng.borrow_mut() = self.tree_rng();
+                Some(tree)
+            })
+            .take(self.threads)
+            .collect();
+        while trees.len() < self.threads {
+            trees.push(self.new_tree(board));
+        }
+        trees
+    }
+
+    fn new_tree(&self, board: &GameY) -> MCTSTree {
+        let mut tree = MCTSTree::new(board);
+        tree.rave_equivalence = self.rave_equivalence;
+        *tree.rng.borrow_mut() = self.tree_rng();
+        self.initialize_untried_moves(0, &mut tree, board);
+        if tree.rave_equivalence.is_some() {
+            tree.expand_all(0);
+        }
+        tree
+    }
 
-            let mut rng = rand::rng();
-            moves.shuffle(&mut rng);
+    // Un árbol completo de un hilo: itera hasta agotar sus iteraciones o el
+    // tiempo, o hasta demostrar el resultado de la raíz
+    fn grow_tree(&self, mut tree: MCTSTree, max_iterations: u64, time_limit_ms: u64, start: Instant) -> (MCTSTree, u64) {
+        let mut iterations = 0;
+        while iterations < max_iterations && tree.nodes[tree.root].proven.is_none() {
+            if start.elapsed().as_millis() > time_limit_ms as u128 {
+                break;
+            }
+            tree.iterate();
+            iterations += 1;
+        }
+        (tree, iterations)
+    }
 
-            node.untried_moves = moves;
+    fn initialize_untried_moves(&self, node_idx: usize, tree: &mut MCTSTree, board: &GameY) {
+        if let Some(node) = tree.nodes.get_mut(node_idx) {
+            MCTSTree::fill_untried_moves(node, board, &mut tree.rng.borrow_mut());
         }
     }
+
+    // Generador para un árbol, sacado del generador del bot
+    fn tree_rng(&self) -> StdRng {
+        let seed = self.rng.lock().unwrap_or_else(|e| e.into_inner()).random();
+        StdRng::seed_from_u64(seed)
+    }
+}
+
+// Por defecto se usa un hilo por núcleo disponible, hasta MAX_DEFAULT_THREADS
+fn default_threads() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(MAX_DEFAULT_THREADS)
 }
 
 impl YBot for MonteCarloBot {
@@ -435,31 +853,122 @@ impl YBot for MonteCarloBot {
         match self.difficulty {
             MonteCarloDifficulty::Hard => "monte_carlo_hard",
             MonteCarloDifficulty::Extreme => "monte_carlo_extreme",
+            MonteCarloDifficulty::Rave => "monte_carlo_rave",
         }
     }
 
     fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
-        let start = Instant::now();
-        let mut tree = MCTSTree::new(board);
+        self.search(board, &SearchLimits::default()).best_move
+    }
 
-        self.initialize_untried_moves(0, &mut tree, board);
+    // El límite de nodos se aplica al total de iteraciones de todos los hilos;
+    // MCTS no tiene profundidad
+    fn search(&self, board: &GameY, limits: &SearchLimits) -> SearchResult {
+        let start = Instant::now();
+        let time_limit_ms = limits.max_time_ms.unwrap_or(self.time_limit_ms);
+        let threads = self.threads as u64;
+
+        // FINAL DE PARTIDA: con pocas casillas libres se intenta demostrar el
+        // resultado antes de buscar. El solver tiene la mitad del tiempo
+        let mut proven_loss = false;
+        let mut solver_nodes = 0;
+        if let Some(max_empty_cells) = self.endgame_cells
+            && !board.check_game_over()
+            && board.available_cells().len() as u32 <= max_empty_cells
+        {
+            let solver_limits = SearchLimits::new()
+                .with_max_nodes(limits.max_nodes.unwrap_or(ENDGAME_SOLVER_NODES))
+                .with_max_time_ms(time_limit_ms / 2);
+            let solution = solve(board, &solver_limits);
+            solver_nodes = solution.nodes;
+            match solution.outcome {
+                SolveOutcome::Win => {
+                    return SearchResult {
+                        elapsed_ms: start.elapsed().as_millis() as u64,
+                        ..solution.to_search_result()
+                    };
+                }
+                SolveOutcome::Loss => proven_loss = true,
+                SolveOutcome::Unknown => {}
+            }
+        }
 
-        let mut iterations = 0;
+        let budget = |thread: u64| match limits.max_nodes {
+            Some(total) => total / threads + u64::from(thread < total % threads),
+            None => self.iterations as u64,
+        };
 
-        while iterations < self.iterations {
-            if start.elapsed().as_millis() > self.time_limit_ms as u128 {
-                println!("[MCTS] Límite alcanzado: {} ms, {}/{} iteraciones",
-                         start.elapsed().as_millis(), iterations, self.iterations);
-                break;
-            }
+        // PARALELISMO EN LA RAÍZ: cada hilo hace crecer un árbol independiente
+        let trees = self.starting_trees(board);
+        let results: Vec<(MCTSTree, u64)> = std::thread::scope(|scope| {
+            let handles: Vec<_> = trees
+                .into_iter()
+                .zip(0..threads)
+                .map(|(tree, thread)| {
+                    let max_iterations = budget(thread);
+                    scope.spawn(move || self.grow_tree(tree, max_iterations, time_limit_ms, start))
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().expect("MCTS thread panicked")).collect()
+        });
+
+        let iterations: u64 = results.iter().map(|(_, n)| n).sum();
+        let trees: Vec<MCTSTree> = results.into_iter().map(|(tree, _)| tree).collect();
+        tracing::debug!("[MCTS] {} iteraciones en {} hilos, {} ms",
+                        iterations, threads, start.elapsed().as_millis());
+
+        let merged = merge_root_stats(&trees);
+        // Las victorias de un nodo se apuntan al jugador que hizo su jugada,
+        // que en los hijos de la raíz es el propio bot
+        let best = merged.first().filter(|stats| stats.visits > 0);
+        let principal_variation = best
+            .map(|stats| {
+                let mut pv = vec![stats.coords];
+                pv.extend(trees[stats.tree].variation_from(stats.node));
+                pv
+            })
+            .unwrap_or_default();
+
+        let best_move = merged.first().map(|stats| stats.coords);
+        // La raíz ganada para el rival significa que todas las jugadas pierden
+        let lost = trees.iter().any(|tree| tree.nodes[tree.root].proven == Some(Proof::Win));
+        let win_probability = best.map(|stats| match stats.proven {
+            Some(Proof::Win) => 1.0,
+            _ if lost || proven_loss => 0.0,
+            _ => stats.wins / stats.visits as f64,
+        });
+
+        if self.reuse_trees {
+            *self.last_trees.lock().unwrap_or_else(|e| e.into_inner()) = trees;
+        }
 
-            tree.iterate();
-            iterations += 1;
+        SearchResult {
+            best_move,
+            score: None,
+            win_probability,
+            principal_variation,
+            depth: None,
+            nodes: iterations + solver_nodes,
+            elapsed_ms: start.elapsed().as_millis() as u64,
         }
+    }
 
-        println!("[MCTS] {} iteraciones en {} ms", iterations, start.elapsed().as_millis());
+    fn decide(&self, board: &GameY, limits: &SearchLimits) -> Option<BotDecision> {
+        decide_with_search(self, board, limits, self.time_limit_ms, self.resign_threshold)
+    }
 
-        tree.best_move()
+    // Copia con la misma configuración, sin árboles guardados y con un solo
+    // hilo: con varios, el reparto de iteraciones depende del planificador y
+    // la misma semilla podría dar otra jugada
+    fn reseeded(&self, seed: u64) -> Option<Arc<dyn YBot>> {
+        let bot = MonteCarloBot::new(self.difficulty)
+            .with_resign_threshold(self.resign_threshold)
+            .with_threads(1)
+            .with_tree_reuse(self.reuse_trees)
+            .with_rave(self.rave_equivalence)
+            .with_endgame_solver(self.endgame_cells)
+            .with_seed(seed);
+        Some(Arc::new(bot))
     }
 }
 
@@ -468,11 +977,16 @@ impl YBot for MonteCarloBot {
 mod tests {
     use super::*;
     use crate::{Coordinates, GameStatus, GameY, Movement, PlayerId, YBot};
-    use std::time::Duration;
-    use rand::Rng;
+
+    // Colores usados en las posiciones de prueba
+    const BOT_PLAYER_ID: u32 = 1;
+    const HUMAN_PLAYER_ID: u32 = 0;
+    
+    
 
     fn create_winning_game(size: u32, player_id: u32) -> GameY {
-        let mut game = GameY::new(size);
+        // Solo juega un jugador, así que la posición se construye en modo permisivo
+        let mut game = GameY::new(size).with_validation(MoveValidation::Permissive);
 
         match size {
             3 => {
@@ -573,7 +1087,7 @@ mod tests {
 
         let tree = MCTSTree::new(&game);
         assert_eq!(tree.nodes.len(), 1);
-        assert_eq!(tree.board.available_cells().len(), game.available_cells().len());
+        assert_eq!(tree.board.borrow().available_cells().len(), game.available_cells().len());
     }
 
     #[test]
@@ -674,94 +1188,48 @@ mod tests {
     }
 
     // ============================================================
-    // GRUPO 4: Check winner (12 tests)
+    // GRUPO 4: Rollouts completos (3 tests)
     // ============================================================
 
     #[test]
-    fn test_check_winner_no_win() {
-        let game = GameY::new(3);
+    fn test_rollout_on_large_board_always_has_winner() {
+        let game = GameY::new(11).with_validation(MoveValidation::Permissive);
         let tree = MCTSTree::new(&game);
-        assert!(!tree.check_winner(&game, BOT_PLAYER_ID));
-        assert!(!tree.check_winner(&game, HUMAN_PLAYER_ID));
-    }
+        let mut board = game.clone();
 
-    #[test]
-    fn test_check_winner_single_piece() {
-        let mut game = GameY::new(3);
-        game.add_move(Movement::Placement {
-            player: PlayerId::new(0),
-            coords: Coordinates::new(2, 0, 0),
-        }).unwrap();
+        let mut wins = 0;
+        let runs = 200;
+        for _ in 0..runs {
+            let result = tree.simulate(&mut board, PlayerId::new(0));
+            assert!(result == WIN_SCORE || result == LOSE_SCORE);
+            if result == WIN_SCORE {
+                wins += 1;
+            }
+        }
 
-        let tree = MCTSTree::new(&game);
-        assert!(!tree.check_winner(&game, HUMAN_PLAYER_ID));
+        // Sin corte por profundidad ganan los dos jugadores alguna vez
+        assert!(wins > 0 && wins < runs, "wins: {}, runs: {}", wins, runs);
+        assert_eq!(board.zobrist_hash(), game.zobrist_hash());
     }
 
     #[test]
-    fn test_check_winner_winning_path_size3() {
+    fn test_rollout_from_finished_game() {
         let game = create_winning_game(3, 0);
         let tree = MCTSTree::new(&game);
-        assert!(tree.check_winner(&game, HUMAN_PLAYER_ID));
-    }
-
-
-    #[test]
-    fn test_check_winner_disconnected_pieces() {
-        let mut game = GameY::new(4);
-        // Place pieces on all three sides but not connected
-        game.add_move(Movement::Placement {
-            player: PlayerId::new(0),
-            coords: Coordinates::new(3, 0, 0), // A
-        }).unwrap();
-        game.add_move(Movement::Placement {
-            player: PlayerId::new(0),
-            coords: Coordinates::new(0, 3, 0), // B
-        }).unwrap();
-        game.add_move(Movement::Placement {
-            player: PlayerId::new(0),
-            coords: Coordinates::new(0, 0, 3), // C
-        }).unwrap();
 
-        let tree = MCTSTree::new(&game);
-        assert!(!tree.check_winner(&game, HUMAN_PLAYER_ID));
+        assert_eq!(tree.simulate(&mut game.clone(), PlayerId::new(0)), WIN_SCORE);
+        assert_eq!(tree.simulate(&mut game.clone(), PlayerId::new(1)), LOSE_SCORE);
     }
 
     #[test]
-    fn test_check_winner_opponent_pieces_ignored() {
-        let mut game = GameY::new(3);
-        // Winning path for player 0
-        for coords in [(0,2,0), (1,1,0), (0,1,1), (0,0,2)] {
-            game.add_move(Movement::Placement {
-                player: PlayerId::new(0),
-                coords: Coordinates::new(coords.0, coords.1, coords.2),
-            }).unwrap();
-        }
-
+    fn test_rollout_restores_game_status() {
+        let mut game = GameY::new(9).with_validation(MoveValidation::Permissive);
         let tree = MCTSTree::new(&game);
-        assert!(!tree.check_winner(&game, BOT_PLAYER_ID)); // Bot should not win
-    }
-
 
-    // Additional tests for check_winner to reach 12 total
-    #[test]
-    fn test_check_winner_empty_board() {
-        let game = GameY::new(5);
-        let tree = MCTSTree::new(&game);
-        assert!(!tree.check_winner(&game, BOT_PLAYER_ID));
-        assert!(!tree.check_winner(&game, HUMAN_PLAYER_ID));
-    }
+        tree.simulate(&mut game, PlayerId::new(0));
 
-    #[test]
-    fn test_check_winner_few_pieces() {
-        let mut game = GameY::new(5);
-        for i in 0..2 {
-            game.add_move(Movement::Placement {
-                player: PlayerId::new(0),
-                coords: Coordinates::new(4-i, i, 0),
-            }).unwrap();
-        }
-        let tree = MCTSTree::new(&game);
-        assert!(!tree.check_winner(&game, HUMAN_PLAYER_ID));
+        assert!(!game.check_game_over());
+        assert!(game.history().is_empty());
     }
 
     // ============================================================
@@ -905,21 +1373,21 @@ mod tests {
     fn test_simulation_empty_board() {
         let game = GameY::new(3);
         let tree = MCTSTree::new(&game);
-        let result = tree.simulate(game.clone(), PlayerId::new(HUMAN_PLAYER_ID));
-        assert!(result == WIN_SCORE || result == LOSE_SCORE || result == DRAW_SCORE);
+        let result = tree.simulate(&mut game.clone(), PlayerId::new(HUMAN_PLAYER_ID));
+        assert!(result == WIN_SCORE || result == LOSE_SCORE);
     }
 
     #[test]
-    fn test_simulation_max_depth() {
+    fn test_simulation_size4() {
         let game = GameY::new(4);
         let tree = MCTSTree::new(&game);
-        let result = tree.simulate(game, PlayerId::new(BOT_PLAYER_ID));
-        assert!(result == WIN_SCORE || result == LOSE_SCORE || result == DRAW_SCORE);
+        let result = tree.simulate(&mut game.clone(), PlayerId::new(BOT_PLAYER_ID));
+        assert!(result == WIN_SCORE || result == LOSE_SCORE);
     }
 
     #[test]
     fn test_simulation_immediate_win() {
-        let mut game = GameY::new(3);
+        let mut game = GameY::new(3).with_validation(MoveValidation::Permissive);
 
         // Colocamos 3 piezas que forman un camino casi ganador para player 0
         // Necesitan una pieza más para ganar
@@ -939,11 +1407,10 @@ mod tests {
         let tree = MCTSTree::new(&game);
 
         // Simulamos desde el turno de player 0, deberían ganar
-        let result = tree.simulate(game, PlayerId::new(0));
+        let result = tree.simulate(&mut game.clone(), PlayerId::new(0));
 
-        // Puede ser WIN_SCORE o DRAW_SCORE dependiendo de la simulación
-        // Pero no debería ser LOSE_SCORE
-        assert_ne!(result, LOSE_SCORE, "Player should not lose from winning position");
+        // Cualquier casilla que quede conecta los tres lados
+        assert_eq!(result, WIN_SCORE, "Player should win from winning position");
     }
 
     #[test]
@@ -958,8 +1425,9 @@ mod tests {
         }
 
         let tree = MCTSTree::new(&game);
-        let result = tree.simulate(game, PlayerId::new(0));
-        assert_eq!(result, DRAW_SCORE);
+        // El jugador 0 ya ha ganado con la tercera piedra
+        assert_eq!(tree.simulate(&mut game.clone(), PlayerId::new(0)), WIN_SCORE);
+        assert_eq!(tree.simulate(&mut game.clone(), PlayerId::new(1)), LOSE_SCORE);
     }
 
     #[test]
@@ -968,14 +1436,14 @@ mod tests {
         let tree = MCTSTree::new(&game);
 
         // Simulate from human's turn
-        let result_human = tree.simulate(game.clone(), PlayerId::new(HUMAN_PLAYER_ID));
+        let result_human = tree.simulate(&mut game.clone(), PlayerId::new(HUMAN_PLAYER_ID));
 
         // Simulate from bot's turn
-        let result_bot = tree.simulate(game, PlayerId::new(BOT_PLAYER_ID));
+        let result_bot = tree.simulate(&mut game.clone(), PlayerId::new(BOT_PLAYER_ID));
 
         // Ambos resultados deben ser válidos
-        assert!(result_human == WIN_SCORE || result_human == LOSE_SCORE || result_human == DRAW_SCORE);
-        assert!(result_bot == WIN_SCORE || result_bot == LOSE_SCORE || result_bot == DRAW_SCORE);
+        assert!(result_human == WIN_SCORE || result_human == LOSE_SCORE);
+        assert!(result_bot == WIN_SCORE || result_bot == LOSE_SCORE);
 
         // No hay garantía de que sean diferentes, solo verificamos que sean válidos
     }
@@ -987,8 +1455,8 @@ mod tests {
 
         // Run multiple simulations and check that they complete
         for _ in 0..10 {
-            let result = tree.simulate(game.clone(), PlayerId::new(HUMAN_PLAYER_ID));
-            assert!(result == WIN_SCORE || result == LOSE_SCORE || result == DRAW_SCORE);
+            let result = tree.simulate(&mut game.clone(), PlayerId::new(HUMAN_PLAYER_ID));
+            assert!(result == WIN_SCORE || result == LOSE_SCORE);
         }
     }
 
@@ -998,8 +1466,8 @@ mod tests {
         let tree = MCTSTree::new(&game);
 
         // This should terminate (not hang)
-        let result = tree.simulate(game, PlayerId::new(0));
-        assert!(result == WIN_SCORE || result == LOSE_SCORE || result == DRAW_SCORE);
+        let result = tree.simulate(&mut game.clone(), PlayerId::new(0));
+        assert!(result == WIN_SCORE || result == LOSE_SCORE);
     }
 
     #[test]
@@ -1007,12 +1475,41 @@ mod tests {
         let game = GameY::new(3);
         let tree = MCTSTree::new(&game);
 
-        let result0 = tree.simulate(game.clone(), PlayerId::new(0));
-        let result1 = tree.simulate(game.clone(), PlayerId::new(1));
+        let result0 = tree.simulate(&mut game.clone(), PlayerId::new(0));
+        let result1 = tree.simulate(&mut game.clone(), PlayerId::new(1));
 
         // Should both be valid results
-        assert!(result0 == WIN_SCORE || result0 == LOSE_SCORE || result0 == DRAW_SCORE);
-        assert!(result1 == WIN_SCORE || result1 == LOSE_SCORE || result1 == DRAW_SCORE);
+        assert!(result0 == WIN_SCORE || result0 == LOSE_SCORE);
+        assert!(result1 == WIN_SCORE || result1 == LOSE_SCORE);
+    }
+
+    #[test]
+    fn test_simulation_restores_board() {
+        let game = GameY::new(5).with_validation(MoveValidation::Permissive);
+        let tree = MCTSTree::new(&game);
+        let mut board = game.clone();
+
+        tree.simulate(&mut board, PlayerId::new(0));
+
+        assert_eq!(board.zobrist_hash(), game.zobrist_hash());
+        assert_eq!(board.available_cells().len(), game.available_cells().len());
+        assert_eq!(board.history().len(), 0);
+    }
+
+    #[test]
+    fn test_iterate_restores_root_board() {
+        let game = GameY::new(4);
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard);
+        let mut tree = MCTSTree::new(&game);
+        bot.initialize_untried_moves(0, &mut tree, &game);
+
+        for _ in 0..20 {
+            tree.iterate();
+        }
+
+        let board = tree.board.borrow();
+        assert_eq!(board.zobrist_hash(), game.zobrist_hash());
+        assert_eq!(board.available_cells().len(), game.available_cells().len());
     }
 
     // ============================================================
@@ -1147,12 +1644,11 @@ mod tests {
     #[test]
     fn test_expand_new_node() {
         let mut tree = MCTSTree::new(&GameY::new(3));
-        let board = GameY::new(3);
 
         // Add untried moves to root
         tree.nodes[0].untried_moves = vec![Coordinates::new(2,0,0)];
 
-        let new_idx = tree.expand(0, &board);
+        let new_idx = tree.expand(0);
 
         assert_eq!(tree.nodes.len(), 2);
         assert_eq!(new_idx, 1);
@@ -1165,11 +1661,10 @@ mod tests {
     #[test]
     fn test_expand_with_no_untried_moves() {
         let mut tree = MCTSTree::new(&GameY::new(3));
-        let board = GameY::new(3);
 
         tree.nodes[0].untried_moves.clear();
 
-        let result = tree.expand(0, &board);
+        let result = tree.expand(0);
         assert_eq!(result, 0);
         assert_eq!(tree.nodes.len(), 1);
     }
@@ -1177,7 +1672,6 @@ mod tests {
     #[test]
     fn test_expand_multiple_children() {
         let mut tree = MCTSTree::new(&GameY::new(3));
-        let board = GameY::new(3);
 
         tree.nodes[0].untried_moves = vec![
             Coordinates::new(2,0,0),
@@ -1186,17 +1680,17 @@ mod tests {
         ];
 
         // Expand first
-        let idx1 = tree.expand(0, &board);
+        let idx1 = tree.expand(0);
         assert_eq!(idx1, 1);
         assert_eq!(tree.nodes[0].untried_moves.len(), 2);
 
         // Expand second
-        let idx2 = tree.expand(0, &board);
+        let idx2 = tree.expand(0);
         assert_eq!(idx2, 2);
         assert_eq!(tree.nodes[0].untried_moves.len(), 1);
 
         // Expand third
-        let idx3 = tree.expand(0, &board);
+        let idx3 = tree.expand(0);
         assert_eq!(idx3, 3);
         assert!(tree.nodes[0].untried_moves.is_empty());
 
@@ -1206,25 +1700,23 @@ mod tests {
     #[test]
     fn test_expand_correct_player_alternation() {
         let mut tree = MCTSTree::new(&GameY::new(3));
-        let board = GameY::new(3);
 
         // Root is human
         tree.nodes[0].player = PlayerId::new(HUMAN_PLAYER_ID);
         tree.nodes[0].untried_moves = vec![Coordinates::new(2,0,0)];
 
-        let idx = tree.expand(0, &board);
+        let idx = tree.expand(0);
         assert_eq!(tree.nodes[idx].player.id(), BOT_PLAYER_ID);
 
         // Expand again from bot
         tree.nodes[idx].untried_moves = vec![Coordinates::new(1,1,0)];
-        let idx2 = tree.expand(idx, &board);
+        let idx2 = tree.expand(idx);
         assert_eq!(tree.nodes[idx2].player.id(), HUMAN_PLAYER_ID);
     }
 
     #[test]
     fn test_expand_preserves_move_order() {
         let mut tree = MCTSTree::new(&GameY::new(3));
-        let board = GameY::new(3);
 
         let moves = vec![
             Coordinates::new(2,0,0),
@@ -1235,7 +1727,7 @@ mod tests {
 
         // Expand all in order
         for expected_move in moves {
-            let idx = tree.expand(0, &board);
+            let idx = tree.expand(0);
             assert_eq!(tree.nodes[idx].move_coords, Some(expected_move));
         }
     }
@@ -1243,7 +1735,6 @@ mod tests {
     #[test]
     fn test_expand_with_different_parents() {
         let mut tree = MCTSTree::new(&GameY::new(3));
-        let board = GameY::new(3);
 
         // Root with two untried moves
         tree.nodes[0].untried_moves = vec![
@@ -1252,13 +1743,13 @@ mod tests {
         ];
 
         // Expand first child
-        let child1 = tree.expand(0, &board);
+        let child1 = tree.expand(0);
 
         // Give child its own untried move
         tree.nodes[child1].untried_moves = vec![Coordinates::new(0,1,1)];
 
         // Expand from child
-        let child2 = tree.expand(child1, &board);
+        let child2 = tree.expand(child1);
 
         assert_eq!(tree.nodes[child2].parent, Some(child1));
         assert_eq!(tree.nodes[child1].children, vec![child2]);
@@ -1271,7 +1762,7 @@ mod tests {
     #[test]
     fn test_play_to_node_root() {
         let game = GameY::new(3);
-        let mut tree = MCTSTree::new(&game);
+        let tree = MCTSTree::new(&game);
         let mut board = game.clone();
 
         tree.play_to_node(&mut board, 0);
@@ -1280,7 +1771,7 @@ mod tests {
 
     #[test]
     fn test_play_to_node_child() {
-        let mut game = GameY::new(3);
+        let game = GameY::new(3).with_validation(MoveValidation::Permissive);
         let mut tree = MCTSTree::new(&game);
 
         // Add a child node
@@ -1298,7 +1789,7 @@ mod tests {
 
     #[test]
     fn test_play_to_node_deep_path() {
-        let mut game = GameY::new(3);
+        let game = GameY::new(3).with_validation(MoveValidation::Permissive);
         let mut tree = MCTSTree::new(&game);
 
         // Build path: root -> child1 -> child2
@@ -1322,7 +1813,7 @@ mod tests {
 
     #[test]
     fn test_play_to_node_multiple_branches() {
-        let mut game = GameY::new(3);
+        let game = GameY::new(3).with_validation(MoveValidation::Permissive);
         let mut tree = MCTSTree::new(&game);
 
         // Root with two children
@@ -1358,8 +1849,9 @@ mod tests {
         let mut tree = MCTSTree::new(&game);
 
         // Add a child node with another move
+        // (the bot moves here, so the human is to move in the child)
         let coords2 = Coordinates::new(1,1,0);
-        let child = MCTSNode::new(Some(coords2), Some(0), PlayerId::new(BOT_PLAYER_ID));
+        let child = MCTSNode::new(Some(coords2), Some(0), PlayerId::new(HUMAN_PLAYER_ID));
         tree.nodes.push(child);
         tree.nodes[0].children.push(1);
 
@@ -1598,6 +2090,40 @@ mod tests {
         assert_ne!(coords, Coordinates::new(2,0,0));
     }
 
+    #[test]
+    fn test_choose_move_plays_either_colour() {
+        // Same position with the colours swapped: the player to move has
+        // (2,0,0) and (1,0,1) and wins at (0,0,2) or (0,1,1)
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard);
+        for (turn, layout) in [(0, "B/BR/..R"), (1, "R/RB/..B")] {
+            let yen = crate::YEN::new(3, turn, vec!['B', 'R'], layout.to_string());
+            let mut game = GameY::try_from(yen).unwrap();
+
+            let coords = bot.choose_move(&game).unwrap();
+            game.add_move(Movement::Placement {
+                player: PlayerId::new(turn),
+                coords,
+            }).unwrap();
+
+            assert_eq!(*game.status(), GameStatus::Finished { winner: PlayerId::new(turn) });
+        }
+    }
+
+    #[test]
+    fn test_backpropagate_credits_the_mover() {
+        let mut tree = MCTSTree::new(&GameY::new(3));
+        let root_player = tree.nodes[0].player;
+        let child = MCTSNode::new(Some(Coordinates::new(2,0,0)), Some(0), root_player.opponent());
+        tree.nodes.push(child);
+        tree.nodes[0].children.push(1);
+
+        // The player to move in the child loses, so the root player's move wins
+        tree.backpropagate(1, LOSE_SCORE);
+
+        assert_eq!(tree.nodes[1].wins, WIN_SCORE);
+        assert_eq!(tree.nodes[0].wins, LOSE_SCORE);
+    }
+
     #[test]
     fn test_choose_move_full_board() {
         let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard);
@@ -1668,16 +2194,17 @@ mod tests {
         let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard);
         let game = GameY::new(3);
 
-        // Run multiple times to see if it returns different moves
-        let mut moves = std::collections::HashSet::new();
-        for _ in 0..5 {
-            if let Some(coords) = bot.choose_move(&game) {
-                moves.insert(coords);
-            }
+        // On the size 3 board the strong openings are the three cells that
+        // touch a single side, not the corners
+        let inner = [
+            Coordinates::new(1, 1, 0),
+            Coordinates::new(1, 0, 1),
+            Coordinates::new(0, 1, 1),
+        ];
+        for _ in 0..3 {
+            let coords = bot.choose_move(&game).unwrap();
+            assert!(inner.contains(&coords), "unexpected opening {:?}", coords);
         }
-
-        // Should explore different moves (not always the same)
-        assert!(moves.len() > 1);
     }
 
     // ============================================================
@@ -1736,6 +2263,7 @@ mod tests {
 
         // If they're exactly the same, it's possible but unlikely
         // We'll accept either outcome
+        let _ = same;
     }
 
     #[test]
@@ -1816,13 +2344,12 @@ mod tests {
     }
 
     #[test]
-    fn test_tree_with_max_depth_simulation() {
+    fn test_tree_simulation_size5() {
         let game = GameY::new(5);
-        let mut tree = MCTSTree::new(&game);
+        let tree = MCTSTree::new(&game);
 
-        // Force simulation to reach max depth
-        let result = tree.simulate(game, PlayerId::new(0));
-        assert!(result == WIN_SCORE || result == LOSE_SCORE || result == DRAW_SCORE);
+        let result = tree.simulate(&mut game.clone(), PlayerId::new(0));
+        assert!(result == WIN_SCORE || result == LOSE_SCORE);
     }
 
     #[test]
@@ -1864,7 +2391,7 @@ mod tests {
         tree.nodes[0].untried_moves = vec![];
 
         let selected = tree.select();
-        assert!(selected >= 1 && selected <= 3);
+        assert!((1..=3).contains(&selected));
     }
 
     #[test]
@@ -1873,7 +2400,7 @@ mod tests {
         // Fill board
         for i in 0..3 {
             game.add_move(Movement::Placement {
-                player: PlayerId::new((i % 2) as u32),
+                player: PlayerId::new(i % 2),
                 coords: Coordinates::from_index(i, 2),
             }).unwrap();
         }
@@ -1881,15 +2408,15 @@ mod tests {
         let mut tree = MCTSTree::new(&game);
         tree.nodes[0].untried_moves = vec![]; // No moves
 
-        let result = tree.expand(0, &game);
+        let result = tree.expand(0);
         assert_eq!(result, 0);
     }
 
     #[test]
     fn test_heuristic_random_move_empty_available() {
         let game = GameY::new(3);
-        let tree = MCTSTree::new(&game);
-        let available: Vec<u32> = vec![];
+        let _tree = MCTSTree::new(&game);
+        let _available: Vec<u32> = vec![];
 
         // This should panic or handle gracefully - our implementation assumes non-empty
         // We'll skip this or expect a panic
@@ -1904,8 +2431,8 @@ mod tests {
 
         impl TinyTimeBot {
             fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
-                let start = Instant::now();
-                let mut tree = MCTSTree::new(board);
+                let _start = Instant::now();
+                let tree = MCTSTree::new(board);
                 // Don't initialize moves, just return best move (will be None)
                 tree.best_move()
             }
@@ -1916,11 +2443,16 @@ mod tests {
 
         // This should return None or Some quickly
         let _ = bot.choose_move(&game);
+
+        // A tree that was never expanded has no move to suggest
+        let tiny = TinyTimeBot { bot };
+        assert!(tiny.choose_move(&game).is_none());
+        let _ = tiny.bot.choose_move(&game);
     }
 
     #[test]
     fn test_game_over_during_simulation() {
-        let mut game = GameY::new(3);
+        let mut game = GameY::new(3).with_validation(MoveValidation::Permissive);
         // Create a winning position for player 0
         for coords in [(0,2,0), (1,1,0), (0,1,1), (0,0,2)] {
             game.add_move(Movement::Placement {
@@ -1932,7 +2464,7 @@ mod tests {
         let tree = MCTSTree::new(&game);
 
         // Simulate from player 1's perspective
-        let result = tree.simulate(game, PlayerId::new(1));
+        let result = tree.simulate(&mut game.clone(), PlayerId::new(1));
         assert_eq!(result, LOSE_SCORE); // Player 1 should lose
     }
 
@@ -1941,6 +2473,7 @@ mod tests {
     // ============================================================
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_full_game_hard_difficulty() {
         let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard);
         let mut game = GameY::new(3);
@@ -1949,7 +2482,7 @@ mod tests {
         for _ in 0..3 {
             if let Some(coords) = bot.choose_move(&game) {
                 game.add_move(Movement::Placement {
-                    player: PlayerId::new(1), // Bot is player 1
+                    player: game.next_player().unwrap(),
                     coords,
                 }).unwrap();
             }
@@ -2021,7 +2554,7 @@ mod tests {
     #[test]
     fn test_mcts_with_swap_action() {
         let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard);
-        let mut game = GameY::new(3);
+        let game = GameY::new(3);
 
         // Bot doesn't handle swap actions, only placements
         // So just verify it works with normal play
@@ -2079,7 +2612,7 @@ mod tests {
 
     #[test]
     fn test_mcts_avoids_losing_move() {
-        let mut game = GameY::new(3);
+        let game = GameY::new(3);
         // Set up position where if bot plays at (2,0,0), opponent wins next move
         // This is a more complex scenario - just verify it doesn't pick an obviously bad move
 
@@ -2112,7 +2645,7 @@ mod tests {
 
     #[test]
     fn test_find_via_union() {
-        let mut game = GameY::new(3);
+        let game = GameY::new(3);
         let mut tree = MCTSTree::new(&game);
 
         // This indirectly tests find through union in backpropagation
@@ -2140,12 +2673,12 @@ mod tests {
         tree.nodes[0].untried_moves = vec![Coordinates::new(2,0,0)];
 
         // Expand should create child with bot player
-        let child_idx = tree.expand(0, &game);
+        let child_idx = tree.expand(0);
         assert_eq!(tree.nodes[child_idx].player.id(), BOT_PLAYER_ID);
 
         // Expand again from bot should create human
         tree.nodes[child_idx].untried_moves = vec![Coordinates::new(1,1,0)];
-        let grandchild_idx = tree.expand(child_idx, &game);
+        let grandchild_idx = tree.expand(child_idx);
         assert_eq!(tree.nodes[grandchild_idx].player.id(), HUMAN_PLAYER_ID);
     }
 
@@ -2170,8 +2703,8 @@ mod tests {
         let node_a = MCTSNode::new(Some(coords_a), None, PlayerId::new(BOT_PLAYER_ID));
         tree.nodes.push(node_a);
 
-        // We can't directly check touches_side_a, but we can see it in check_winner
-        // This is tested in the check_winner tests
+        // We can't directly check touches_side_a here; the engine tests cover
+        // win detection
     }
 
     #[test]
@@ -2243,6 +2776,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_mcts_memory_usage() {
         // This test checks that MCTS doesn't use excessive memory
         let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard);
@@ -2256,4 +2790,717 @@ mod tests {
         // If we get here without OOM, test passes
         assert!(true);
     }
-}
\ No newline at end of file
+
+    // ============================================================
+    // GRUPO 19: Búsqueda con límites (4 tests)
+    // ============================================================
+
+    #[test]
+    fn test_search_respects_iteration_limit() {
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard);
+        let game = GameY::new(5);
+
+        let result = bot.search(&game, &SearchLimits::new().with_max_nodes(200));
+
+        assert!(result.best_move.is_some());
+        assert_eq!(result.nodes, 200);
+        assert_eq!(result.depth, None);
+    }
+
+    #[test]
+    fn test_search_respects_time_limit() {
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Extreme);
+        let game = GameY::new(9);
+
+        let result = bot.search(&game, &SearchLimits::new().with_max_time_ms(50));
+
+        assert!(result.best_move.is_some());
+        assert!(result.elapsed_ms < 1000);
+        assert!(result.nodes < EXTREME_ITERATIONS as u64);
+    }
+
+    #[test]
+    fn test_search_reports_win_probability_and_pv() {
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard);
+        let mut game = GameY::new(4);
+
+        let result = bot.search(&game, &SearchLimits::new().with_max_nodes(2000));
+
+        let p = result.win_probability.unwrap();
+        assert!((0.0..=1.0).contains(&p));
+        assert_eq!(result.principal_variation.first(), result.best_move.as_ref());
+
+        // La variante principal se puede jugar tal cual
+        for coords in result.principal_variation {
+            let player = game.next_player().unwrap();
+            game.add_move(Movement::Placement { player, coords }).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_search_sees_immediate_win() {
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard);
+        // B juega (0,0,2) y conecta los tres lados
+        let game = GameY::try_from(crate::YEN::new(3, 0, vec!['B', 'R'], "B/BR/.R.".to_string())).unwrap();
+
+        let result = bot.search(&game, &SearchLimits::new().with_max_nodes(3000));
+
+        assert_eq!(result.best_move, Some(Coordinates::new(0, 0, 2)));
+        assert!(result.win_probability.unwrap() > 0.5);
+    }
+
+    // ============================================================
+    // GRUPO 20: Cambio y rendición (3 tests)
+    // ============================================================
+
+    // Posición perdida para BOT: el rival gana con cualquier casilla de la última fila
+    fn lost_position() -> GameY {
+        let mut game = GameY::new(3).with_validation(crate::MoveValidation::Permissive);
+        for (coords, player) in [
+            (Coordinates::new(2, 0, 0), HUMAN_PLAYER_ID),
+            (Coordinates::new(1, 1, 0), HUMAN_PLAYER_ID),
+            (Coordinates::new(1, 0, 1), HUMAN_PLAYER_ID),
+        ] {
+            game.add_move(Movement::Placement { player: PlayerId::new(player), coords }).unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn test_decide_resigns_lost_position() {
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard);
+        let decision = bot.decide(&lost_position(), &SearchLimits::new().with_max_nodes(500)).unwrap();
+
+        assert_eq!(decision.action(), Some(&crate::GameAction::Resign));
+    }
+
+    #[test]
+    fn test_decide_with_zero_threshold_keeps_playing() {
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard).with_resign_threshold(0.0);
+        let decision = bot.decide(&lost_position(), &SearchLimits::new().with_max_nodes(500)).unwrap();
+
+        assert!(matches!(decision.movement, Movement::Placement { .. }));
+    }
+
+    #[test]
+    fn test_decide_after_opening_is_playable() {
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard);
+        let mut game = GameY::new(5);
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::new(2, 1, 1),
+        }).unwrap();
+
+        let decision = bot.decide(&game, &SearchLimits::new().with_max_nodes(2000)).unwrap();
+        assert_ne!(decision.action(), Some(&crate::GameAction::Resign));
+        game.add_move(decision.movement).unwrap();
+    }
+
+    // ============================================================
+    // GRUPO 21: Búsqueda en paralelo (5 tests)
+    // ============================================================
+
+    #[test]
+    fn test_threads_default_and_builder() {
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard);
+        assert!(bot.threads() >= 1);
+        assert!(bot.threads() <= MAX_DEFAULT_THREADS);
+
+        assert_eq!(bot.with_threads(4).threads(), 4);
+        assert_eq!(MonteCarloBot::new(MonteCarloDifficulty::Hard).with_threads(0).threads(), 1);
+    }
+
+    #[test]
+    fn test_parallel_search_splits_iteration_limit() {
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard).with_threads(3);
+        let game = GameY::new(5);
+
+        let result = bot.search(&game, &SearchLimits::new().with_max_nodes(301));
+
+        assert_eq!(result.nodes, 301);
+        let coords = result.best_move.unwrap();
+        assert!(game.available_cells().contains(&coords.to_index(5)));
+    }
+
+    #[test]
+    fn test_parallel_search_finds_immediate_win() {
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard).with_threads(4);
+        let game = GameY::try_from(crate::YEN::new(3, 0, vec!['B', 'R'], "B/BR/.R.".to_string())).unwrap();
+
+        let result = bot.search(&game, &SearchLimits::new().with_max_nodes(2000));
+
+        assert_eq!(result.best_move, Some(Coordinates::new(0, 0, 2)));
+        assert_eq!(result.principal_variation.first(), result.best_move.as_ref());
+    }
+
+    #[test]
+    fn test_parallel_search_respects_time_limit() {
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Extreme).with_threads(4);
+        let game = GameY::new(9);
+
+        let start = Instant::now();
+        let result = bot.search(&game, &SearchLimits::new().with_max_time_ms(100));
+
+        assert!(result.best_move.is_some());
+        assert!(start.elapsed().as_millis() < 1500);
+    }
+
+    #[test]
+    fn test_merge_root_stats_sums_visits() {
+        let game = GameY::new(3);
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard);
+        let trees: Vec<MCTSTree> = (0..2)
+            .map(|_| bot.grow_tree(bot.new_tree(&game), 300, 10_000, Instant::now()).0)
+            .collect();
+
+        let merged = merge_root_stats(&trees);
+
+        let total: u32 = merged.iter().map(|s| s.visits).sum();
+        let expected: u32 = trees
+            .iter()
+            .flat_map(|t| t.nodes[t.root].children.iter().map(|&c| t.nodes[c].visits))
+            .sum();
+        assert_eq!(total, expected);
+        assert_eq!(merged.len(), game.available_cells().len());
+        assert!(merged.windows(2).all(|w| w[0].visits >= w[1].visits));
+    }
+
+    // ============================================================
+    // GRUPO 22: Reutilización del árbol (6 tests)
+    // ============================================================
+
+    // Aplica a `game` la jugada más visitada de la raíz y la respuesta más
+    // visitada a esa jugada, y devuelve el nodo de esa respuesta
+    fn play_expected_line(tree: &MCTSTree, game: &mut GameY) -> usize {
+        let mut node_idx = tree.root;
+        for _ in 0..2 {
+            node_idx = tree.most_visited_child(node_idx).unwrap();
+            let player = game.next_player().unwrap();
+            let coords = tree.nodes[node_idx].move_coords.unwrap();
+            game.add_move(Movement::Placement { player, coords }).unwrap();
+        }
+        node_idx
+    }
+
+    #[test]
+    fn test_tree_grows_below_root_children() {
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard);
+        let (tree, _) = bot.grow_tree(bot.new_tree(&GameY::new(4)), 2000, 10_000, Instant::now());
+
+        let grandchildren = tree.nodes[tree.root].children
+            .iter()
+            .map(|&c| tree.nodes[c].children.len())
+            .sum::<usize>();
+        assert!(grandchildren > 0);
+    }
+
+    #[test]
+    fn test_find_position_after_reply() {
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard);
+        let mut game = GameY::new(4);
+        let (tree, _) = bot.grow_tree(bot.new_tree(&game), 3000, 10_000, Instant::now());
+
+        assert_eq!(tree.find_position(&game), Some(tree.root));
+
+        let reply = play_expected_line(&tree, &mut game);
+        assert_eq!(tree.find_position(&game), Some(reply));
+
+        // Una posición que no sale del árbol no se encuentra
+        assert_eq!(tree.find_position(&GameY::new(5)), None);
+    }
+
+    #[test]
+    fn test_into_subtree_keeps_statistics() {
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard);
+        let mut game = GameY::new(4);
+        let (tree, _) = bot.grow_tree(bot.new_tree(&game), 3000, 10_000, Instant::now());
+        let reply = play_expected_line(&tree, &mut game);
+        let visits = tree.nodes[reply].visits;
+        let children = tree.nodes[reply].children.len();
+
+        let subtree = tree.into_subtree(reply, &game);
+
+        let root = &subtree.nodes[subtree.root];
+        assert_eq!(root.visits, visits);
+        assert_eq!(root.children.len(), children);
+        assert_eq!(root.parent, None);
+        assert_eq!(root.move_coords, None);
+        assert_eq!(Some(root.player), game.next_player());
+        for (idx, node) in subtree.nodes.iter().enumerate() {
+            for &child in &node.children {
+                assert_eq!(subtree.nodes[child].parent, Some(idx));
+            }
+        }
+    }
+
+    #[test]
+    fn test_search_reuses_tree_after_reply() {
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard).with_threads(1);
+        let mut game = GameY::new(4);
+        bot.search(&game, &SearchLimits::new().with_max_nodes(3000));
+
+        let reused_visits = {
+            let trees = bot.last_trees.lock().unwrap();
+            let reply = play_expected_line(&trees[0], &mut game);
+            trees[0].nodes[reply].visits
+        };
+        assert!(reused_visits > 1);
+
+        bot.search(&game, &SearchLimits::new().with_max_nodes(10));
+
+        let trees = bot.last_trees.lock().unwrap();
+        assert_eq!(trees[0].nodes[trees[0].root].visits, reused_visits + 10);
+    }
+
+    #[test]
+    fn test_unrelated_position_starts_fresh() {
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard).with_threads(1);
+        bot.search(&GameY::new(4), &SearchLimits::new().with_max_nodes(500));
+        bot.search(&GameY::new(5), &SearchLimits::new().with_max_nodes(50));
+
+        let trees = bot.last_trees.lock().unwrap();
+        assert_eq!(trees[0].nodes[trees[0].root].visits, 50);
+    }
+
+    #[test]
+    fn test_tree_reuse_can_be_disabled() {
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard)
+            .with_threads(1)
+            .with_tree_reuse(false);
+        let result = bot.search(&GameY::new(4), &SearchLimits::new().with_max_nodes(200));
+
+        assert!(result.best_move.is_some());
+        assert!(bot.last_trees.lock().unwrap().is_empty());
+    }
+
+    // ============================================================
+    // GRUPO 23: RAVE (7 tests + comparativa)
+    // ============================================================
+
+    #[test]
+    fn test_new_rave() {
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Rave);
+        assert_eq!(bot.name(), "monte_carlo_rave");
+        assert_eq!(bot.iterations, RAVE_ITERATIONS);
+        assert_eq!(bot.time_limit_ms, RAVE_TIME_LIMIT_MS);
+        assert_eq!(bot.rave_equivalence, Some(RAVE_EQUIVALENCE));
+
+        // Las otras dificultades usan UCB normal
+        assert_eq!(MonteCarloBot::new(MonteCarloDifficulty::Extreme).rave_equivalence, None);
+    }
+
+    #[test]
+    fn test_with_rave_configures_new_trees() {
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard).with_rave(Some(300.0));
+        assert_eq!(bot.new_tree(&GameY::new(4)).rave_equivalence, Some(300.0));
+
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Rave).with_rave(None);
+        assert_eq!(bot.new_tree(&GameY::new(4)).rave_equivalence, None);
+    }
+
+    #[test]
+    fn test_rave_score_without_amaf_is_ucb() {
+        let mut node = MCTSNode::new(None, None, PlayerId::new(0));
+        node.visits = 10;
+        node.wins = 4.0;
+
+        assert_eq!(node.rave_score(50, 0.4, 1000.0), node.ucb_score(50, 0.4));
+
+        node.visits = 0;
+        assert_eq!(node.rave_score(50, 0.4, 1000.0), f64::INFINITY);
+
+        // Sin visitas pero con AMAF se valora por la AMAF
+        node.amaf_visits = 4;
+        node.amaf_wins = 3.0;
+        assert_eq!(node.rave_score(50, 0.0, 1000.0), 0.75);
+    }
+
+    #[test]
+    fn test_rave_score_mixes_amaf() {
+        let mut node = MCTSNode::new(None, None, PlayerId::new(0));
+        node.visits = 100;
+        node.wins = 0.0;
+        node.amaf_visits = 200;
+        node.amaf_wins = 200.0;
+
+        // Con `equivalence` visitas reales las dos medias pesan lo mismo
+        let score = node.rave_score(1, 0.0, 100.0);
+        assert!((score - 0.5).abs() < 1e-9, "score: {}", score);
+
+        // Con muchas más visitas manda la media normal
+        let score = node.rave_score(1, 0.0, 1.0);
+        assert!(score < 0.1, "score: {}", score);
+    }
+
+    #[test]
+    fn test_update_amaf_credits_moves_of_the_player_to_move() {
+        let game = GameY::new(3);
+        let mut tree = MCTSTree::new(&game);
+        let player = PlayerId::new(0);
+        let cells = [0, 1, 2];
+        for cell in cells {
+            let coords = Coordinates::from_index(cell, 3);
+            tree.nodes.push(MCTSNode::new(Some(coords), Some(0), player.opponent()));
+            let child = tree.nodes.len() - 1;
+            tree.nodes[0].children.push(child);
+        }
+
+        // Rollout desde la raíz: 0 lo juega el jugador 0, 1 su rival; 2 no se juega
+        tree.update_amaf(0, &[0, 1], player, player, 3);
+
+        let amaf: Vec<(u32, f64)> = tree.nodes[1..]
+            .iter()
+            .map(|node| (node.amaf_visits, node.amaf_wins))
+            .collect();
+        assert_eq!(amaf, vec![(1, WIN_SCORE), (0, 0.0), (0, 0.0)]);
+    }
+
+    #[test]
+    fn test_rave_tree_collects_amaf_statistics() {
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Rave);
+        let (tree, _) = bot.grow_tree(bot.new_tree(&GameY::new(5)), 500, 10_000, Instant::now());
+
+        let root = &tree.nodes[tree.root];
+        let visits: u32 = root.children.iter().map(|&c| tree.nodes[c].visits).sum();
+        let amaf_visits: u32 = root.children.iter().map(|&c| tree.nodes[c].amaf_visits).sum();
+        // Cada simulación cuenta para todas las casillas que jugó el bot
+        assert!(amaf_visits > visits, "amaf: {}, visits: {}", amaf_visits, visits);
+
+        // Sin RAVE no se apuntan
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Extreme);
+        let (tree, _) = bot.grow_tree(bot.new_tree(&GameY::new(5)), 200, 10_000, Instant::now());
+        assert!(tree.nodes.iter().all(|node| node.amaf_visits == 0));
+    }
+
+    #[test]
+    fn test_rave_search_sees_immediate_win() {
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Rave).with_threads(1);
+        let game = GameY::try_from(crate::YEN::new(3, 0, vec!['B', 'R'], "B/BR/.R.".to_string())).unwrap();
+
+        let result = bot.search(&game, &SearchLimits::new().with_max_nodes(2000));
+
+        assert_eq!(result.best_move, Some(Coordinates::new(0, 0, 2)));
+    }
+
+    // Juega una partida entre dos bots con el mismo número de iteraciones por
+    // jugada y devuelve el ganador
+    fn play_match(first: &MonteCarloBot, second: &MonteCarloBot, size: u32, nodes: u64) -> PlayerId {
+        let mut game = GameY::new(size).with_swap_rule(crate::SwapRule::Disabled);
+        let limits = SearchLimits::new().with_max_nodes(nodes);
+        while let Some(player) = game.next_player() {
+            let bot = if player.id() == 0 { first } else { second };
+            let coords = bot.search(&game, &limits).best_move.unwrap();
+            game.add_move(Movement::Placement { player, coords }).unwrap();
+        }
+        match game.status() {
+            GameStatus::Finished { winner } => *winner,
+            GameStatus::Ongoing { .. } => unreachable!(),
+        }
+    }
+
+    // Comparativa de fuerza: `cargo test --release monte_carlo_rave_vs_extreme -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn test_monte_carlo_rave_vs_extreme() {
+        let games = 40;
+        let (size, nodes) = (7, 5000);
+        let rave = MonteCarloBot::new(MonteCarloDifficulty::Rave).with_threads(1);
+        let extreme = MonteCarloBot::new(MonteCarloDifficulty::Extreme).with_threads(1);
+
+        let mut rave_wins = 0;
+        for game in 0..games {
+            // Se alternan los colores para no dar ventaja al que empieza
+            let rave_wins_game = if game % 2 == 0 {
+                play_match(&rave, &extreme, size, nodes).id() == 0
+            } else {
+                play_match(&extreme, &rave, size, nodes).id() == 1
+            };
+            if rave_wins_game {
+                rave_wins += 1;
+            }
+        }
+
+        println!("monte_carlo_rave vs monte_carlo_extreme: {}/{} (tamaño {}, {} iteraciones)",
+                 rave_wins, games, size, nodes);
+        assert!(rave_wins * 2 >= games);
+    }
+
+    // ============================================================
+    // GRUPO 24: MCTS-Solver (7 tests)
+    // ============================================================
+
+    // Raíz con un hijo por jugada, para montar árboles a mano
+    fn tree_with_children(moves: &[Coordinates]) -> MCTSTree {
+        let mut tree = MCTSTree::new(&GameY::new(3));
+        let root_player = tree.nodes[0].player;
+        for &coords in moves {
+            tree.nodes.push(MCTSNode::new(Some(coords), Some(0), root_player.opponent()));
+            let child = tree.nodes.len() - 1;
+            tree.nodes[0].children.push(child);
+        }
+        tree
+    }
+
+    #[test]
+    fn test_proof_from_children() {
+        let mut tree = tree_with_children(&[Coordinates::new(2, 0, 0), Coordinates::new(1, 1, 0)]);
+        assert_eq!(tree.proof_from_children(0), None);
+
+        // Todas las jugadas pierden: la raíz es una victoria para quien jugó hacia ella
+        tree.nodes[1].proven = Some(Proof::Loss);
+        tree.nodes[2].proven = Some(Proof::Loss);
+        assert_eq!(tree.proof_from_children(0), Some(Proof::Win));
+
+        // Salvo que queden jugadas sin probar
+        tree.nodes[0].untried_moves = vec![Coordinates::new(1, 0, 1)];
+        assert_eq!(tree.proof_from_children(0), None);
+
+        // Con una jugada ganadora basta
+        tree.nodes[2].proven = Some(Proof::Win);
+        assert_eq!(tree.proof_from_children(0), Some(Proof::Loss));
+    }
+
+    #[test]
+    fn test_backpropagate_propagates_proofs() {
+        let mut tree = tree_with_children(&[Coordinates::new(2, 0, 0)]);
+        let player = tree.nodes[1].player;
+        tree.nodes.push(MCTSNode::new(Some(Coordinates::new(1, 1, 0)), Some(1), player.opponent()));
+        tree.nodes[1].children.push(2);
+        tree.nodes[2].proven = Some(Proof::Win);
+
+        tree.backpropagate(2, LOSE_SCORE);
+
+        // El rival gana desde el hijo, y era la única jugada de la raíz
+        assert_eq!(tree.nodes[1].proven, Some(Proof::Loss));
+        assert_eq!(tree.nodes[0].proven, Some(Proof::Win));
+        assert_eq!(tree.nodes[0].visits, 1);
+    }
+
+    #[test]
+    fn test_select_skips_proven_losses() {
+        let mut tree = tree_with_children(&[Coordinates::new(2, 0, 0), Coordinates::new(1, 1, 0)]);
+        tree.nodes[0].visits = 20;
+        tree.nodes[1].visits = 10;
+        tree.nodes[1].wins = 10.0;
+        tree.nodes[1].proven = Some(Proof::Loss);
+        tree.nodes[2].visits = 10;
+
+        assert_eq!(tree.select(), 2);
+    }
+
+    #[test]
+    fn test_best_move_prefers_proven_win() {
+        let coords = [Coordinates::new(2, 0, 0), Coordinates::new(1, 1, 0)];
+        let mut tree = tree_with_children(&coords);
+        tree.nodes[1].visits = 50;
+        tree.nodes[1].wins = 40.0;
+        tree.nodes[2].visits = 2;
+        tree.nodes[2].wins = 2.0;
+        tree.nodes[2].proven = Some(Proof::Win);
+
+        assert_eq!(tree.best_move(), Some(coords[1]));
+        assert_eq!(tree.variation_from(0), vec![coords[1]]);
+    }
+
+    #[test]
+    fn test_mate_in_one_is_proven() {
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard);
+        // B juega (0,0,2) y conecta los tres lados
+        let game = GameY::try_from(crate::YEN::new(3, 0, vec!['B', 'R'], "B/BR/.R.".to_string())).unwrap();
+
+        let (tree, iterations) = bot.grow_tree(bot.new_tree(&game), 5000, 10_000, Instant::now());
+
+        let winning = tree.nodes[tree.root].children
+            .iter()
+            .find(|&&child| tree.nodes[child].move_coords == Some(Coordinates::new(0, 0, 2)))
+            .copied()
+            .unwrap();
+        assert_eq!(tree.nodes[winning].proven, Some(Proof::Win));
+        assert_eq!(tree.nodes[tree.root].proven, Some(Proof::Loss));
+        // La búsqueda se corta en cuanto se demuestra la raíz
+        assert!(iterations < 5000, "iterations: {}", iterations);
+    }
+
+    #[test]
+    fn test_search_finds_mate_in_two() {
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard).with_threads(1).with_endgame_solver(None);
+        // B juega (1,1,0) o (0,1,1) y amenaza dos casillas a la vez
+        let game = GameY::try_from(crate::YEN::new(3, 0, vec!['B', 'R'], "B/../R..".to_string())).unwrap();
+
+        let result = bot.search(&game, &SearchLimits::new().with_max_nodes(5000));
+
+        let best = result.best_move.unwrap();
+        assert!(best == Coordinates::new(1, 1, 0) || best == Coordinates::new(0, 1, 1), "best: {:?}", best);
+        assert_eq!(result.win_probability, Some(1.0));
+        assert_eq!(result.principal_variation.len(), 3);
+        assert!(result.nodes < 5000);
+    }
+
+    #[test]
+    fn test_search_reports_proven_loss() {
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard).with_threads(1).with_endgame_solver(None);
+
+        let result = bot.search(&lost_position(), &SearchLimits::new().with_max_nodes(2000));
+
+        assert!(result.best_move.is_some());
+        assert_eq!(result.win_probability, Some(0.0));
+        assert!(result.nodes < 2000);
+    }
+
+    // ============================================================
+    // GRUPO 25: Semillas (3 tests)
+    // ============================================================
+
+    #[test]
+    fn test_same_seed_same_search() {
+        let game = GameY::new(5);
+        let limits = SearchLimits::new().with_max_nodes(400);
+        let search = |seed| {
+            MonteCarloBot::new(MonteCarloDifficulty::Hard)
+                .with_threads(2)
+                .with_seed(seed)
+                .search(&game, &limits)
+        };
+
+        let first = search(7);
+        let second = search(7);
+        assert_eq!(first.best_move, second.best_move);
+        assert_eq!(first.principal_variation, second.principal_variation);
+        assert_eq!(first.win_probability, second.win_probability);
+        assert_eq!(first.nodes, second.nodes);
+    }
+
+    #[test]
+    fn test_same_seed_same_game() {
+        // Con reutilización del árbol la partida entera se repite jugada a jugada
+        let play = |seed| {
+            let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard).with_threads(1).with_seed(seed);
+            let limits = SearchLimits::new().with_max_nodes(200);
+            let mut game = GameY::new(4).with_swap_rule(crate::SwapRule::Disabled);
+            let mut moves = Vec::new();
+            while !game.check_game_over() {
+                let coords = bot.search(&game, &limits).best_move.unwrap();
+                let player = game.next_player().unwrap();
+                game.add_move(Movement::Placement { player, coords }).unwrap();
+                moves.push(coords);
+            }
+            moves
+        };
+
+        assert_eq!(play(11), play(11));
+    }
+
+    #[test]
+    fn test_reseeded_keeps_configuration() {
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Rave).with_threads(2);
+
+        let replay = bot.reseeded(3).unwrap();
+        assert_eq!(replay.name(), "monte_carlo_rave");
+
+        // La copia busca en un solo hilo
+        let game = GameY::new(4);
+        let limits = SearchLimits::new().with_max_nodes(300);
+        let expected = MonteCarloBot::new(MonteCarloDifficulty::Rave)
+            .with_threads(1)
+            .with_seed(3)
+            .search(&game, &limits);
+        let result = replay.search(&game, &limits);
+        assert_eq!(result.principal_variation, expected.principal_variation);
+        assert_eq!(result.win_probability, expected.win_probability);
+    }
+
+    // ============================================================
+    // GRUPO 26: Solver de final (3 tests)
+    // ============================================================
+
+    #[test]
+    fn test_endgame_solver_answers_without_tree() {
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard).with_threads(1);
+        let game = GameY::try_from(crate::YEN::new(3, 0, vec!['B', 'R'], "B/../R..".to_string())).unwrap();
+
+        let result = bot.search(&game, &SearchLimits::new().with_max_nodes(5000));
+
+        let best = result.best_move.unwrap();
+        assert!(best == Coordinates::new(1, 1, 0) || best == Coordinates::new(0, 1, 1), "best: {:?}", best);
+        assert_eq!(result.win_probability, Some(1.0));
+        assert_eq!(result.principal_variation.len(), 3);
+        // El solver responde sin hacer crecer ningún árbol
+        assert!(bot.last_trees.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_endgame_solver_loss_keeps_searching() {
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard).with_threads(1);
+
+        let result = bot.search(&lost_position(), &SearchLimits::new().with_max_nodes(200));
+
+        assert!(result.best_move.is_some());
+        assert_eq!(result.win_probability, Some(0.0));
+        assert!(!bot.last_trees.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_endgame_solver_threshold() {
+        // Con umbral 0 nunca se usa el solver: la búsqueda crece un árbol
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard).with_threads(1).with_endgame_solver(Some(0));
+        let game = GameY::try_from(crate::YEN::new(3, 0, vec!['B', 'R'], "B/../R..".to_string())).unwrap();
+
+        bot.search(&game, &SearchLimits::new().with_max_nodes(50));
+        assert!(!bot.last_trees.lock().unwrap().is_empty());
+    }
+
+    // ============================================================
+    // GRUPO 27: Juego óptimo en tableros pequeños (2 tests)
+    // ============================================================
+
+    // Posiciones aleatorias de tamaño 4 en las que gana quien mueve, según la tabla resuelta
+    fn winning_positions(table: &crate::SolvedTable, min_stones: usize, count: usize) -> Vec<GameY> {
+        let mut rng = StdRng::seed_from_u64(27);
+        let mut positions = Vec::new();
+        while positions.len() < count {
+            let mut game = GameY::new(4).with_swap_rule(crate::SwapRule::Disabled);
+            for _ in 0..rng.random_range(min_stones..7) {
+                let cells = game.available_cells().clone();
+                let cell = cells[rng.random_range(0..cells.len())];
+                let player = game.next_player().unwrap();
+                game.make_move(player, Coordinates::from_index(cell, 4)).unwrap();
+                if game.check_game_over() {
+                    break;
+                }
+            }
+            if game.perfect_play(table).is_some_and(|p| p.outcome == crate::SolveOutcome::Win) {
+                positions.push(game);
+            }
+        }
+        positions
+    }
+
+    #[test]
+    fn test_mcts_solver_plays_perfectly() {
+        // Sin solver de final, las pruebas del árbol bastan cuando quedan pocas casillas
+        let table = crate::SolvedTable::solve(4).unwrap();
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard)
+            .with_threads(1)
+            .with_seed(27)
+            .with_endgame_solver(Some(0));
+
+        for game in winning_positions(&table, 4, 15) {
+            let result = bot.search(&game, &SearchLimits::new().with_max_nodes(20_000));
+            let chosen = result.best_move.unwrap();
+            let winning_moves = game.perfect_play(&table).unwrap().winning_moves;
+            assert!(winning_moves.contains(&chosen), "{:?} not in {:?}", chosen, winning_moves);
+        }
+    }
+
+    #[test]
+    fn test_endgame_solver_plays_perfectly() {
+        let table = crate::SolvedTable::solve(4).unwrap();
+        let bot = MonteCarloBot::new(MonteCarloDifficulty::Hard).with_threads(1).with_seed(27);
+
+        // Con una piedra o más quedan como mucho 9 casillas: responde el solver
+        for game in winning_positions(&table, 1, 15) {
+            let chosen = bot.search(&game, &SearchLimits::new().with_max_nodes(2000)).best_move.unwrap();
+            assert!(game.perfect_play(&table).unwrap().winning_moves.contains(&chosen));
+        }
+    }
+}
@@ -4,7 +4,10 @@
 //! It is useful for testing and as a baseline opponent.
 
 use crate::{Coordinates, GameY, YBot};
+use rand::SeedableRng;
 use rand::prelude::IndexedRandom;
+use rand::rngs::StdRng;
+use std::sync::{Arc, Mutex};
 
 /// A bot that chooses moves randomly from the available cells.
 ///
@@ -12,19 +15,46 @@ use rand::prelude::IndexedRandom;
 /// a random empty cell on the board. While not strategic, it serves as
 /// a useful baseline and testing tool.
 ///
+/// Two bots created with the same seed choose the same moves in the same games.
+///
 /// # Example
 ///
 /// ```
 /// use gamey::{GameY, RandomBot, YBot};
 ///
-/// let bot = RandomBot;
+/// let bot = RandomBot::new(None);
 /// let game = GameY::new(5);
 ///
 /// // The bot will always return Some when there are available moves
 /// let chosen_move = bot.choose_move(&game);
 /// assert!(chosen_move.is_some());
+///
+/// // Seeded bots can be replayed
+/// let a = RandomBot::new(Some(7));
+/// let b = RandomBot::new(Some(7));
+/// assert_eq!(a.choose_move(&game), b.choose_move(&game));
 /// ```
-pub struct RandomBot;
+pub struct RandomBot {
+    rng: Mutex<StdRng>,
+}
+
+impl RandomBot {
+    /// Creates a random bot. With `Some(seed)` its moves are reproducible;
+    /// with `None` it is seeded from the operating system.
+    pub fn new(seed: Option<u64>) -> Self {
+        let rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_rng(&mut rand::rng()),
+        };
+        Self { rng: Mutex::new(rng) }
+    }
+}
+
+impl Default for RandomBot {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
 
 impl YBot for RandomBot {
     fn name(&self) -> &str {
@@ -33,10 +63,15 @@ impl YBot for RandomBot {
 
     fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
         let available_cells = board.available_cells();
-        let cell = available_cells.choose(&mut rand::rng())?;
+        let mut rng = self.rng.lock().unwrap_or_else(|e| e.into_inner());
+        let cell = available_cells.choose(&mut *rng)?;
         let coordinates = Coordinates::from_index(*cell, board.board_size());
         Some(coordinates)
     }
+
+    fn reseeded(&self, seed: u64) -> Option<Arc<dyn YBot>> {
+        Some(Arc::new(RandomBot::new(Some(seed))))
+    }
 }
 
 #[cfg(test)]
@@ -46,13 +81,13 @@ mod tests {
 
     #[test]
     fn test_random_bot_name() {
-        let bot = RandomBot;
+        let bot = RandomBot::new(None);
         assert_eq!(bot.name(), "random_bot");
     }
 
     #[test]
     fn test_random_bot_returns_move_on_empty_board() {
-        let bot = RandomBot;
+        let bot = RandomBot::new(None);
         let game = GameY::new(5);
 
         let chosen_move = bot.choose_move(&game);
@@ -61,7 +96,7 @@ mod tests {
 
     #[test]
     fn test_random_bot_returns_valid_coordinates() {
-        let bot = RandomBot;
+        let bot = RandomBot::new(None);
         let game = GameY::new(5);
 
         let coords = bot.choose_move(&game).unwrap();
@@ -74,7 +109,7 @@ mod tests {
 
     #[test]
     fn test_random_bot_returns_none_on_full_board() {
-        let bot = RandomBot;
+        let bot = RandomBot::new(None);
         let mut game = GameY::new(2);
 
         // Fill the board (size 2 has 3 cells)
@@ -105,7 +140,7 @@ mod tests {
 
     #[test]
     fn test_random_bot_chooses_from_available_cells() {
-        let bot = RandomBot;
+        let bot = RandomBot::new(None);
         let mut game = GameY::new(3);
 
         // Make some moves to reduce available cells
@@ -124,7 +159,7 @@ mod tests {
 
     #[test]
     fn test_random_bot_multiple_calls_return_valid_moves() {
-        let bot = RandomBot;
+        let bot = RandomBot::new(None);
         let game = GameY::new(7);
 
         // Call choose_move multiple times to exercise the randomness
@@ -137,4 +172,31 @@ mod tests {
             assert!(game.available_cells().contains(&index));
         }
     }
+
+    #[test]
+    fn test_random_bot_same_seed_same_moves() {
+        let a = RandomBot::new(Some(42));
+        let b = RandomBot::new(Some(42));
+        let game = GameY::new(9);
+
+        let moves_a: Vec<_> = (0..20).map(|_| a.choose_move(&game)).collect();
+        let moves_b: Vec<_> = (0..20).map(|_| b.choose_move(&game)).collect();
+        assert_eq!(moves_a, moves_b);
+
+        // Another seed gives another sequence
+        let c = RandomBot::new(Some(43));
+        let moves_c: Vec<_> = (0..20).map(|_| c.choose_move(&game)).collect();
+        assert_ne!(moves_a, moves_c);
+    }
+
+    #[test]
+    fn test_random_bot_reseeded() {
+        let game = GameY::new(9);
+        let reseeded = RandomBot::new(None).reseeded(42).unwrap();
+        let seeded = RandomBot::new(Some(42));
+
+        for _ in 0..20 {
+            assert_eq!(reseeded.choose_move(&game), seeded.choose_move(&game));
+        }
+    }
 }
@@ -0,0 +1,340 @@
+//! Bounded transposition table that searches can share.
+//!
+//! For every position already searched it keeps the score found, the depth
+//! it was searched to and the best move. It has a fixed number of slots and
+//! can be shared between threads (for example, between server requests)
+//! through an `Arc`.
+//!
+//! Slots take no locks: each one is three atomic words, and the first holds
+//! the key XOR-ed with the other two. If two threads write the same slot at
+//! once, a read does not match the key and counts as a miss, just like a
+//! position that is not in the table.
+
+use crate::PlayerId;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
+
+/// Kind of bound the stored score is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum NodeType {
+    Exact,
+    Lower,
+    Upper,
+}
+
+/// Result of searching a position.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TranspositionEntry {
+    pub score: i32,
+    pub depth: u32,
+    pub flag: NodeType,
+    // Cell index of the best move found, if any
+    pub best_move: Option<u32>,
+}
+
+// Depth is stored in 30 bits: no board gets anywhere near deeper
+const DEPTH_MASK: u64 = (1 << 30) - 1;
+// The generation takes the top 31 bits of the second word
+const GENERATION_MASK: u32 = (1 << 31) - 1;
+
+// A slot of the table. An empty slot has all three words at zero; a used one
+// never has `data` at zero because the bound kind is stored as 1..=3
+#[derive(Default)]
+struct Slot {
+    // key ^ data ^ extra
+    check: AtomicU64,
+    // score (top 32 bits), depth (30 bits) and bound kind (2 bits)
+    data: AtomicU64,
+    // generation (top 31 bits) and best move + 1, or 0 if none (33 bits)
+    extra: AtomicU64,
+}
+
+/// Transposition table with a fixed size.
+///
+/// When two positions fall in the same slot the deeper search is kept,
+/// unless the stored one comes from an earlier search: those are always
+/// replaced.
+pub struct TranspositionTable {
+    capacity: usize,
+    slots: OnceLock<Box<[Slot]>>,
+    len: AtomicUsize,
+    generation: AtomicU32,
+}
+
+impl TranspositionTable {
+    /// Creates an empty table with `capacity` slots. The memory is allocated
+    /// on the first write.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            slots: OnceLock::new(),
+            len: AtomicUsize::new(0),
+            generation: AtomicU32::new(0),
+        }
+    }
+
+    /// Maximum number of entries.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of entries in use.
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    /// Returns true if the table has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Empties the table.
+    pub fn clear(&self) {
+        if let Some(slots) = self.slots.get() {
+            for slot in slots.iter() {
+                slot.data.store(0, Ordering::Relaxed);
+                slot.extra.store(0, Ordering::Relaxed);
+                slot.check.store(0, Ordering::Relaxed);
+            }
+        }
+        self.len.store(0, Ordering::Relaxed);
+    }
+
+    /// Marks the start of a new search: earlier entries can still be probed,
+    /// but from now on they can always be replaced.
+    pub(crate) fn new_search(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Looks up the position as evaluated for `player`.
+    pub(crate) fn probe(&self, hash: u64, board_size: u32, player: PlayerId) -> Option<TranspositionEntry> {
+        let slot = &self.slots.get()?[self.index(hash)];
+        let check = slot.check.load(Ordering::Acquire);
+        let data = slot.data.load(Ordering::Acquire);
+        let extra = slot.extra.load(Ordering::Acquire);
+        (data != 0 && check ^ data ^ extra == Self::key(hash, board_size, player))
+            .then(|| Self::unpack(data, extra))
+    }
+
+    /// Stores the result of a position evaluated for `player`.
+    pub(crate) fn store(&self, hash: u64, board_size: u32, player: PlayerId, entry: TranspositionEntry) {
+        let slot = &self.slots()[self.index(hash)];
+        let key = Self::key(hash, board_size, player);
+        let generation = self.generation.load(Ordering::Relaxed) & GENERATION_MASK;
+        let (data, extra) = Self::pack(&entry, generation);
+
+        let old_data = slot.data.load(Ordering::Acquire);
+        if old_data == 0 {
+            // Only the thread that takes the empty slot counts a new entry
+            if slot
+                .data
+                .compare_exchange(0, data, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+            {
+                self.len.fetch_add(1, Ordering::Relaxed);
+                slot.extra.store(extra, Ordering::Release);
+                slot.check.store(key ^ data ^ extra, Ordering::Release);
+                return;
+            }
+        } else {
+            let old_extra = slot.extra.load(Ordering::Acquire);
+            let old_key = slot.check.load(Ordering::Acquire) ^ old_data ^ old_extra;
+            let old_generation = (old_extra >> 33) as u32;
+            let old_depth = ((old_data >> 2) & DEPTH_MASK) as u32;
+            let replace = old_key == key || old_generation != generation || entry.depth >= old_depth;
+            if !replace {
+                return;
+            }
+        }
+
+        slot.data.store(data, Ordering::Release);
+        slot.extra.store(extra, Ordering::Release);
+        slot.check.store(key ^ data ^ extra, Ordering::Release);
+    }
+
+    fn slots(&self) -> &[Slot] {
+        self.slots
+            .get_or_init(|| (0..self.capacity).map(|_| Slot::default()).collect())
+    }
+
+    fn index(&self, hash: u64) -> usize {
+        (hash % self.capacity as u64) as usize
+    }
+
+    // The hash covers neither the board size nor the player the position was
+    // evaluated for, so both are mixed into the key that is checked
+    fn key(hash: u64, board_size: u32, player: PlayerId) -> u64 {
+        let extra = (u64::from(board_size) << 32) | u64::from(player.id());
+        hash ^ extra.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+    }
+
+    fn pack(entry: &TranspositionEntry, generation: u32) -> (u64, u64) {
+        let flag = match entry.flag {
+            NodeType::Exact => 1,
+            NodeType::Lower => 2,
+            NodeType::Upper => 3,
+        };
+        let depth = u64::from(entry.depth).min(DEPTH_MASK);
+        let data = (u64::from(entry.score as u32) << 32) | (depth << 2) | flag;
+        let best_move = entry.best_move.map_or(0, |m| u64::from(m) + 1);
+        (data, (u64::from(generation) << 33) | best_move)
+    }
+
+    fn unpack(data: u64, extra: u64) -> TranspositionEntry {
+        let flag = match data & 3 {
+            1 => NodeType::Exact,
+            2 => NodeType::Lower,
+            _ => NodeType::Upper,
+        };
+        let best_move = extra & ((1 << 33) - 1);
+        TranspositionEntry {
+            score: (data >> 32) as u32 as i32,
+            depth: ((data >> 2) & DEPTH_MASK) as u32,
+            flag,
+            best_move: (best_move != 0).then(|| (best_move - 1) as u32),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    const WIN_SCORE: i32 = 1_000_000;
+
+    fn entry(score: i32, depth: u32, best_move: Option<u32>) -> TranspositionEntry {
+        TranspositionEntry {
+            score,
+            depth,
+            flag: NodeType::Exact,
+            best_move,
+        }
+    }
+
+    #[test]
+    fn test_store_and_probe() {
+        let table = TranspositionTable::new(64);
+        assert!(table.is_empty());
+
+        table.store(42, 5, PlayerId::new(1), entry(7, 3, Some(4)));
+
+        let found = table.probe(42, 5, PlayerId::new(1)).unwrap();
+        assert_eq!(found.score, 7);
+        assert_eq!(found.depth, 3);
+        assert_eq!(found.best_move, Some(4));
+        assert_eq!(table.len(), 1);
+    }
+
+    #[test]
+    fn test_probe_checks_size_and_player() {
+        let table = TranspositionTable::new(64);
+        table.store(42, 5, PlayerId::new(1), entry(7, 3, None));
+
+        assert!(table.probe(42, 6, PlayerId::new(1)).is_none());
+        assert!(table.probe(42, 5, PlayerId::new(0)).is_none());
+        assert!(table.probe(42 + 64, 5, PlayerId::new(1)).is_none());
+    }
+
+    #[test]
+    fn test_deeper_entry_is_kept_within_a_search() {
+        let table = TranspositionTable::new(64);
+        table.store(1, 5, PlayerId::new(0), entry(10, 4, None));
+        // Same slot, another position and less depth: not replaced
+        table.store(1 + 64, 5, PlayerId::new(0), entry(20, 2, None));
+
+        assert_eq!(table.probe(1, 5, PlayerId::new(0)).unwrap().score, 10);
+        assert!(table.probe(1 + 64, 5, PlayerId::new(0)).is_none());
+    }
+
+    #[test]
+    fn test_old_search_entries_are_replaced() {
+        let table = TranspositionTable::new(64);
+        table.store(1, 5, PlayerId::new(0), entry(10, 4, None));
+        table.new_search();
+        table.store(1 + 64, 5, PlayerId::new(0), entry(20, 2, None));
+
+        assert!(table.probe(1, 5, PlayerId::new(0)).is_none());
+        assert_eq!(table.probe(1 + 64, 5, PlayerId::new(0)).unwrap().score, 20);
+        assert_eq!(table.len(), 1);
+    }
+
+    #[test]
+    fn test_size_is_bounded() {
+        let table = TranspositionTable::new(16);
+        for hash in 0..1000 {
+            table.store(hash, 5, PlayerId::new(0), entry(0, 1, None));
+        }
+        assert_eq!(table.len(), 16);
+        assert_eq!(table.capacity(), 16);
+
+        table.clear();
+        assert!(table.is_empty());
+    }
+
+    #[test]
+    fn test_shared_between_threads() {
+        let table = Arc::new(TranspositionTable::new(1024));
+        let handles: Vec<_> = (0..4u64)
+            .map(|t| {
+                let table = Arc::clone(&table);
+                std::thread::spawn(move || {
+                    for i in 0..100 {
+                        table.store(t * 100 + i, 7, PlayerId::new(0), entry(i as i32, 1, None));
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(table.len(), 400);
+        assert_eq!(table.probe(250, 7, PlayerId::new(0)).unwrap().score, 50);
+    }
+
+    #[test]
+    fn test_concurrent_writes_to_one_slot_never_mix_entries() {
+        // Every thread writes to the same slot an entry whose score and move
+        // depend on its hash: whatever is read back must be consistent
+        let table = Arc::new(TranspositionTable::new(1));
+        let handles: Vec<_> = (0..4u64)
+            .map(|t| {
+                let table = Arc::clone(&table);
+                std::thread::spawn(move || {
+                    for _ in 0..2000 {
+                        table.store(t, 5, PlayerId::new(0), entry(t as i32 * 10, 1, Some(t as u32)));
+                        for hash in 0..4u64 {
+                            if let Some(found) = table.probe(hash, 5, PlayerId::new(0)) {
+                                assert_eq!(found.score, hash as i32 * 10);
+                                assert_eq!(found.best_move, Some(hash as u32));
+                            }
+                        }
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(table.len(), 1);
+    }
+
+    #[test]
+    fn test_entry_round_trip() {
+        let table = TranspositionTable::new(8);
+        let stored = TranspositionEntry {
+            score: -WIN_SCORE,
+            depth: 12,
+            flag: NodeType::Upper,
+            best_move: Some(0),
+        };
+        table.store(3, 9, PlayerId::new(1), stored);
+
+        let found = table.probe(3, 9, PlayerId::new(1)).unwrap();
+        assert_eq!(found.score, -WIN_SCORE);
+        assert_eq!(found.depth, 12);
+        assert_eq!(found.flag, NodeType::Upper);
+        assert_eq!(found.best_move, Some(0));
+    }
+}
@@ -0,0 +1,228 @@
+//! Bot decisions that go beyond placing a stone.
+//!
+//! [`YBot::decide`] returns a [`BotDecision`]: the [`Movement`] the bot wants
+//! to play (a placement, a swap or a resignation) together with the analysis
+//! behind it. [`decide_with_search`] implements the policy shared by the
+//! searching bots on top of [`YBot::search`].
+
+use crate::{GameAction, GameY, Movement, SearchLimits, SearchResult, YBot};
+
+/// Win probability below which searching bots resign by default.
+pub const DEFAULT_RESIGN_THRESHOLD: f64 = 0.05;
+
+/// What a bot decided to play on its turn, with the analysis behind it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BotDecision {
+    /// The movement to apply, made by the player to move.
+    pub movement: Movement,
+    /// The search that led to the decision.
+    pub analysis: SearchResult,
+}
+
+impl BotDecision {
+    /// Returns the action of the decision, or `None` for a placement.
+    pub fn action(&self) -> Option<&GameAction> {
+        match &self.movement {
+            Movement::Action { action, .. } => Some(action),
+            Movement::Placement { .. } => None,
+        }
+    }
+}
+
+/// Decides between placing, swapping and resigning using `bot`'s own search.
+///
+/// When the swap rule can be invoked, the bot searches both the current
+/// position and the swapped one, each with half of the time and node budget
+/// (`default_time_ms` is the bot's time limit when `limits` leaves it open),
+/// and swaps if that looks better. Otherwise it resigns when its estimated
+/// win probability is below `resign_threshold`, and places its best move.
+pub fn decide_with_search<B: YBot + ?Sized>(
+    bot: &B,
+    board: &GameY,
+    limits: &SearchLimits,
+    default_time_ms: u64,
+    resign_threshold: f64,
+) -> Option<BotDecision> {
+    let player = board.next_player()?;
+
+    if board.can_swap() {
+        let half = SearchLimits {
+            max_time_ms: Some(limits.max_time_ms.unwrap_or(default_time_ms) / 2),
+            max_nodes: limits.max_nodes.map(|n| (n / 2).max(1)),
+            max_depth: limits.max_depth,
+        };
+        let place = bot.search(board, &half);
+
+        let mut swapped = board.clone();
+        let swap = Movement::Action {
+            player,
+            action: GameAction::Swap,
+        };
+        if swapped.add_move(swap.clone()).is_ok() {
+            // The opponent moves after the swap: its analysis is seen from the other side
+            let reply = bot.search(&swapped, &half);
+            if swap_is_better(&place, &reply) {
+                let analysis = SearchResult {
+                    best_move: None,
+                    score: reply.score.map(|s| -s),
+                    win_probability: reply.win_probability.map(|p| 1.0 - p),
+                    principal_variation: reply.principal_variation,
+                    depth: reply.depth,
+                    nodes: place.nodes + reply.nodes,
+                    elapsed_ms: place.elapsed_ms + reply.elapsed_ms,
+                };
+                return Some(BotDecision {
+                    movement: swap,
+                    analysis,
+                });
+            }
+        }
+        return place_or_resign(player, place, resign_threshold);
+    }
+
+    place_or_resign(player, bot.search(board, limits), resign_threshold)
+}
+
+/// Compares placing (`place`, from the bot's side) with swapping (`reply`,
+/// from the opponent's side after the swap).
+fn swap_is_better(place: &SearchResult, reply: &SearchResult) -> bool {
+    match (place.win_probability, reply.win_probability) {
+        (Some(p), Some(q)) => 1.0 - q > p,
+        _ => match (place.score, reply.score) {
+            (Some(p), Some(q)) => q.saturating_neg() > p,
+            _ => false,
+        },
+    }
+}
+
+fn place_or_resign(
+    player: crate::PlayerId,
+    analysis: SearchResult,
+    resign_threshold: f64,
+) -> Option<BotDecision> {
+    let hopeless = analysis
+        .win_probability
+        .is_some_and(|p| p < resign_threshold);
+    let movement = if hopeless {
+        Movement::Action {
+            player,
+            action: GameAction::Resign,
+        }
+    } else {
+        Movement::Placement {
+            player,
+            coords: analysis.best_move?,
+        }
+    };
+    Some(BotDecision { movement, analysis })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coordinates, PlayerId, SwapRule};
+    use std::time::Duration;
+
+    /// Bot with a fixed answer, to drive the decision policy.
+    struct FixedBot {
+        win_probability: f64,
+        // Probability reported when the opening stone is the bot's own
+        after_swap: f64,
+    }
+
+    impl YBot for FixedBot {
+        fn name(&self) -> &str {
+            "fixed"
+        }
+
+        fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+            let idx = *board.available_cells().first()?;
+            Some(Coordinates::from_index(idx, board.board_size()))
+        }
+
+        fn search(&self, board: &GameY, _limits: &SearchLimits) -> SearchResult {
+            let swapped = board
+                .history()
+                .iter()
+                .any(|m| matches!(m, Movement::Action { action: GameAction::Swap, .. }));
+            SearchResult {
+                win_probability: Some(if swapped { self.after_swap } else { self.win_probability }),
+                ..SearchResult::from_move(self.choose_move(board), Duration::ZERO)
+            }
+        }
+    }
+
+    fn after_opening() -> GameY {
+        let mut game = GameY::new(5);
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::new(2, 1, 1),
+        })
+        .unwrap();
+        game
+    }
+
+    #[test]
+    fn test_places_in_balanced_position() {
+        let bot = FixedBot { win_probability: 0.5, after_swap: 0.5 };
+        let decision = decide_with_search(&bot, &GameY::new(4), &SearchLimits::new(), 100, 0.05).unwrap();
+        assert!(matches!(decision.movement, Movement::Placement { .. }));
+        assert_eq!(decision.action(), None);
+    }
+
+    #[test]
+    fn test_resigns_below_threshold() {
+        let bot = FixedBot { win_probability: 0.01, after_swap: 0.5 };
+        let decision = decide_with_search(&bot, &GameY::new(4), &SearchLimits::new(), 100, 0.05).unwrap();
+        assert_eq!(
+            decision.movement,
+            Movement::Action { player: PlayerId::new(0), action: GameAction::Resign }
+        );
+
+        // A threshold of 0 never resigns
+        let decision = decide_with_search(&bot, &GameY::new(4), &SearchLimits::new(), 100, 0.0).unwrap();
+        assert_eq!(decision.action(), None);
+    }
+
+    #[test]
+    fn test_swaps_a_strong_opening() {
+        // After the swap the opponent only wins 20%: better than playing on at 40%
+        let bot = FixedBot { win_probability: 0.4, after_swap: 0.2 };
+        let decision = decide_with_search(&bot, &after_opening(), &SearchLimits::new(), 100, 0.05).unwrap();
+        assert_eq!(decision.action(), Some(&GameAction::Swap));
+        assert_eq!(decision.analysis.win_probability, Some(0.8));
+
+        let mut game = after_opening();
+        game.add_move(decision.movement).unwrap();
+        assert_eq!(game.next_player(), Some(PlayerId::new(0)));
+    }
+
+    #[test]
+    fn test_keeps_a_weak_opening() {
+        let bot = FixedBot { win_probability: 0.6, after_swap: 0.7 };
+        let decision = decide_with_search(&bot, &after_opening(), &SearchLimits::new(), 100, 0.05).unwrap();
+        assert!(matches!(decision.movement, Movement::Placement { .. }));
+    }
+
+    #[test]
+    fn test_no_swap_when_rule_disabled() {
+        let bot = FixedBot { win_probability: 0.4, after_swap: 0.2 };
+        let mut game = GameY::new(5).with_swap_rule(SwapRule::Disabled);
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::new(2, 1, 1),
+        })
+        .unwrap();
+        let decision = decide_with_search(&bot, &game, &SearchLimits::new(), 100, 0.05).unwrap();
+        assert!(matches!(decision.movement, Movement::Placement { .. }));
+    }
+
+    #[test]
+    fn test_no_decision_when_game_is_over() {
+        let bot = FixedBot { win_probability: 0.5, after_swap: 0.5 };
+        let mut game = GameY::new(3);
+        game.add_move(Movement::Action { player: PlayerId::new(0), action: GameAction::Resign })
+            .unwrap();
+        assert!(decide_with_search(&bot, &game, &SearchLimits::new(), 100, 0.05).is_none());
+    }
+}
@@ -4,16 +4,34 @@
 //! that can play the Game of Y. It includes:
 //!
 //! - [`YBot`] - A trait that defines the interface for all bots
+//! - [`SearchLimits`] and [`SearchResult`] - Limits and analysis for [`YBot::search`]
+//! - [`BotDecision`] - A placement, swap or resignation chosen by [`YBot::decide`]
 //! - [`YBotRegistry`] - A registry for managing multiple bot implementations
+//! - [`OpeningBook`] and [`BookBot`] - Precomputed opening moves and a bot that plays them
+//! - [`solve`] - An exact endgame solver based on proof-number search
+//! - [`SolvedTable`] - Exhaustively solved positions for small boards
+//! - [`run_arena`] - Matches between bots, with win rates and Elo estimates
 //! - [`RandomBot`] - A simple bot that makes random valid moves
 
 
 
 pub mod ybot;
+pub mod search;
+pub mod decision;
 pub mod ybot_registry;
+pub mod opening_book;
+pub mod solver;
+pub mod solved_table;
+pub mod arena;
 pub mod bot_implementations;
 
 
 pub use ybot::YBot;
+pub use search::{SearchLimits, SearchResult};
+pub use decision::{BotDecision, DEFAULT_RESIGN_THRESHOLD, decide_with_search};
 pub use ybot_registry::YBotRegistry;
-pub use bot_implementations::{RandomBot, HeuristicBot, MinimaxBot, AlfaBetaBot, MonteCarloBot};
+pub use opening_book::{BookBot, BookMove, OpeningBook};
+pub use solver::{DEFAULT_ENDGAME_CELLS, SolveOutcome, Solution, solve};
+pub use solved_table::{MAX_TABLE_SIZE, SolvedPosition, SolvedTable};
+pub use arena::{ArenaConfig, ArenaReport, BotStanding, EloEstimate, GameRecord, MatchResult, MoveRecord, run_arena};
+pub use bot_implementations::{RandomBot, HeuristicBot, MinimaxBot, AlfaBetaBot, MonteCarloBot, TranspositionTable};
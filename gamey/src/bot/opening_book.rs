@@ -0,0 +1,580 @@
+//! Opening books: precomputed moves for the first plies of a game.
+//!
+//! An [`OpeningBook`] remembers, for every position it has seen early in a
+//! game, which moves were played there and how often the player who played
+//! them went on to win. Positions are keyed by board size and a canonical
+//! hash, so the rotations and reflections of a position share one entry.
+//! Books are built from game records or self-play, saved as JSON and used by
+//! any bot through the [`BookBot`] wrapper.
+
+use crate::{
+    BotDecision, Coordinates, GameAction, GameStatus, GameY, GameYError, Movement, Result,
+    SearchLimits, SearchResult, YBot,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A move stored in the book, with the results of the games where it was played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BookMove {
+    /// The cell to place a stone on, or `None` for a swap.
+    pub coords: Option<Coordinates>,
+    /// Number of games where the move was played.
+    pub games: u32,
+    /// Number of those games won by the player who played the move.
+    pub wins: u32,
+}
+
+impl BookMove {
+    /// Returns true if the move is a swap rather than a placement.
+    pub fn is_swap(&self) -> bool {
+        self.coords.is_none()
+    }
+
+    /// Fraction of the games won by the player who played the move.
+    pub fn win_rate(&self) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            self.wins as f64 / self.games as f64
+        }
+    }
+}
+
+/// Moves for the first plies of a game, keyed by canonical position.
+#[derive(Debug, Clone, Default)]
+pub struct OpeningBook {
+    // (board size, canonical hash) -> moves in the canonical orientation
+    positions: HashMap<(u32, u64), Vec<BookMove>>,
+}
+
+/// On-disk layout of a book.
+#[derive(Serialize, Deserialize)]
+struct BookFile {
+    positions: Vec<BookPosition>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BookPosition {
+    size: u32,
+    hash: u64,
+    moves: Vec<BookMove>,
+}
+
+impl OpeningBook {
+    /// Creates an empty book.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a book from finished games, recording their first `max_plies` moves.
+    pub fn from_games<'a>(games: impl IntoIterator<Item = &'a GameY>, max_plies: usize) -> Self {
+        let mut book = Self::new();
+        for game in games {
+            book.add_game(game, max_plies);
+        }
+        book
+    }
+
+    /// Number of positions in the book.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns true if the book has no positions.
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Records the first `max_plies` moves of a finished game.
+    ///
+    /// The game is replayed from its history, so it must have been played
+    /// from an empty board. Placements and swaps are recorded; unfinished
+    /// games are ignored. Returns true if the game was recorded.
+    pub fn add_game(&mut self, game: &GameY, max_plies: usize) -> bool {
+        let Some(winner) = winner(game) else {
+            return false;
+        };
+
+        let mut replay = GameY::new(game.board_size())
+            .with_swap_rule(game.swap_rule())
+            .with_validation(game.validation());
+        for movement in game.history().iter().take(max_plies) {
+            let coords = match movement {
+                Movement::Placement { coords, .. } => Some(*coords),
+                Movement::Action { action: GameAction::Swap, .. } => None,
+                Movement::Action { .. } => break,
+            };
+            let Some(player) = replay.next_player() else {
+                break;
+            };
+            self.record(&replay, coords, player == winner);
+            if replay.add_move(movement.clone()).is_err() {
+                break;
+            }
+        }
+        true
+    }
+
+    /// Plays `games` games of `bot` against itself on a board of `board_size`
+    /// within `limits` per move, and records their first `max_plies` moves.
+    ///
+    /// Game `i` is played by the bot reseeded with `seed + i` (see
+    /// [`YBot::reseeded`]), so bots with randomness explore different lines.
+    /// A deterministic bot plays the same game every time.
+    pub fn add_self_play(
+        &mut self,
+        bot: &dyn YBot,
+        board_size: u32,
+        games: u32,
+        max_plies: usize,
+        limits: &SearchLimits,
+        seed: u64,
+    ) {
+        for i in 0..games {
+            let reseeded = bot.reseeded(seed.wrapping_add(i as u64));
+            let player = reseeded.as_deref().unwrap_or(bot);
+
+            let mut game = GameY::new(board_size);
+            while !game.check_game_over() {
+                let Some(decision) = player.decide(&game, limits) else {
+                    break;
+                };
+                if game.add_move(decision.movement).is_err() {
+                    break;
+                }
+            }
+            self.add_game(&game, max_plies);
+        }
+    }
+
+    /// Returns the book moves for `board`, oriented to the board.
+    ///
+    /// Moves that cannot be played on the board (an occupied cell, or a swap
+    /// when the swap rule does not allow it) are left out.
+    pub fn lookup(&self, board: &GameY) -> Vec<BookMove> {
+        if board.check_game_over() {
+            return Vec::new();
+        }
+        let (hash, symmetry) = board.canonical();
+        let Some(moves) = self.positions.get(&(board.board_size(), hash)) else {
+            return Vec::new();
+        };
+
+        let inverse = symmetry.inverse();
+        let size = board.board_size();
+        moves
+            .iter()
+            .map(|m| BookMove {
+                coords: m.coords.map(|c| inverse.apply(c)),
+                ..*m
+            })
+            .filter(|m| match m.coords {
+                Some(c) => c.is_valid_for(size) && board.stone_at(c.to_index(size)).is_none(),
+                None => board.can_swap(),
+            })
+            .collect()
+    }
+
+    /// Returns the most played book move for `board`, if the position is in the book.
+    pub fn best_move(&self, board: &GameY) -> Option<BookMove> {
+        most_played(self.lookup(board))
+    }
+
+    /// Returns the most played book placement for `board`, leaving swaps out.
+    pub fn best_placement(&self, board: &GameY) -> Option<BookMove> {
+        most_played(self.lookup(board).into_iter().filter(|m| !m.is_swap()))
+    }
+
+    /// Loads a book from a JSON file.
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let filename = path.as_ref().display().to_string();
+        let file_content = std::fs::read_to_string(path).map_err(|e| GameYError::IoError {
+            message: format!("Failed to read file: {}", filename),
+            error: e.to_string(),
+        })?;
+        let file: BookFile =
+            serde_json::from_str(&file_content).map_err(|e| GameYError::SerdeError { error: e })?;
+        let positions = file
+            .positions
+            .into_iter()
+            .map(|p| ((p.size, p.hash), p.moves))
+            .collect();
+        Ok(Self { positions })
+    }
+
+    /// Saves the book to a JSON file.
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut positions: Vec<BookPosition> = self
+            .positions
+            .iter()
+            .map(|(&(size, hash), moves)| BookPosition {
+                size,
+                hash,
+                moves: moves.clone(),
+            })
+            .collect();
+        positions.sort_by_key(|p| (p.size, p.hash));
+
+        let json_content = serde_json::to_string_pretty(&BookFile { positions })
+            .map_err(|e| GameYError::SerdeError { error: e })?;
+        let filename = path.as_ref().display().to_string();
+        std::fs::write(path, json_content).map_err(|e| GameYError::IoError {
+            message: format!("Failed to write file: {}", filename),
+            error: e.to_string(),
+        })?;
+        Ok(())
+    }
+
+    fn record(&mut self, board: &GameY, coords: Option<Coordinates>, won: bool) {
+        let (hash, symmetry) = board.canonical();
+        let coords = coords.map(|c| symmetry.apply(c));
+        let moves = self.positions.entry((board.board_size(), hash)).or_default();
+        let index = match moves.iter().position(|m| m.coords == coords) {
+            Some(index) => index,
+            None => {
+                moves.push(BookMove { coords, games: 0, wins: 0 });
+                moves.len() - 1
+            }
+        };
+        moves[index].games += 1;
+        moves[index].wins += won as u32;
+    }
+}
+
+/// A bot that plays from an opening book and falls back to another bot
+/// once the game leaves the book.
+///
+/// The wrapper keeps the name of the bot it wraps, so it can take its place
+/// in a [`YBotRegistry`](crate::YBotRegistry).
+pub struct BookBot {
+    inner: Arc<dyn YBot>,
+    book: Arc<OpeningBook>,
+}
+
+impl BookBot {
+    /// Wraps `inner` so that it plays the moves of `book` while it has them.
+    pub fn new(inner: Arc<dyn YBot>, book: Arc<OpeningBook>) -> Self {
+        Self { inner, book }
+    }
+
+    /// The book the bot plays from.
+    pub fn book(&self) -> &OpeningBook {
+        &self.book
+    }
+}
+
+impl YBot for BookBot {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        match self.book.best_placement(board) {
+            Some(book_move) => book_move.coords,
+            None => self.inner.choose_move(board),
+        }
+    }
+
+    fn search(&self, board: &GameY, limits: &SearchLimits) -> SearchResult {
+        let start = Instant::now();
+        match self.book.best_placement(board) {
+            Some(book_move) => book_analysis(&book_move, start.elapsed()),
+            None => self.inner.search(board, limits),
+        }
+    }
+
+    fn reseeded(&self, seed: u64) -> Option<Arc<dyn YBot>> {
+        let inner = self.inner.reseeded(seed)?;
+        Some(Arc::new(BookBot::new(inner, Arc::clone(&self.book))))
+    }
+
+    fn decide(&self, board: &GameY, limits: &SearchLimits) -> Option<BotDecision> {
+        let start = Instant::now();
+        let Some(book_move) = self.book.best_move(board) else {
+            return self.inner.decide(board, limits);
+        };
+        let player = board.next_player()?;
+        let movement = match book_move.coords {
+            Some(coords) => Movement::Placement { player, coords },
+            None => Movement::Action {
+                player,
+                action: GameAction::Swap,
+            },
+        };
+        Some(BotDecision {
+            movement,
+            analysis: book_analysis(&book_move, start.elapsed()),
+        })
+    }
+}
+
+fn book_analysis(book_move: &BookMove, elapsed: Duration) -> SearchResult {
+    SearchResult {
+        win_probability: Some(book_move.win_rate()),
+        ..SearchResult::from_move(book_move.coords, elapsed)
+    }
+}
+
+fn most_played(moves: impl IntoIterator<Item = BookMove>) -> Option<BookMove> {
+    moves.into_iter().max_by_key(|m| (m.games, m.wins))
+}
+
+fn winner(game: &GameY) -> Option<crate::PlayerId> {
+    match game.status() {
+        GameStatus::Finished { winner } => Some(*winner),
+        GameStatus::Ongoing { .. } => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PlayerId, RandomBot, SwapRule};
+
+    /// Plays `moves` (cell indices) in order, then resigns for the side to move
+    /// if the game is still going.
+    fn record(size: u32, moves: &[u32]) -> GameY {
+        let mut game = GameY::new(size).with_swap_rule(SwapRule::Disabled);
+        for &idx in moves {
+            let player = game.next_player().unwrap();
+            game.make_move(player, Coordinates::from_index(idx, size)).unwrap();
+        }
+        if let Some(player) = game.next_player() {
+            game.add_move(Movement::Action { player, action: GameAction::Resign })
+                .unwrap();
+        }
+        game
+    }
+
+    /// Bot that always plays the first free cell and counts its searches.
+    struct FirstCellBot {
+        searches: std::sync::atomic::AtomicUsize,
+    }
+
+    impl YBot for FirstCellBot {
+        fn name(&self) -> &str {
+            "first_cell"
+        }
+
+        fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+            self.searches.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            let idx = *board.available_cells().first()?;
+            Some(Coordinates::from_index(idx, board.board_size()))
+        }
+    }
+
+    #[test]
+    fn test_book_counts_games_and_wins() {
+        // The first two games open on cell 4 and the opener wins; the third opens on cell 0
+        let games = [record(3, &[4, 0]), record(3, &[4, 1]), record(3, &[0])];
+        let book = OpeningBook::from_games(&games, 2);
+
+        let moves = book.lookup(&GameY::new(3).with_swap_rule(SwapRule::Disabled));
+        let center = moves.iter().find(|m| m.coords == Some(Coordinates::from_index(4, 3))).unwrap();
+        assert_eq!((center.games, center.wins), (2, 0));
+        let corner = moves.iter().find(|m| m.coords == Some(Coordinates::from_index(0, 3))).unwrap();
+        assert_eq!((corner.games, corner.wins), (1, 1));
+
+        let best = book.best_move(&GameY::new(3).with_swap_rule(SwapRule::Disabled)).unwrap();
+        assert_eq!(best.coords, Some(Coordinates::from_index(4, 3)));
+        assert_eq!(best.win_rate(), 0.0);
+    }
+
+    #[test]
+    fn test_unfinished_games_are_ignored() {
+        let mut game = GameY::new(3);
+        game.make_move(PlayerId::new(0), Coordinates::new(1, 1, 0)).unwrap();
+
+        let mut book = OpeningBook::new();
+        assert!(!book.add_game(&game, 4));
+        assert!(book.is_empty());
+    }
+
+    #[test]
+    fn test_max_plies_bounds_the_book() {
+        let book = OpeningBook::from_games(&[record(4, &[0, 1, 2, 3])], 2);
+        // Empty board and the position after the first move
+        assert_eq!(book.len(), 2);
+    }
+
+    #[test]
+    fn test_symmetric_positions_share_an_entry() {
+        let size = 4;
+        // The three corners are symmetric: all of them map to the same book position
+        let corners = [
+            Coordinates::new(3, 0, 0),
+            Coordinates::new(0, 3, 0),
+            Coordinates::new(0, 0, 3),
+        ];
+        let games: Vec<GameY> = corners
+            .iter()
+            .map(|c| record(size, &[c.to_index(size), Coordinates::new(1, 1, 1).to_index(size)]))
+            .collect();
+        let book = OpeningBook::from_games(&games, 2);
+        assert_eq!(book.len(), 2);
+
+        // The stored reply is turned to the orientation of each board
+        for corner in corners {
+            let mut board = GameY::new(size).with_swap_rule(SwapRule::Disabled);
+            board.make_move(PlayerId::new(0), corner).unwrap();
+            let reply = book.best_move(&board).unwrap();
+            assert_eq!(reply.coords, Some(Coordinates::new(1, 1, 1)));
+            assert_eq!(reply.games, 3);
+        }
+    }
+
+    #[test]
+    fn test_reply_follows_the_reflection() {
+        let size = 4;
+        let game = record(size, &[
+            Coordinates::new(3, 0, 0).to_index(size),
+            Coordinates::new(2, 1, 0).to_index(size),
+        ]);
+        let book = OpeningBook::from_games([&game], 2);
+
+        // Reflection that keeps the x corner and exchanges y and z
+        let mut board = GameY::new(size).with_swap_rule(SwapRule::Disabled);
+        board.make_move(PlayerId::new(0), Coordinates::new(3, 0, 0)).unwrap();
+        assert_eq!(book.best_move(&board).unwrap().coords, Some(Coordinates::new(2, 1, 0)));
+
+        // At the y corner, the reply is the matching neighboring cell
+        let mut board = GameY::new(size).with_swap_rule(SwapRule::Disabled);
+        board.make_move(PlayerId::new(0), Coordinates::new(0, 3, 0)).unwrap();
+        let reply = book.best_move(&board).unwrap().coords.unwrap();
+        assert!(reply == Coordinates::new(1, 2, 0) || reply == Coordinates::new(0, 2, 1), "reply: {}", reply);
+    }
+
+    #[test]
+    fn test_swaps_are_recorded() {
+        let mut game = GameY::new(3);
+        game.make_move(PlayerId::new(0), Coordinates::new(1, 1, 0)).unwrap();
+        game.add_move(Movement::Action { player: PlayerId::new(1), action: GameAction::Swap })
+            .unwrap();
+        game.add_move(Movement::Action { player: PlayerId::new(0), action: GameAction::Resign })
+            .unwrap();
+        let book = OpeningBook::from_games([&game], 4);
+
+        let mut board = GameY::new(3);
+        board.make_move(PlayerId::new(0), Coordinates::new(1, 1, 0)).unwrap();
+        let best = book.best_move(&board).unwrap();
+        assert!(best.is_swap());
+        assert_eq!(best.wins, 1);
+        assert!(book.best_placement(&board).is_none());
+
+        // Without the swap rule the book does not offer the swap
+        let mut board = GameY::new(3).with_swap_rule(SwapRule::Disabled);
+        board.make_move(PlayerId::new(0), Coordinates::new(1, 1, 0)).unwrap();
+        assert!(book.best_move(&board).is_none());
+    }
+
+    #[test]
+    fn test_unknown_positions_have_no_moves() {
+        let book = OpeningBook::from_games([&record(3, &[4, 0])], 2);
+        let mut board = GameY::new(3).with_swap_rule(SwapRule::Disabled);
+        board.make_move(PlayerId::new(0), Coordinates::from_index(4, 3)).unwrap();
+        board.make_move(PlayerId::new(1), Coordinates::from_index(0, 3)).unwrap();
+        assert!(book.lookup(&board).is_empty());
+
+        // Positions are stored per board size
+        assert!(book.best_move(&GameY::new(4)).is_none());
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let book = OpeningBook::from_games(&[record(4, &[0, 5, 2]), record(4, &[9, 4])], 3);
+        let file = tempfile::NamedTempFile::new().unwrap();
+
+        book.save_to_file(file.path()).unwrap();
+        let loaded = OpeningBook::load_from_file(file.path()).unwrap();
+
+        assert_eq!(loaded.len(), book.len());
+        let board = GameY::new(4).with_swap_rule(SwapRule::Disabled);
+        assert_eq!(loaded.lookup(&board), book.lookup(&board));
+    }
+
+    #[test]
+    fn test_load_missing_file_fails() {
+        let result = OpeningBook::load_from_file("/nonexistent/book.json");
+        assert!(matches!(result, Err(GameYError::IoError { .. })));
+    }
+
+    #[test]
+    fn test_self_play_builds_a_book() {
+        let bot = RandomBot::new(None);
+        let mut book = OpeningBook::new();
+        book.add_self_play(&bot, 4, 10, 2, &SearchLimits::new(), 1);
+
+        let empty = GameY::new(4);
+        let total: u32 = book.lookup(&empty).iter().map(|m| m.games).sum();
+        assert_eq!(total, 10);
+
+        // Same seeds, same book
+        let mut again = OpeningBook::new();
+        again.add_self_play(&bot, 4, 10, 2, &SearchLimits::new(), 1);
+        assert_eq!(again.lookup(&empty), book.lookup(&empty));
+    }
+
+    #[test]
+    fn test_book_bot_plays_from_the_book() {
+        let book = Arc::new(OpeningBook::from_games([&record(4, &[5, 0])], 2));
+        let inner = Arc::new(FirstCellBot { searches: Default::default() });
+        let bot = BookBot::new(inner.clone(), book);
+        assert_eq!(bot.name(), "first_cell");
+
+        let mut board = GameY::new(4).with_swap_rule(SwapRule::Disabled);
+        let result = bot.search(&board, &SearchLimits::new());
+        assert_eq!(result.best_move, Some(Coordinates::from_index(5, 4)));
+        assert_eq!(result.win_probability, Some(0.0));
+        assert_eq!(inner.searches.load(std::sync::atomic::Ordering::Relaxed), 0);
+
+        // Out of the book, the wrapped bot decides
+        board.make_move(PlayerId::new(0), Coordinates::from_index(9, 4)).unwrap();
+        let decision = bot.decide(&board, &SearchLimits::new()).unwrap();
+        assert_eq!(
+            decision.movement,
+            Movement::Placement { player: PlayerId::new(1), coords: Coordinates::from_index(0, 4) }
+        );
+        assert_eq!(inner.searches.load(std::sync::atomic::Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_book_bot_swaps_from_the_book() {
+        let mut game = GameY::new(3);
+        game.make_move(PlayerId::new(0), Coordinates::new(1, 1, 0)).unwrap();
+        game.add_move(Movement::Action { player: PlayerId::new(1), action: GameAction::Swap })
+            .unwrap();
+        game.add_move(Movement::Action { player: PlayerId::new(0), action: GameAction::Resign })
+            .unwrap();
+        let bot = BookBot::new(
+            Arc::new(FirstCellBot { searches: Default::default() }),
+            Arc::new(OpeningBook::from_games([&game], 2)),
+        );
+
+        let mut board = GameY::new(3);
+        board.make_move(PlayerId::new(0), Coordinates::new(1, 1, 0)).unwrap();
+        let decision = bot.decide(&board, &SearchLimits::new()).unwrap();
+        assert_eq!(decision.action(), Some(&GameAction::Swap));
+        assert_eq!(decision.analysis.win_probability, Some(1.0));
+    }
+
+    #[test]
+    fn test_book_bot_reseeded_keeps_the_book() {
+        let book = Arc::new(OpeningBook::from_games([&record(4, &[5, 0])], 2));
+        let bot = BookBot::new(Arc::new(RandomBot::new(None)), book);
+
+        let replay = bot.reseeded(4).unwrap();
+        assert_eq!(replay.name(), "random_bot");
+        let board = GameY::new(4).with_swap_rule(SwapRule::Disabled);
+        assert_eq!(replay.choose_move(&board), Some(Coordinates::from_index(5, 4)));
+
+        let deterministic = BookBot::new(
+            Arc::new(FirstCellBot { searches: Default::default() }),
+            Arc::new(OpeningBook::new()),
+        );
+        assert!(deterministic.reseeded(4).is_none());
+    }
+}
@@ -0,0 +1,149 @@
+//! Search limits and analysis results for [`YBot::search`](crate::YBot::search).
+//!
+//! [`SearchLimits`] lets callers cap how long a bot thinks and
+//! [`SearchResult`] reports what the bot found: the move, its evaluation,
+//! the expected continuation and how much work the search took.
+
+use crate::Coordinates;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Limits for a single search. Limits left as `None` use the bot's own defaults.
+///
+/// Bots honour the limits that make sense for their algorithm: a node limit
+/// caps the iterations of a Monte Carlo bot and the evaluated positions of
+/// a tree search, while a depth limit is ignored by bots without depth.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchLimits {
+    /// Maximum thinking time in milliseconds.
+    pub max_time_ms: Option<u64>,
+    /// Maximum number of nodes (or iterations) to search.
+    pub max_nodes: Option<u64>,
+    /// Maximum search depth in plies.
+    pub max_depth: Option<u32>,
+}
+
+impl SearchLimits {
+    /// Creates limits that leave every bot with its defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum thinking time in milliseconds.
+    pub fn with_max_time_ms(mut self, max_time_ms: u64) -> Self {
+        self.max_time_ms = Some(max_time_ms);
+        self
+    }
+
+    /// Sets the maximum number of nodes (or iterations) to search.
+    pub fn with_max_nodes(mut self, max_nodes: u64) -> Self {
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    /// Sets the maximum search depth in plies.
+    pub fn with_max_depth(mut self, max_depth: u32) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Lowers every limit to the one in `max`. Limits that are not given stay
+    /// unset, so the bot still uses its own defaults for them.
+    pub fn clamped_to(self, max: &SearchLimits) -> Self {
+        fn clamp<T: Ord>(value: Option<T>, max: Option<T>) -> Option<T> {
+            match (value, max) {
+                (Some(value), Some(max)) => Some(value.min(max)),
+                (value, _) => value,
+            }
+        }
+        SearchLimits {
+            max_time_ms: clamp(self.max_time_ms, max.max_time_ms),
+            max_nodes: clamp(self.max_nodes, max.max_nodes),
+            max_depth: clamp(self.max_depth, max.max_depth),
+        }
+    }
+}
+
+/// Outcome of a search: the chosen move plus whatever the bot can report about it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchResult {
+    /// The chosen move, or `None` if the bot has no move to play.
+    pub best_move: Option<Coordinates>,
+    /// Evaluation of the position for the side to move, in the bot's own units.
+    pub score: Option<i32>,
+    /// Estimated probability that the side to move wins, between 0 and 1.
+    pub win_probability: Option<f64>,
+    /// Expected continuation, starting with `best_move`.
+    pub principal_variation: Vec<Coordinates>,
+    /// Depth reached by the search, for bots that search by depth.
+    pub depth: Option<u32>,
+    /// Nodes (or iterations) searched; 0 if the bot does not count them.
+    pub nodes: u64,
+    /// Time spent searching, in milliseconds.
+    pub elapsed_ms: u64,
+}
+
+impl SearchResult {
+    /// Creates a result holding only the move and the time it took.
+    pub fn from_move(best_move: Option<Coordinates>, elapsed: Duration) -> Self {
+        Self {
+            best_move,
+            score: None,
+            win_probability: None,
+            principal_variation: best_move.into_iter().collect(),
+            depth: None,
+            nodes: 0,
+            elapsed_ms: elapsed.as_millis() as u64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits_builder() {
+        let limits = SearchLimits::new()
+            .with_max_time_ms(500)
+            .with_max_nodes(1000)
+            .with_max_depth(3);
+        assert_eq!(limits.max_time_ms, Some(500));
+        assert_eq!(limits.max_nodes, Some(1000));
+        assert_eq!(limits.max_depth, Some(3));
+        assert_eq!(SearchLimits::new(), SearchLimits::default());
+    }
+
+    #[test]
+    fn test_limits_clamped_to() {
+        let max = SearchLimits::new().with_max_time_ms(1000).with_max_depth(8);
+        let limits = SearchLimits::new()
+            .with_max_time_ms(60_000)
+            .with_max_nodes(5)
+            .with_max_depth(3)
+            .clamped_to(&max);
+        assert_eq!(limits.max_time_ms, Some(1000));
+        assert_eq!(limits.max_nodes, Some(5));
+        assert_eq!(limits.max_depth, Some(3));
+        assert_eq!(SearchLimits::new().clamped_to(&max), SearchLimits::new());
+    }
+
+    #[test]
+    fn test_limits_deserialize_missing_fields() {
+        let limits: SearchLimits = serde_json::from_str(r#"{"max_time_ms":100}"#).unwrap();
+        assert_eq!(limits, SearchLimits::new().with_max_time_ms(100));
+    }
+
+    #[test]
+    fn test_result_from_move() {
+        let coords = Coordinates::new(1, 0, 1);
+        let result = SearchResult::from_move(Some(coords), Duration::from_millis(42));
+        assert_eq!(result.best_move, Some(coords));
+        assert_eq!(result.principal_variation, vec![coords]);
+        assert_eq!(result.elapsed_ms, 42);
+        assert_eq!(result.nodes, 0);
+
+        let none = SearchResult::from_move(None, Duration::ZERO);
+        assert!(none.principal_variation.is_empty());
+    }
+}
@@ -0,0 +1,419 @@
+//! Tables of exhaustively solved positions for small boards.
+//!
+//! [`SolvedTable::solve`] plays out every position reachable on boards up to
+//! a given size and stores, for each one, the moves that win for the side to
+//! move (none if it is lost). Positions are keyed by canonical hash, so the
+//! six symmetric images of a position share one entry. The table is the
+//! ground truth behind [`GameY::perfect_play`] and the optimal-play tests of
+//! the bots.
+//!
+//! Besides the positions of normal play, the table covers the positions that
+//! follow a swap: a single stone of the second player with the first to move.
+//!
+//! # File format
+//!
+//! Tables are saved in a compact little-endian binary format: the magic bytes
+//! `GYST`, a version byte and the number of board sizes, then for every size
+//! its value, the number of positions (`u32`) and the positions sorted by
+//! hash, each one as the canonical hash (`u64`) and the winning moves as a
+//! bit mask over the canonical cell indices (`u64`).
+
+use crate::{Coordinates, GameY, GameYError, MoveValidation, PlayerId, Result, SolveOutcome};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// Largest board size a table can hold. Size 5 is solved in seconds, while
+/// size 6 has too many positions to finish in any reasonable time.
+pub const MAX_TABLE_SIZE: u32 = 5;
+
+const MAGIC: &[u8; 4] = b"GYST";
+const VERSION: u8 = 1;
+
+/// The solved value of a position, for the side to move.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolvedPosition {
+    /// Whether the side to move wins or loses with perfect play.
+    pub outcome: SolveOutcome,
+    /// Every move that keeps the win, oriented to the board. Empty for a loss.
+    pub winning_moves: Vec<Coordinates>,
+}
+
+/// Solved positions for one or more board sizes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SolvedTable {
+    // Board size -> (canonical hash, winning-move mask), sorted by hash
+    sizes: BTreeMap<u32, Vec<(u64, u64)>>,
+}
+
+impl SolvedTable {
+    /// Creates an empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Solves every board size from 1 to `max_size`.
+    ///
+    /// Returns [`GameYError::SolvedTableError`] if `max_size` is above
+    /// [`MAX_TABLE_SIZE`].
+    pub fn solve(max_size: u32) -> Result<Self> {
+        check_size(max_size)?;
+        let mut table = Self::new();
+        for size in 1..=max_size {
+            table.solve_size(size)?;
+        }
+        Ok(table)
+    }
+
+    /// Solves every position of a board of `size`, replacing any previous
+    /// entries for that size.
+    pub fn solve_size(&mut self, size: u32) -> Result<()> {
+        check_size(size)?;
+
+        let mut memo = HashMap::new();
+        let mut board = GameY::new(size).with_validation(MoveValidation::Permissive);
+        solve_position(&mut board, &mut memo);
+        // Positions after a swap: one stone of the second player and the first to move
+        for cell_idx in 0..board.total_cells() {
+            let coords = Coordinates::from_index(cell_idx, size);
+            board.make_move(PlayerId::new(1), coords)?;
+            if !board.check_game_over() {
+                solve_position(&mut board, &mut memo);
+            }
+            board.unmake_move();
+        }
+
+        let mut entries: Vec<(u64, u64)> = memo.into_iter().collect();
+        entries.sort_unstable();
+        self.sizes.insert(size, entries);
+        Ok(())
+    }
+
+    /// Board sizes in the table.
+    pub fn sizes(&self) -> Vec<u32> {
+        self.sizes.keys().copied().collect()
+    }
+
+    /// Number of positions in the table, over all sizes.
+    pub fn len(&self) -> usize {
+        self.sizes.values().map(Vec::len).sum()
+    }
+
+    /// Returns true if the table has no positions.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the solved value of `board`, or `None` if its size is not in the
+    /// table, the game is over, or the position cannot arise in a game.
+    pub fn lookup(&self, board: &GameY) -> Option<SolvedPosition> {
+        if board.check_game_over() {
+            return None;
+        }
+        let size = board.board_size();
+        let entries = self.sizes.get(&size)?;
+        let (hash, symmetry) = board.canonical();
+        let index = entries.binary_search_by_key(&hash, |&(h, _)| h).ok()?;
+        let mask = entries[index].1;
+
+        let inverse = symmetry.inverse();
+        let mut winning_moves: Vec<Coordinates> = (0..board.total_cells())
+            .filter(|&cell_idx| mask & (1 << cell_idx) != 0)
+            .map(|cell_idx| inverse.apply(Coordinates::from_index(cell_idx, size)))
+            .collect();
+        winning_moves.sort_by_key(|c| c.to_index(size));
+        let outcome = if mask != 0 { SolveOutcome::Win } else { SolveOutcome::Loss };
+        Some(SolvedPosition { outcome, winning_moves })
+    }
+
+    /// Encodes the table in the binary file format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(6 + self.len() * 16);
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.push(self.sizes.len() as u8);
+        for (&size, entries) in &self.sizes {
+            bytes.push(size as u8);
+            bytes.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+            for &(hash, mask) in entries {
+                bytes.extend_from_slice(&hash.to_le_bytes());
+                bytes.extend_from_slice(&mask.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    /// Decodes a table from the binary file format.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader { bytes, pos: 0 };
+        if reader.take(4)? != MAGIC {
+            return Err(table_error("bad magic number"));
+        }
+        let version = reader.u8()?;
+        if version != VERSION {
+            return Err(table_error(&format!("unsupported version {}", version)));
+        }
+
+        let mut sizes = BTreeMap::new();
+        for _ in 0..reader.u8()? {
+            let size = reader.u8()? as u32;
+            check_size(size)?;
+            let count = u32::from_le_bytes(reader.array()?) as usize;
+            let mut entries = Vec::with_capacity(count.min(bytes.len() / 16));
+            for _ in 0..count {
+                let hash = u64::from_le_bytes(reader.array()?);
+                let mask = u64::from_le_bytes(reader.array()?);
+                entries.push((hash, mask));
+            }
+            if !entries.is_sorted() {
+                return Err(table_error(&format!("positions of size {} are not sorted", size)));
+            }
+            sizes.insert(size, entries);
+        }
+        if reader.pos != bytes.len() {
+            return Err(table_error("trailing bytes after the last size"));
+        }
+        Ok(Self { sizes })
+    }
+
+    /// Loads a table from a file.
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let filename = path.as_ref().display().to_string();
+        let bytes = std::fs::read(path).map_err(|e| GameYError::IoError {
+            message: format!("Failed to read file: {}", filename),
+            error: e.to_string(),
+        })?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Saves the table to a file.
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let filename = path.as_ref().display().to_string();
+        std::fs::write(path, self.to_bytes()).map_err(|e| GameYError::IoError {
+            message: format!("Failed to write file: {}", filename),
+            error: e.to_string(),
+        })?;
+        Ok(())
+    }
+}
+
+impl GameY {
+    /// Returns the perfect-play value of the position from a solved `table`:
+    /// whether the side to move wins and every move that keeps the win.
+    ///
+    /// Returns `None` if the table does not cover the position (see
+    /// [`SolvedTable::lookup`]).
+    pub fn perfect_play(&self, table: &SolvedTable) -> Option<SolvedPosition> {
+        table.lookup(self)
+    }
+}
+
+/// Solves the position by exhaustive negamax and returns true if the side to
+/// move wins. Every position below it ends up in `memo` by canonical hash.
+fn solve_position(board: &mut GameY, memo: &mut HashMap<u64, u64>) -> bool {
+    let (hash, symmetry) = board.canonical();
+    if let Some(&mask) = memo.get(&hash) {
+        return mask != 0;
+    }
+
+    let size = board.board_size();
+    let player = board.next_player().expect("only ongoing positions are solved");
+    let mut mask = 0u64;
+    for cell_idx in board.available_cells().clone() {
+        let coords = Coordinates::from_index(cell_idx, size);
+        board
+            .make_move(player, coords)
+            .expect("available cells are empty");
+        // A move that ends the game wins it for the player who made it
+        let wins = board.check_game_over() || !solve_position(board, memo);
+        board.unmake_move();
+        if wins {
+            mask |= 1 << symmetry.apply(coords).to_index(size);
+        }
+    }
+    memo.insert(hash, mask);
+    mask != 0
+}
+
+fn check_size(size: u32) -> Result<()> {
+    if size == 0 || size > MAX_TABLE_SIZE {
+        return Err(table_error(&format!(
+            "board size {} is outside 1..={}",
+            size, MAX_TABLE_SIZE
+        )));
+    }
+    Ok(())
+}
+
+fn table_error(message: &str) -> GameYError {
+    GameYError::SolvedTableError {
+        message: message.to_string(),
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let slice = self
+            .bytes
+            .get(self.pos..self.pos + len)
+            .ok_or_else(|| table_error("unexpected end of file"))?;
+        self.pos += len;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.take(N)?.try_into().expect("take returns N bytes"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameAction, Movement, SearchLimits, SwapRule, solve};
+    use rand::prelude::*;
+    use std::sync::OnceLock;
+
+    fn table() -> &'static SolvedTable {
+        static TABLE: OnceLock<SolvedTable> = OnceLock::new();
+        TABLE.get_or_init(|| SolvedTable::solve(4).unwrap())
+    }
+
+    /// Plays random moves from an empty board of size 4 until `stones` are
+    /// placed or the game ends.
+    fn random_position(rng: &mut StdRng, stones: usize) -> GameY {
+        let mut board = GameY::new(4).with_swap_rule(SwapRule::Disabled);
+        for _ in 0..stones {
+            let cells = board.available_cells().clone();
+            let cell_idx = cells[rng.random_range(0..cells.len())];
+            let player = board.next_player().unwrap();
+            board.make_move(player, Coordinates::from_index(cell_idx, 4)).unwrap();
+            if board.check_game_over() {
+                break;
+            }
+        }
+        board
+    }
+
+    #[test]
+    fn test_first_player_wins() {
+        for size in 1..=4 {
+            let solved = table().lookup(&GameY::new(size)).unwrap();
+            assert_eq!(solved.outcome, SolveOutcome::Win, "size {}", size);
+            assert!(!solved.winning_moves.is_empty());
+        }
+        assert_eq!(table().sizes(), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_agrees_with_solver() {
+        let mut rng = StdRng::seed_from_u64(23);
+        for _ in 0..60 {
+            let stones = rng.random_range(0..8);
+            let board = random_position(&mut rng, stones);
+            if board.check_game_over() {
+                assert!(table().lookup(&board).is_none());
+                continue;
+            }
+
+            let solved = table().lookup(&board).unwrap();
+            assert_eq!(solved.outcome, solve(&board, &SearchLimits::new()).outcome);
+
+            // Every winning move leaves the opponent lost, and no other move does
+            let player = board.next_player().unwrap();
+            for cell_idx in board.available_cells().clone() {
+                let coords = Coordinates::from_index(cell_idx, 4);
+                let mut next = board.clone();
+                next.make_move(player, coords).unwrap();
+                let wins = next.check_game_over()
+                    || table().lookup(&next).unwrap().outcome == SolveOutcome::Loss;
+                assert_eq!(wins, solved.winning_moves.contains(&coords), "{}", coords);
+            }
+        }
+    }
+
+    #[test]
+    fn test_symmetric_positions_have_symmetric_answers() {
+        let mut board = GameY::new(4);
+        board.make_move(PlayerId::new(0), Coordinates::new(3, 0, 0)).unwrap();
+        let mut mirrored = GameY::new(4);
+        mirrored.make_move(PlayerId::new(0), Coordinates::new(0, 3, 0)).unwrap();
+
+        let mut expected: Vec<Coordinates> = table()
+            .lookup(&board)
+            .unwrap()
+            .winning_moves
+            .iter()
+            .map(|c| Coordinates::new(c.y(), c.x(), c.z()))
+            .collect();
+        expected.sort_by_key(|c| c.to_index(4));
+        assert_eq!(table().lookup(&mirrored).unwrap().winning_moves, expected);
+    }
+
+    #[test]
+    fn test_covers_positions_after_a_swap() {
+        let mut board = GameY::new(4);
+        board.make_move(PlayerId::new(0), Coordinates::new(1, 1, 1)).unwrap();
+        board
+            .add_move(Movement::Action { player: PlayerId::new(1), action: GameAction::Swap })
+            .unwrap();
+
+        let solved = board.perfect_play(table()).unwrap();
+        assert_eq!(solved.outcome, solve(&board, &SearchLimits::new()).outcome);
+    }
+
+    #[test]
+    fn test_unreachable_positions_are_not_covered() {
+        // Two stones of the first player and none of the second
+        let mut board = GameY::new(4).with_validation(MoveValidation::Permissive);
+        board.make_move(PlayerId::new(0), Coordinates::new(3, 0, 0)).unwrap();
+        board.make_move(PlayerId::new(0), Coordinates::new(0, 3, 0)).unwrap();
+        assert!(table().lookup(&board).is_none());
+
+        assert!(table().lookup(&GameY::new(5)).is_none());
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let table = SolvedTable::solve(3).unwrap();
+        let bytes = table.to_bytes();
+        assert_eq!(&bytes[..4], b"GYST");
+        assert_eq!(bytes.len(), 6 + 3 * 5 + table.len() * 16);
+        assert_eq!(SolvedTable::from_bytes(&bytes).unwrap(), table);
+    }
+
+    #[test]
+    fn test_file_round_trip() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        table().save_to_file(file.path()).unwrap();
+        let loaded = SolvedTable::load_from_file(file.path()).unwrap();
+        assert_eq!(&loaded, table());
+    }
+
+    #[test]
+    fn test_rejects_corrupt_bytes() {
+        let bytes = SolvedTable::solve(2).unwrap().to_bytes();
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'X';
+        assert!(matches!(SolvedTable::from_bytes(&bad_magic), Err(GameYError::SolvedTableError { .. })));
+        assert!(SolvedTable::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(SolvedTable::from_bytes(&trailing).is_err());
+    }
+
+    #[test]
+    fn test_rejects_unsupported_sizes() {
+        assert!(SolvedTable::solve(MAX_TABLE_SIZE + 1).is_err());
+        assert!(SolvedTable::new().solve_size(0).is_err());
+        assert!(SolvedTable::new().is_empty());
+    }
+}
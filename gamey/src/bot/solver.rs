@@ -0,0 +1,418 @@
+//! Exact endgame solver based on depth-first proof-number search (df-pn).
+//!
+//! [`solve`] proves whether the side to move wins or loses a position by
+//! playing it out on a copy of the board: the engine's incremental union-find
+//! tells when a move ends the game. Y has no draws and a position never
+//! repeats, so every finished line is a proof for one of the players. The
+//! search keeps its proof and disproof numbers in a table keyed by Zobrist
+//! hash, so transpositions are only proved once.
+//!
+//! Bots use the solver as an endgame phase: once few cells are left they
+//! try to prove the position before searching it (see [`DEFAULT_ENDGAME_CELLS`]).
+
+use crate::{Coordinates, GameY, MoveValidation, SearchLimits, SearchResult};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Empty cells at or below which searching bots try the solver first.
+pub const DEFAULT_ENDGAME_CELLS: u32 = 9;
+
+/// Node limit of [`solve`] when the limits give neither nodes nor time.
+pub const DEFAULT_SOLVER_NODES: u64 = 1_000_000;
+
+/// How often, in nodes, the solver looks at the clock.
+const TIME_CHECK_INTERVAL: u64 = 1024;
+
+/// Proof or disproof number of a decided position.
+const INFINITY: u64 = u64::MAX / 4;
+
+/// Result of a solved position, for the side to move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolveOutcome {
+    /// The side to move wins with perfect play.
+    Win,
+    /// The side to move loses against perfect play.
+    Loss,
+    /// The limits ran out before the position was solved.
+    Unknown,
+}
+
+/// What the solver found about a position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solution {
+    /// Whether the side to move wins, loses, or the search ran out of budget.
+    pub outcome: SolveOutcome,
+    /// A winning move when the outcome is [`SolveOutcome::Win`].
+    pub winning_move: Option<Coordinates>,
+    /// A line of perfect play from the position, when it is solved.
+    pub principal_variation: Vec<Coordinates>,
+    /// Positions expanded by the search.
+    pub nodes: u64,
+    /// Time spent searching, in milliseconds.
+    pub elapsed_ms: u64,
+}
+
+impl Solution {
+    /// Returns true if the position was proved a win or a loss.
+    pub fn is_solved(&self) -> bool {
+        self.outcome != SolveOutcome::Unknown
+    }
+
+    /// Probability that the side to move wins: 1 or 0 if solved, `None` otherwise.
+    pub fn win_probability(&self) -> Option<f64> {
+        match self.outcome {
+            SolveOutcome::Win => Some(1.0),
+            SolveOutcome::Loss => Some(0.0),
+            SolveOutcome::Unknown => None,
+        }
+    }
+
+    /// Converts the solution into a search result. Only a win has a move to play.
+    pub fn to_search_result(&self) -> SearchResult {
+        SearchResult {
+            win_probability: self.win_probability(),
+            principal_variation: self.principal_variation.clone(),
+            nodes: self.nodes,
+            ..SearchResult::from_move(self.winning_move, Duration::from_millis(self.elapsed_ms))
+        }
+    }
+}
+
+/// Tries to prove the position for the side to move within `limits`.
+///
+/// The node and time limits are honoured; the depth limit is ignored. With
+/// neither nodes nor time given, the search stops after
+/// [`DEFAULT_SOLVER_NODES`] nodes. Finished games have no side to move and
+/// come back as [`SolveOutcome::Unknown`].
+pub fn solve(board: &GameY, limits: &SearchLimits) -> Solution {
+    let start = Instant::now();
+    let mut board = board.clone().with_validation(MoveValidation::Permissive);
+    if board.check_game_over() {
+        return Solution {
+            outcome: SolveOutcome::Unknown,
+            winning_move: None,
+            principal_variation: Vec::new(),
+            nodes: 0,
+            elapsed_ms: start.elapsed().as_millis() as u64,
+        };
+    }
+
+    let max_nodes = match (limits.max_nodes, limits.max_time_ms) {
+        (None, None) => Some(DEFAULT_SOLVER_NODES),
+        (max_nodes, _) => max_nodes,
+    };
+    let mut solver = Solver {
+        table: HashMap::new(),
+        nodes: 0,
+        max_nodes,
+        deadline: limits.max_time_ms.map(|ms| start + Duration::from_millis(ms)),
+        aborted: false,
+    };
+    let (pn, dn) = solver.mid(&mut board, INFINITY, INFINITY);
+
+    let outcome = if pn == 0 {
+        SolveOutcome::Win
+    } else if dn == 0 {
+        SolveOutcome::Loss
+    } else {
+        SolveOutcome::Unknown
+    };
+    let principal_variation = if outcome == SolveOutcome::Unknown {
+        Vec::new()
+    } else {
+        solver.principal_variation(&mut board)
+    };
+    let winning_move = match outcome {
+        SolveOutcome::Win => principal_variation.first().copied(),
+        _ => None,
+    };
+
+    Solution {
+        outcome,
+        winning_move,
+        principal_variation,
+        nodes: solver.nodes,
+        elapsed_ms: start.elapsed().as_millis() as u64,
+    }
+}
+
+struct Solver {
+    // Proof and disproof numbers of every expanded position, for its side to move
+    table: HashMap<u64, (u64, u64)>,
+    nodes: u64,
+    max_nodes: Option<u64>,
+    deadline: Option<Instant>,
+    aborted: bool,
+}
+
+impl Solver {
+    /// Expands the position until its proof number reaches `th_pn` or its
+    /// disproof number reaches `th_dn`, and returns both numbers.
+    fn mid(&mut self, board: &mut GameY, th_pn: u64, th_dn: u64) -> (u64, u64) {
+        self.nodes += 1;
+        self.check_limits();
+        let moves = board.available_cells().clone();
+
+        loop {
+            // Negamax form: a position is proved when some reply is disproved
+            // for the opponent, and disproved when every reply is proved
+            let mut pn = INFINITY;
+            let mut dn = 0;
+            let mut best = None;
+            let mut best_dn = INFINITY;
+            let mut best_pn = INFINITY;
+            let mut second_dn = INFINITY;
+            for &cell_idx in &moves {
+                let (child_pn, child_dn) = self.child_numbers(board, cell_idx);
+                pn = pn.min(child_dn);
+                dn = (dn + child_pn).min(INFINITY);
+                if best.is_none() || child_dn < best_dn {
+                    second_dn = best_dn;
+                    best_dn = child_dn;
+                    best_pn = child_pn;
+                    best = Some(cell_idx);
+                } else if child_dn < second_dn {
+                    second_dn = child_dn;
+                }
+            }
+            self.table.insert(board.zobrist_hash(), (pn, dn));
+
+            if pn >= th_pn || dn >= th_dn || self.aborted {
+                return (pn, dn);
+            }
+            let Some(cell_idx) = best else {
+                return (pn, dn);
+            };
+
+            let child_th_pn = (th_dn - dn).saturating_add(best_pn).min(INFINITY);
+            let child_th_dn = th_pn.min(second_dn.saturating_add(1));
+            self.play(board, cell_idx);
+            self.mid(board, child_th_pn, child_th_dn);
+            board.unmake_move();
+        }
+    }
+
+    /// Numbers of the position after playing `cell_idx`, for its side to move.
+    fn child_numbers(&self, board: &mut GameY, cell_idx: u32) -> (u64, u64) {
+        self.play(board, cell_idx);
+        let numbers = if board.check_game_over() {
+            // The move won: the side to move in the child has lost
+            (INFINITY, 0)
+        } else {
+            self.table.get(&board.zobrist_hash()).copied().unwrap_or((1, 1))
+        };
+        board.unmake_move();
+        numbers
+    }
+
+    fn play(&self, board: &mut GameY, cell_idx: u32) {
+        let player = board.next_player().expect("the solver only plays in ongoing games");
+        let coords = Coordinates::from_index(cell_idx, board.board_size());
+        board
+            .make_move(player, coords)
+            .expect("the solver only plays on empty cells");
+    }
+
+    /// Follows the proof from a solved position: the winning reply where the
+    /// side to move wins, the longest resistance where it loses.
+    fn principal_variation(&self, board: &mut GameY) -> Vec<Coordinates> {
+        let mut pv = Vec::new();
+        while !board.check_game_over() {
+            let moves = board.available_cells().clone();
+            let numbers: Vec<(u32, (u64, u64))> = moves
+                .iter()
+                .map(|&cell_idx| (cell_idx, self.child_numbers(board, cell_idx)))
+                .collect();
+            // A disproved reply wins; otherwise the reply that was hardest to prove
+            let next = numbers
+                .iter()
+                .find(|(_, (_, dn))| *dn == 0)
+                .or_else(|| {
+                    numbers
+                        .iter()
+                        .filter(|(_, (pn, _))| *pn == 0)
+                        .max_by_key(|(_, (_, dn))| *dn)
+                })
+                .map(|&(cell_idx, _)| cell_idx);
+            let Some(cell_idx) = next else {
+                break;
+            };
+            pv.push(Coordinates::from_index(cell_idx, board.board_size()));
+            self.play(board, cell_idx);
+        }
+        for _ in 0..pv.len() {
+            board.unmake_move();
+        }
+        pv
+    }
+
+    fn check_limits(&mut self) {
+        if let Some(max_nodes) = self.max_nodes
+            && self.nodes > max_nodes
+        {
+            self.aborted = true;
+        }
+        if self.nodes.is_multiple_of(TIME_CHECK_INTERVAL)
+            && let Some(deadline) = self.deadline
+            && Instant::now() >= deadline
+        {
+            self.aborted = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameStatus, Movement, PlayerId, YEN};
+    use rand::prelude::*;
+
+    fn position(size: u32, turn: u32, layout: &str) -> GameY {
+        GameY::try_from(YEN::new(size, turn, vec!['B', 'R'], layout.to_string())).unwrap()
+    }
+
+    /// Exhaustive negamax: true if the side to move wins.
+    fn brute_force_wins(board: &mut GameY) -> bool {
+        let player = board.next_player().unwrap();
+        for cell_idx in board.available_cells().clone() {
+            board.make_move(player, Coordinates::from_index(cell_idx, board.board_size())).unwrap();
+            let wins = match board.status() {
+                GameStatus::Finished { winner } => *winner == player,
+                GameStatus::Ongoing { .. } => !brute_force_wins(board),
+            };
+            board.unmake_move();
+            if wins {
+                return true;
+            }
+        }
+        false
+    }
+
+    #[test]
+    fn test_finds_immediate_win() {
+        // B connects the three sides with (0,0,2)
+        let board = position(3, 0, "B/BR/.R.");
+        let solution = solve(&board, &SearchLimits::new());
+        assert_eq!(solution.outcome, SolveOutcome::Win);
+        assert_eq!(solution.winning_move, Some(Coordinates::new(0, 0, 2)));
+        assert_eq!(solution.principal_variation, vec![Coordinates::new(0, 0, 2)]);
+        assert_eq!(solution.win_probability(), Some(1.0));
+    }
+
+    #[test]
+    fn test_finds_mate_in_two() {
+        let board = position(3, 0, "B/../R..");
+        let solution = solve(&board, &SearchLimits::new());
+
+        assert_eq!(solution.outcome, SolveOutcome::Win);
+        let winning = solution.winning_move.unwrap();
+        assert!(
+            winning == Coordinates::new(1, 1, 0) || winning == Coordinates::new(0, 1, 1),
+            "winning: {}",
+            winning
+        );
+        assert_eq!(solution.principal_variation.len(), 3);
+
+        // The line ends in a win for the side to move
+        let mut replay = board.clone();
+        for &coords in &solution.principal_variation {
+            let player = replay.next_player().unwrap();
+            replay.add_move(Movement::Placement { player, coords }).unwrap();
+        }
+        assert_eq!(replay.status(), &GameStatus::Finished { winner: PlayerId::new(0) });
+    }
+
+    #[test]
+    fn test_proves_loss() {
+        // R to move, but B threatens two wins at once
+        let board = position(3, 1, "B/B./R.B");
+        let mut check = board.clone();
+        assert!(!brute_force_wins(&mut check));
+
+        let solution = solve(&board, &SearchLimits::new());
+        assert_eq!(solution.outcome, SolveOutcome::Loss);
+        assert_eq!(solution.winning_move, None);
+        assert_eq!(solution.win_probability(), Some(0.0));
+        assert!(!solution.principal_variation.is_empty());
+    }
+
+    #[test]
+    fn test_first_player_wins_small_boards() {
+        for size in 1..=4 {
+            let solution = solve(&GameY::new(size), &SearchLimits::new());
+            assert_eq!(solution.outcome, SolveOutcome::Win, "size {}", size);
+        }
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let mut rng = StdRng::seed_from_u64(5);
+        for _ in 0..40 {
+            let mut board = GameY::new(4);
+            let stones = rng.random_range(0..6);
+            for _ in 0..stones {
+                let cells = board.available_cells().clone();
+                let cell_idx = cells[rng.random_range(0..cells.len())];
+                let player = board.next_player().unwrap();
+                board.make_move(player, Coordinates::from_index(cell_idx, 4)).unwrap();
+                if board.check_game_over() {
+                    break;
+                }
+            }
+            if board.check_game_over() {
+                continue;
+            }
+
+            let expected = brute_force_wins(&mut board.clone());
+            let solution = solve(&board, &SearchLimits::new());
+            let expected = if expected { SolveOutcome::Win } else { SolveOutcome::Loss };
+            assert_eq!(solution.outcome, expected, "{}", board.render(&Default::default()));
+
+            // The winning move leads to a lost position for the opponent
+            if let Some(coords) = solution.winning_move {
+                let player = board.next_player().unwrap();
+                board.make_move(player, coords).unwrap();
+                assert!(board.check_game_over() || !brute_force_wins(&mut board));
+            }
+        }
+    }
+
+    #[test]
+    fn test_node_limit_gives_unknown() {
+        let solution = solve(&GameY::new(9), &SearchLimits::new().with_max_nodes(50));
+        assert_eq!(solution.outcome, SolveOutcome::Unknown);
+        assert!(!solution.is_solved());
+        assert_eq!(solution.winning_move, None);
+        assert!(solution.principal_variation.is_empty());
+        assert!(solution.nodes <= 51);
+    }
+
+    #[test]
+    fn test_time_limit_stops_search() {
+        let start = Instant::now();
+        let solution = solve(&GameY::new(12), &SearchLimits::new().with_max_time_ms(50));
+        assert_eq!(solution.outcome, SolveOutcome::Unknown);
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_finished_game_is_unknown() {
+        let mut board = GameY::new(3);
+        board
+            .add_move(Movement::Action { player: PlayerId::new(0), action: crate::GameAction::Resign })
+            .unwrap();
+        let solution = solve(&board, &SearchLimits::new());
+        assert_eq!(solution.outcome, SolveOutcome::Unknown);
+        assert_eq!(solution.nodes, 0);
+    }
+
+    #[test]
+    fn test_to_search_result() {
+        let solution = solve(&position(3, 0, "B/BR/.R."), &SearchLimits::new());
+        let result = solution.to_search_result();
+        assert_eq!(result.best_move, Some(Coordinates::new(0, 0, 2)));
+        assert_eq!(result.win_probability, Some(1.0));
+        assert_eq!(result.principal_variation, solution.principal_variation);
+        assert_eq!(result.nodes, solution.nodes);
+    }
+}
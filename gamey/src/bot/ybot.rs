@@ -1,4 +1,6 @@
-use crate::{Coordinates, GameY};
+use crate::{BotDecision, Coordinates, GameY, Movement, SearchLimits, SearchResult};
+use std::sync::Arc;
+use std::time::Instant;
 
 /// Trait representing a Y game bot (YBot)
 /// A YBot is an AI that can choose moves in the game of Y.
@@ -9,4 +11,43 @@ pub trait YBot: Send + Sync {
 
     /// Chooses a move based on the current game state.
     fn choose_move(&self, board: &GameY) -> Option<Coordinates>;
+
+    /// Searches the current game state within `limits` and reports the analysis.
+    ///
+    /// The default implementation ignores the limits and wraps [`YBot::choose_move`];
+    /// searching bots override it to honour the limits and fill in the evaluation.
+    fn search(&self, board: &GameY, limits: &SearchLimits) -> SearchResult {
+        let _ = limits;
+        let start = Instant::now();
+        let best_move = self.choose_move(board);
+        SearchResult::from_move(best_move, start.elapsed())
+    }
+
+    /// Returns a fresh copy of the bot that draws its random numbers from `seed`,
+    /// so that its moves can be replayed.
+    ///
+    /// The copy starts without the state the bot keeps between searches (search
+    /// trees, transposition tables) and searches on a single thread, so a search
+    /// bounded by nodes plays the same move for the same seed. Searches bounded
+    /// by time may still differ between runs. Bots that use no randomness and
+    /// keep no state return `None`: they already play the same move in the
+    /// same position.
+    fn reseeded(&self, seed: u64) -> Option<Arc<dyn YBot>> {
+        let _ = seed;
+        None
+    }
+
+    /// Decides what to play on the current turn: a placement, a swap or a resignation.
+    ///
+    /// The default implementation always places the move found by [`YBot::search`].
+    /// Returns `None` if the game is over or there is nothing to play.
+    fn decide(&self, board: &GameY, limits: &SearchLimits) -> Option<BotDecision> {
+        let player = board.next_player()?;
+        let analysis = self.search(board, limits);
+        let coords = analysis.best_move?;
+        Some(BotDecision {
+            movement: Movement::Placement { player, coords },
+            analysis,
+        })
+    }
 }
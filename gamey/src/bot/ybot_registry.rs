@@ -5,7 +5,7 @@
 
 use std::{collections::HashMap, sync::Arc};
 
-use crate::{MonteCarloBot, YBot};
+use crate::{BookBot, MonteCarloBot, OpeningBook, YBot};
 use crate::{RandomBot, HeuristicBot, MinimaxBot, AlfaBetaBot};
 use crate::bot_implementations::MonteCarloDifficulty;
 
@@ -21,7 +21,7 @@ use crate::bot_implementations::MonteCarloDifficulty;
 /// use gamey::{YBotRegistry, RandomBot};
 ///
 /// let registry = YBotRegistry::new()
-///     .with_bot(Arc::new(RandomBot));
+///     .with_bot(Arc::new(RandomBot::new(None)));
 ///
 /// let bot = registry.find("random_bot");
 /// assert!(bot.is_some());
@@ -37,12 +37,13 @@ impl YBotRegistry {
             bots: HashMap::new(),
         };
         registry = registry
-            .with_bot(Arc::new(RandomBot))
+            .with_bot(Arc::new(RandomBot::new(None)))
             .with_bot(Arc::new(HeuristicBot))
             .with_bot(Arc::new(MinimaxBot::new(None)))
             .with_bot(Arc::new(AlfaBetaBot::new(None)))
             .with_bot(Arc::new(MonteCarloBot::new(MonteCarloDifficulty::Hard)))
-            .with_bot(Arc::new(MonteCarloBot::new(MonteCarloDifficulty::Extreme)));
+            .with_bot(Arc::new(MonteCarloBot::new(MonteCarloDifficulty::Extreme)))
+            .with_bot(Arc::new(MonteCarloBot::new(MonteCarloDifficulty::Rave)));
 
         registry
     }
@@ -69,6 +70,25 @@ impl YBotRegistry {
         self.bots.get(name).cloned()
     }
 
+    /// Finds a bot by name, seeded with `seed` so that its move can be replayed.
+    ///
+    /// Bots that use randomness or keep state between searches are returned as
+    /// a fresh copy drawing from `seed` (see [`YBot::reseeded`]); other bots are
+    /// returned as registered.
+    pub fn find_seeded(&self, name: &str, seed: u64) -> Option<Arc<dyn YBot>> {
+        let bot = self.find(name)?;
+        Some(bot.reseeded(seed).unwrap_or(bot))
+    }
+
+    /// Wraps every registered bot in a [`BookBot`], so that all of them play
+    /// the moves of `book` while the game is in it.
+    pub fn with_opening_book(mut self, book: Arc<OpeningBook>) -> Self {
+        for bot in self.bots.values_mut() {
+            *bot = Arc::new(BookBot::new(Arc::clone(bot), Arc::clone(&book)));
+        }
+        self
+    }
+
     /// Returns a list of all registered bot names.
     pub fn names(&self) -> Vec<String> {
         self.bots.keys().cloned().collect()
@@ -119,7 +139,7 @@ mod tests {
     fn test_default_registry_has_bots() {
         let registry = YBotRegistry::default();
         assert!(!registry.names().is_empty());
-        assert_eq!(registry.names().len(), 6);  // 6 bots
+        assert_eq!(registry.names().len(), 7);  // 7 bots
     }
 
     #[test]
@@ -163,9 +183,48 @@ mod tests {
 
     #[test]
     fn test_with_random_bot() {
-        let registry = YBotRegistry::new().with_bot(Arc::new(RandomBot));
+        let registry = YBotRegistry::new().with_bot(Arc::new(RandomBot::new(None)));
 
         assert!(registry.find("random_bot").is_some());
     }
 
+    #[test]
+    fn test_find_seeded_replays_random_bot() {
+        let registry = YBotRegistry::new();
+        let game = GameY::new(9);
+
+        let first = registry.find_seeded("random_bot", 99).unwrap().choose_move(&game);
+        let second = registry.find_seeded("random_bot", 99).unwrap().choose_move(&game);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_with_opening_book_wraps_every_bot() {
+        let mut record = GameY::new(4);
+        record.make_move(crate::PlayerId::new(0), Coordinates::new(1, 1, 1)).unwrap();
+        record.add_move(crate::Movement::Action {
+            player: crate::PlayerId::new(1),
+            action: crate::GameAction::Resign,
+        })
+        .unwrap();
+        let book = Arc::new(OpeningBook::from_games([&record], 1));
+
+        let registry = YBotRegistry::new().with_opening_book(book);
+        assert_eq!(registry.names().len(), 7);
+        for name in registry.names() {
+            let bot = registry.find(&name).unwrap();
+            assert_eq!(bot.name(), name);
+            assert_eq!(bot.choose_move(&GameY::new(4)), Some(Coordinates::new(1, 1, 1)));
+        }
+    }
+
+    #[test]
+    fn test_find_seeded_keeps_deterministic_bots() {
+        let registry = YBotRegistry::new_empty().with_bot(Arc::new(MockBot::new("mock")));
+
+        let registered = registry.find("mock").unwrap();
+        let seeded = registry.find_seeded("mock", 1).unwrap();
+        assert!(Arc::ptr_eq(&registered, &seeded));
+        assert!(registry.find_seeded("missing", 1).is_none());
+    }
 }
@@ -1,18 +1,23 @@
 //! Command-line interface for the Y game.
 //!
 //! This module provides the CLI application for playing Y games interactively.
-//! It supports three modes:
+//! It supports five modes:
 //! - Human vs Human: Two players take turns at the same terminal
 //! - Human vs Computer: Play against a bot
 //! - Server: Run as an HTTP server for bot API
+//! - Book: Build an opening book from bot self-play
+//! - Solve: Build a table of solved positions for small boards
+//!
+//! The `arena` subcommand plays matches between bots instead of a game.
 
-use crate::{Coordinates, GameAction, Movement, AlfaBetaBot, MonteCarloBot, RenderOptions, YBot, YBotRegistry, game};
+use crate::{Coordinates, GameAction, Movement, AlfaBetaBot, ArenaConfig, BookBot, OpeningBook, RenderOptions, SearchLimits, SearchResult, SolvedTable, YBot, YBotRegistry, game, MAX_TABLE_SIZE};
 use crate::{GameStatus, GameY, PlayerId};
 use anyhow::Result;
-use clap::{Parser, ValueEnum};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use rustyline::DefaultEditor;
 use rustyline::error::ReadlineError;
 use std::fmt::Display;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 /// Command-line arguments for the GameY application.
@@ -24,7 +29,8 @@ pub struct CliArgs {
     #[arg(short, long, default_value_t = 7)]
     pub size: u32,
 
-    /// Game mode: human (2-player), computer (vs bot), or server (HTTP API).
+    /// Game mode: human (2-player), computer (vs bot), server (HTTP API), book (build an opening book)
+    /// or solve (build a solved-position table for every board size up to --size).
     #[arg(short, long, default_value_t = Mode::Human)]
     pub mode: Mode,
 
@@ -35,6 +41,88 @@ pub struct CliArgs {
     /// Port to run the server on (only used with --mode=server)
     #[arg(short, long, default_value_t = 3000)]
     pub port: u16,
+
+    /// Search threads of each Monte Carlo bot (only used with --mode=server), default = one per core, up to 4
+    #[arg(long)]
+    pub search_threads: Option<usize>,
+
+    /// Maximum bot thinking time per move in milliseconds (used with --mode=computer, --mode=book and arena)
+    #[arg(long, global = true)]
+    pub time_ms: Option<u64>,
+
+    /// Maximum nodes or iterations the bot may search per move (used with --mode=computer, --mode=book and arena)
+    #[arg(long, global = true)]
+    pub nodes: Option<u64>,
+
+    /// Maximum bot search depth (used with --mode=computer, --mode=book and arena)
+    #[arg(long, global = true)]
+    pub depth: Option<u32>,
+
+    /// Seed for the bot's randomness, to replay a game (used with --mode=computer, --mode=book and arena)
+    #[arg(long, global = true)]
+    pub seed: Option<u64>,
+
+    /// Opening book file the bots play from; with --mode=book, the file to build or extend
+    #[arg(long)]
+    pub book: Option<PathBuf>,
+
+    /// Number of self-play games to add to the book (only used with --mode=book)
+    #[arg(long, default_value_t = 20)]
+    pub games: u32,
+
+    /// Number of plies of each game recorded in the book (only used with --mode=book)
+    #[arg(long, default_value_t = 6)]
+    pub plies: usize,
+
+    /// File to write the solved-position table to (only used with --mode=solve)
+    #[arg(long)]
+    pub table: Option<PathBuf>,
+
+    /// A tool to run instead of a game.
+    #[command(subcommand)]
+    pub tool: Option<Tool>,
+}
+
+impl CliArgs {
+    /// Returns the bot search limits given on the command line.
+    pub fn search_limits(&self) -> SearchLimits {
+        SearchLimits {
+            max_time_ms: self.time_ms,
+            max_nodes: self.nodes,
+            max_depth: self.depth,
+        }
+    }
+}
+
+/// Tools run instead of a game, as subcommands.
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum Tool {
+    /// Play matches between bots and report win rates and Elo estimates.
+    Arena(ArenaArgs),
+}
+
+/// Arguments of the `arena` subcommand.
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct ArenaArgs {
+    /// Bots to play against each other, comma separated; every pair plays a match
+    #[arg(long, value_delimiter = ',', required = true, num_args = 1..)]
+    pub bots: Vec<String>,
+
+    /// Board sizes to play every match on, comma separated
+    #[arg(long, value_delimiter = ',', default_value = "7")]
+    pub sizes: Vec<u32>,
+
+    /// Games of each match on each board size
+    #[arg(long, default_value_t = 10)]
+    pub games: u32,
+
+    /// Games played at the same time (default: one per core)
+    #[arg(long)]
+    pub threads: Option<usize>,
+
+    /// JSON lines file to write every game record to
+    #[arg(long)]
+    pub records: Option<PathBuf>,
 }
 
 /// The game mode determining how the game is played.
@@ -46,6 +134,10 @@ pub enum Mode {
     Human,
     /// Run as an HTTP server for bot API.
     Server,
+    /// Build an opening book from bot self-play.
+    Book,
+    /// Solve every position of the small boards and save them as a table.
+    Solve,
 }
 
 impl Display for Mode {
@@ -54,6 +146,8 @@ impl Display for Mode {
             Mode::Computer => "computer",
             Mode::Human => "human",
             Mode::Server => "server",
+            Mode::Book => "book",
+            Mode::Solve => "solve",
         };
         write!(f, "{}", s)
     }
@@ -68,7 +162,8 @@ pub fn run_cli_game() -> Result<()> {
     let mut render_options = crate::RenderOptions::default();
     let mut rl = DefaultEditor::new()?;
     let bots_registry = YBotRegistry::new().with_bot(Arc::new(AlfaBetaBot::new(None)));
-    let bot: Arc<dyn YBot> = match bots_registry.find(&args.bot) {
+    let seed = args.seed.unwrap_or_else(rand::random);
+    let bot: Arc<dyn YBot> = match bots_registry.find_seeded(&args.bot, seed) {
         Some(b) => b,
         None => {
             println!(
@@ -79,6 +174,14 @@ pub fn run_cli_game() -> Result<()> {
             return Ok(());
         }
     };
+    let bot: Arc<dyn YBot> = match &args.book {
+        Some(path) => Arc::new(BookBot::new(bot, Arc::new(OpeningBook::load_from_file(path)?))),
+        None => bot,
+    };
+    if args.mode == Mode::Computer {
+        println!("Bot seed: {} (use --seed {} to replay this game)", seed, seed);
+    }
+    let limits = args.search_limits();
     let mut game = game::GameY::new(args.size);
     loop {
         println!("{}", game.render(&render_options));
@@ -113,6 +216,7 @@ pub fn run_cli_game() -> Result<()> {
                             &mut render_options,
                             args.mode,
                             bot.as_ref(),
+                            &limits,
                         )?;
                     }
                 }
@@ -122,6 +226,95 @@ pub fn run_cli_game() -> Result<()> {
     Ok(())
 }
 
+/// Builds or extends the opening book given by `--book` with self-play games
+/// of `--bot` on a board of `--size`, using the search limits of the command line.
+pub fn run_book_builder(args: &CliArgs) -> Result<()> {
+    let Some(path) = &args.book else {
+        anyhow::bail!("--mode book needs the book file: --book <FILE>");
+    };
+    let Some(bot) = YBotRegistry::new().find(&args.bot) else {
+        anyhow::bail!("Bot '{}' not found", args.bot);
+    };
+
+    let mut book = if path.exists() {
+        OpeningBook::load_from_file(path)?
+    } else {
+        OpeningBook::new()
+    };
+    let seed = args.seed.unwrap_or_else(rand::random);
+    println!(
+        "Playing {} games of {} on a board of size {} (seed {})",
+        args.games, args.bot, args.size, seed
+    );
+    book.add_self_play(
+        bot.as_ref(),
+        args.size,
+        args.games,
+        args.plies,
+        &args.search_limits(),
+        seed,
+    );
+    book.save_to_file(path)?;
+    println!("Saved {} positions to {}", book.len(), path.display());
+    Ok(())
+}
+
+/// Solves every board size up to `--size` and saves the table to `--table`.
+///
+/// Boards up to [`MAX_TABLE_SIZE`] (size 5) take seconds; larger sizes are
+/// rejected before any work is done.
+pub fn run_table_solver(args: &CliArgs) -> Result<()> {
+    let Some(path) = &args.table else {
+        anyhow::bail!("--mode solve needs the table file: --table <FILE>");
+    };
+    if args.size > MAX_TABLE_SIZE {
+        anyhow::bail!(
+            "--mode solve supports boards up to size {}, got {}",
+            MAX_TABLE_SIZE,
+            args.size
+        );
+    }
+
+    let mut table = SolvedTable::new();
+    for size in 1..=args.size {
+        let start = std::time::Instant::now();
+        table.solve_size(size)?;
+        println!("Solved size {} in {} ms", size, start.elapsed().as_millis());
+    }
+    table.save_to_file(path)?;
+    println!("Saved {} positions to {}", table.len(), path.display());
+    Ok(())
+}
+
+/// Plays the matches of the `arena` subcommand with the bots of the default
+/// registry and prints the report, using the search limits of the command line.
+pub fn run_arena_tool(args: &CliArgs, arena: &ArenaArgs) -> Result<()> {
+    let seed = args.seed.unwrap_or_else(rand::random);
+    let mut config = ArenaConfig::new(arena.bots.clone())
+        .with_board_sizes(arena.sizes.clone())
+        .with_games(arena.games)
+        .with_limits(args.search_limits())
+        .with_seed(seed);
+    if let Some(threads) = arena.threads {
+        config = config.with_threads(threads);
+    }
+
+    println!(
+        "Playing {} games per match of {} on sizes {:?} (seed {})",
+        arena.games,
+        arena.bots.join(", "),
+        arena.sizes,
+        seed
+    );
+    let report = crate::run_arena(&YBotRegistry::new(), &config)?;
+    print!("{}", report);
+    if let Some(path) = &arena.records {
+        report.write_records(path)?;
+        println!("Saved {} games to {}", report.records.len(), path.display());
+    }
+    Ok(())
+}
+
 /// Processes a single line of user input and updates game state.
 fn process_input(
     input: &str,
@@ -130,11 +323,12 @@ fn process_input(
     render_options: &mut RenderOptions,
     mode: Mode,
     bot: &dyn YBot,
+    limits: &SearchLimits,
 ) -> Result<()> {
     let command = parse_command(input, game.total_cells());
     match command {
         Command::Place { idx } => {
-            handle_place_command(game, idx, *player, mode, bot);
+            handle_place_command(game, idx, *player, mode, bot, limits);
         }
         Command::Resign => {
             let movement = Movement::Action {
@@ -143,6 +337,15 @@ fn process_input(
             };
             apply_move(game, movement, "Error adding resign move");
         }
+        Command::Swap => {
+            handle_swap_command(game, *player, mode, bot, limits);
+        }
+        Command::Undo => {
+            handle_undo_command(game, *player, mode);
+        }
+        Command::Redo => {
+            handle_redo_command(game, *player, mode);
+        }
         Command::Show3DCoords => {
             render_options.show_3d_coords = !render_options.show_3d_coords;
         }
@@ -214,6 +417,9 @@ pub fn parse_command(input: &str, bound: u32) -> Command {
             }
         }
         "resign" => Command::Resign,
+        "swap" => Command::Swap,
+        "undo" => Command::Undo,
+        "redo" => Command::Redo,
         "help" => Command::Help,
         "exit" => Command::Exit,
         "show_colors" => Command::ShowColors,
@@ -233,6 +439,9 @@ fn print_help() {
     println!("Available commands:");
     println!("  <number>        - Place a piece at the specified index number");
     println!("  resign          - Resign from the game");
+    println!("  swap            - Take the opponent's opening stone (pie rule)");
+    println!("  undo            - Take back the last move");
+    println!("  redo            - Replay the last move taken back");
     println!("  show_coords     - Toggle showing coordinates on the board");
     println!("  show_idx        - Toggle showing index numbers on the board");
     println!("  show_colors     - Toggle showing colors on the board");
@@ -249,6 +458,12 @@ pub enum Command {
     Place { idx: u32 },
     /// Resign from the game.
     Resign,
+    /// Take the opponent's opening stone (pie rule).
+    Swap,
+    /// Take back the last move.
+    Undo,
+    /// Replay the last move taken back.
+    Redo,
     /// No command was entered (empty input).
     None,
     /// An error occurred while parsing the command.
@@ -295,32 +510,115 @@ fn handle_place_command(
     player: PlayerId,
     mode: Mode,
     bot: &dyn YBot,
+    limits: &SearchLimits,
 ) {
-    let coords = Coordinates::from_index(idx, game.board_size());
+    let coords = match Coordinates::try_from_index(idx, game.board_size()) {
+        Ok(coords) => coords,
+        Err(e) => {
+            println!("Error adding move: {}", e);
+            return;
+        }
+    };
     let movement = Movement::Placement { player, coords };
 
     if apply_move(game, movement, "Error adding move") {
         // Only trigger bot if the human move was valid, mode is computer, and game isn't over
         if mode == Mode::Computer && !game.check_game_over() {
-            trigger_bot_move(game, bot);
+            trigger_bot_move(game, bot, limits);
         }
     }
 }
 
+/// Applies the swap rule; the bot answers afterwards when playing against the computer.
+fn handle_swap_command(
+    game: &mut GameY,
+    player: PlayerId,
+    mode: Mode,
+    bot: &dyn YBot,
+    limits: &SearchLimits,
+) {
+    let movement = Movement::Action {
+        player,
+        action: GameAction::Swap,
+    };
+    if apply_move(game, movement, "Error applying swap")
+        && mode == Mode::Computer
+        && !game.check_game_over()
+    {
+        trigger_bot_move(game, bot, limits);
+    }
+}
+
+/// Takes back the last move. Against the computer, the bot reply is taken back too
+/// so that it is the human's turn again.
+fn handle_undo_command(game: &mut GameY, player: PlayerId, mode: Mode) {
+    if game.undo().is_none() {
+        println!("No moves to undo.");
+        return;
+    }
+    if mode == Mode::Computer && game.next_player() != Some(player) && game.can_undo() {
+        game.undo();
+    }
+}
+
+/// Replays the last move taken back. Against the computer, the bot reply is replayed too.
+fn handle_redo_command(game: &mut GameY, player: PlayerId, mode: Mode) {
+    if game.redo().is_none() {
+        println!("No moves to redo.");
+        return;
+    }
+    if mode == Mode::Computer && game.next_player() != Some(player) && game.can_redo() {
+        game.redo();
+    }
+}
+
 /// AI logic extracted to its own function
-fn trigger_bot_move(game: &mut GameY, bot: &dyn YBot) {
-    if let Some(bot_coords) = bot.choose_move(game) {
-        // Assuming next_player() is safe to unwrap here because the game isn't over
-        if let Some(bot_player) = game.next_player() {
-            let bot_movement = Movement::Placement {
-                player: bot_player,
-                coords: bot_coords,
-            };
-            apply_move(game, bot_movement, "Error adding bot move");
-        }
-    } else {
+fn trigger_bot_move(game: &mut GameY, bot: &dyn YBot, limits: &SearchLimits) {
+    let Some(decision) = bot.decide(game, limits) else {
         println!("No available moves for the bot.");
+        return;
+    };
+    match &decision.movement {
+        Movement::Placement { .. } => {
+            println!("Bot plays {}", describe_search(&decision.analysis, game.board_size()));
+        }
+        Movement::Action { action, .. } => {
+            println!("Bot performs action {}", action);
+        }
+    }
+    apply_move(game, decision.movement, "Error adding bot move");
+}
+
+/// Formats a bot's move and analysis for the terminal, e.g. `4 (score 120, depth 3, 2048 nodes, 15 ms)`.
+fn describe_search(result: &SearchResult, board_size: u32) -> String {
+    let mut details = Vec::new();
+    if let Some(score) = result.score {
+        details.push(format!("score {}", score));
+    }
+    if let Some(p) = result.win_probability {
+        details.push(format!("win {:.0}%", p * 100.0));
     }
+    if let Some(depth) = result.depth {
+        details.push(format!("depth {}", depth));
+    }
+    if result.nodes > 0 {
+        details.push(format!("{} nodes", result.nodes));
+    }
+    details.push(format!("{} ms", result.elapsed_ms));
+    if result.principal_variation.len() > 1 {
+        let pv: Vec<String> = result
+            .principal_variation
+            .iter()
+            .map(|c| c.to_index(board_size).to_string())
+            .collect();
+        details.push(format!("pv {}", pv.join(" ")));
+    }
+
+    let idx = result
+        .best_move
+        .map(|c| c.to_index(board_size).to_string())
+        .unwrap_or_else(|| "-".to_string());
+    format!("{} ({})", idx, details.join(", "))
 }
 
 /// Generic helper to apply a move and handle the Result printing
@@ -393,6 +691,36 @@ mod tests {
         assert_eq!(cmd, Command::Resign);
     }
 
+    #[test]
+    fn test_parse_command_undo_redo() {
+        assert_eq!(parse_command("undo", 10), Command::Undo);
+        assert_eq!(parse_command("redo", 10), Command::Redo);
+    }
+
+    #[test]
+    fn test_parse_command_swap() {
+        assert_eq!(parse_command("swap", 10), Command::Swap);
+    }
+
+    #[test]
+    fn test_undo_command_in_computer_mode_takes_back_bot_reply() {
+        let mut game = GameY::new(3);
+        for (player, idx) in [(0, 0), (1, 1)] {
+            game.add_move(Movement::Placement {
+                player: PlayerId::new(player),
+                coords: Coordinates::from_index(idx, 3),
+            })
+            .unwrap();
+        }
+
+        handle_undo_command(&mut game, PlayerId::new(0), Mode::Computer);
+        assert_eq!(game.available_cells().len(), 6);
+
+        handle_redo_command(&mut game, PlayerId::new(0), Mode::Computer);
+        assert_eq!(game.available_cells().len(), 4);
+        assert_eq!(game.next_player(), Some(PlayerId::new(0)));
+    }
+
     #[test]
     fn test_parse_command_help() {
         let cmd = parse_command("help", 10);
@@ -508,5 +836,28 @@ mod tests {
         assert!(debug.contains("Place"));
         assert!(debug.contains("5"));
     }
-}
 
+    #[test]
+    fn test_describe_search_with_analysis() {
+        let result = SearchResult {
+            score: Some(120),
+            depth: Some(3),
+            nodes: 2048,
+            principal_variation: vec![Coordinates::new(1, 1, 1), Coordinates::new(2, 0, 1)],
+            ..SearchResult::from_move(
+                Some(Coordinates::new(1, 1, 1)),
+                std::time::Duration::from_millis(15),
+            )
+        };
+        assert_eq!(
+            describe_search(&result, 4),
+            "4 (score 120, depth 3, 2048 nodes, 15 ms, pv 4 1)"
+        );
+    }
+
+    #[test]
+    fn test_describe_search_move_only() {
+        let result = SearchResult::from_move(Some(Coordinates::new(2, 0, 0)), std::time::Duration::ZERO);
+        assert_eq!(describe_search(&result, 3), "0 (0 ms)");
+    }
+}
@@ -21,6 +21,31 @@ impl Display for GameAction {
     }
 }
 
+/// How the swap (pie) rule is applied when the second player invokes [`GameAction::Swap`].
+///
+/// A swap is only legal right after the opening stone has been placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SwapRule {
+    /// Swapping is not allowed.
+    Disabled,
+    /// The opening stone changes owner and stays where it is.
+    #[default]
+    Transfer,
+    /// The opening stone is replaced by a stone of the swapping player
+    /// on the mirrored cell (the `y` and `z` coordinates are exchanged).
+    Mirror,
+}
+
+impl Display for SwapRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SwapRule::Disabled => write!(f, "disabled"),
+            SwapRule::Transfer => write!(f, "transfer"),
+            SwapRule::Mirror => write!(f, "mirror"),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -42,6 +67,18 @@ mod tests {
         assert_ne!(GameAction::Swap, GameAction::Resign);
     }
 
+    #[test]
+    fn test_swap_rule_default_is_transfer() {
+        assert_eq!(SwapRule::default(), SwapRule::Transfer);
+    }
+
+    #[test]
+    fn test_swap_rule_display() {
+        assert_eq!(format!("{}", SwapRule::Disabled), "disabled");
+        assert_eq!(format!("{}", SwapRule::Transfer), "transfer");
+        assert_eq!(format!("{}", SwapRule::Mirror), "mirror");
+    }
+
     #[test]
     fn test_clone() {
         let action = GameAction::Swap;
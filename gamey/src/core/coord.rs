@@ -2,6 +2,8 @@ use std::fmt::Display;
 
 use serde::{Deserialize, Serialize};
 
+use crate::GameYError;
+
 /// Represents barycentric coordinates (x, y, z) on a triangular board.
 ///
 /// In a triangular board of size N, valid coordinates satisfy:
@@ -25,6 +27,42 @@ impl Coordinates {
         Self { x, y, z }
     }
 
+    /// Creates coordinates checked against a board of the given size.
+    ///
+    /// Returns [`GameYError::CoordOutOfRange`] if a component is not smaller than
+    /// `board_size`, or [`GameYError::InvalidCoordsSum`] if `x + y + z != board_size - 1`.
+    pub fn try_new(x: u32, y: u32, z: u32, board_size: u32) -> Result<Self, GameYError> {
+        let coords = Self { x, y, z };
+        coords.check_bounds(board_size)?;
+        Ok(coords)
+    }
+
+    /// Checks that these coordinates denote a cell of a board of the given size.
+    pub fn check_bounds(&self, board_size: u32) -> Result<(), GameYError> {
+        for (id_coord, coord) in [('x', self.x), ('y', self.y), ('z', self.z)] {
+            if coord >= board_size {
+                return Err(GameYError::CoordOutOfRange {
+                    id_coord,
+                    coord,
+                    board_size,
+                });
+            }
+        }
+        // Each component is below board_size, so the sum cannot overflow
+        if self.x + self.y + self.z != board_size - 1 {
+            return Err(GameYError::InvalidCoordsSum {
+                coordinates: *self,
+                expected: board_size - 1,
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns true if these coordinates denote a cell of a board of the given size.
+    pub fn is_valid_for(&self, board_size: u32) -> bool {
+        self.check_bounds(board_size).is_ok()
+    }
+
     /// Returns the x coordinate (distance from side A).
     pub fn x(&self) -> u32 {
         self.x
@@ -60,6 +98,55 @@ impl Coordinates {
         Coordinates::new(x, y, z)
     }
 
+    /// Converts a linear index to coordinates, checking it against the board size.
+    ///
+    /// Returns [`GameYError::CoordOutOfRange`] with `id_coord = 'i'` if the index
+    /// is not smaller than the number of cells of the board.
+    pub fn try_from_index(index: u32, board_size: u32) -> Result<Self, GameYError> {
+        let total_cells = (board_size * (board_size + 1)) / 2;
+        if index >= total_cells {
+            return Err(GameYError::CoordOutOfRange {
+                id_coord: 'i',
+                coord: index,
+                board_size,
+            });
+        }
+        Ok(Self::from_index(index, board_size))
+    }
+
+    /// Converts a row/column pair to coordinates.
+    ///
+    /// Rows are counted from the top of the triangle and row `r` has `r + 1` cells,
+    /// matching the rows of a YEN layout. Returns [`GameYError::CoordOutOfRange`]
+    /// with `id_coord = 'r'` or `'c'` if the row or column is outside the board.
+    pub fn try_from_row_col(row: u32, col: u32, board_size: u32) -> Result<Self, GameYError> {
+        if row >= board_size {
+            return Err(GameYError::CoordOutOfRange {
+                id_coord: 'r',
+                coord: row,
+                board_size,
+            });
+        }
+        if col > row {
+            return Err(GameYError::CoordOutOfRange {
+                id_coord: 'c',
+                coord: col,
+                board_size,
+            });
+        }
+        let x = board_size - 1 - row;
+        let y = col;
+        let z = row - col;
+        Ok(Self { x, y, z })
+    }
+
+    /// Returns the row (from the top) and column of these coordinates.
+    ///
+    /// This is the inverse of `try_from_row_col`.
+    pub fn to_row_col(&self, board_size: u32) -> (u32, u32) {
+        (board_size - 1 - self.x, self.y)
+    }
+
     /// Converts these coordinates to a linear index.
     ///
     /// This is the inverse of `from_index`.
@@ -84,6 +171,20 @@ impl Coordinates {
         })
     }
 
+    /// Creates coordinates from a slice of values, checked against the board size.
+    ///
+    /// Returns [`GameYError::BadCoordsNumber`] if the slice does not have exactly
+    /// 3 elements, and the errors of [`Coordinates::try_new`] otherwise.
+    pub fn try_from_vec(coords: &[u32], board_size: u32) -> Result<Self, GameYError> {
+        match coords {
+            [x, y, z] => Self::try_new(*x, *y, *z, board_size),
+            _ => Err(GameYError::BadCoordsNumber {
+                expected: 3,
+                found: coords.len(),
+            }),
+        }
+    }
+
     /// Returns true if this cell touches side A (x == 0).
     pub fn touches_side_a(&self) -> bool {
         self.x == 0
@@ -201,6 +302,104 @@ mod tests {
         assert!(!interior.touches_side_c());
     }
 
+    #[test]
+    fn test_try_new_valid() {
+        let coords = Coordinates::try_new(1, 2, 1, 5).unwrap();
+        assert_eq!(coords, Coordinates::new(1, 2, 1));
+        assert!(coords.is_valid_for(5));
+    }
+
+    #[test]
+    fn test_try_new_out_of_range() {
+        let result = Coordinates::try_new(9, 9, 9, 5);
+        assert!(matches!(
+            result,
+            Err(GameYError::CoordOutOfRange {
+                id_coord: 'x',
+                coord: 9,
+                board_size: 5
+            })
+        ));
+        assert!(matches!(
+            Coordinates::try_new(0, 0, 5, 5),
+            Err(GameYError::CoordOutOfRange { id_coord: 'z', .. })
+        ));
+    }
+
+    #[test]
+    fn test_try_new_wrong_sum() {
+        let result = Coordinates::try_new(1, 1, 1, 5);
+        assert!(matches!(
+            result,
+            Err(GameYError::InvalidCoordsSum { expected: 4, .. })
+        ));
+        assert!(!Coordinates::new(1, 1, 1).is_valid_for(5));
+    }
+
+    #[test]
+    fn test_try_from_index() {
+        assert_eq!(
+            Coordinates::try_from_index(14, 5).unwrap(),
+            Coordinates::new(0, 4, 0)
+        );
+        assert!(matches!(
+            Coordinates::try_from_index(15, 5),
+            Err(GameYError::CoordOutOfRange {
+                id_coord: 'i',
+                coord: 15,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_try_from_row_col() {
+        assert_eq!(
+            Coordinates::try_from_row_col(0, 0, 3).unwrap(),
+            Coordinates::new(2, 0, 0)
+        );
+        assert_eq!(
+            Coordinates::try_from_row_col(2, 1, 3).unwrap(),
+            Coordinates::new(0, 1, 1)
+        );
+        assert!(matches!(
+            Coordinates::try_from_row_col(3, 0, 3),
+            Err(GameYError::CoordOutOfRange { id_coord: 'r', .. })
+        ));
+        assert!(matches!(
+            Coordinates::try_from_row_col(1, 2, 3),
+            Err(GameYError::CoordOutOfRange { id_coord: 'c', .. })
+        ));
+    }
+
+    #[test]
+    fn test_to_row_col_roundtrip() {
+        for idx in 0..15 {
+            let coords = Coordinates::from_index(idx, 5);
+            let (row, col) = coords.to_row_col(5);
+            assert_eq!(Coordinates::try_from_row_col(row, col, 5).unwrap(), coords);
+        }
+    }
+
+    #[test]
+    fn test_try_from_vec() {
+        assert_eq!(
+            Coordinates::try_from_vec(&[0, 1, 1], 3).unwrap(),
+            Coordinates::new(0, 1, 1)
+        );
+        assert!(matches!(
+            Coordinates::try_from_vec(&[0, 1], 3),
+            Err(GameYError::BadCoordsNumber {
+                expected: 3,
+                found: 2
+            })
+        ));
+        assert!(matches!(
+            Coordinates::try_from_vec(&[0, 1, 7], 3),
+            Err(GameYError::CoordOutOfRange { .. })
+        ));
+    }
+
     // Property-based tests using proptest
 
     proptest! {
@@ -254,5 +453,14 @@ mod tests {
             prop_assert!(back_idx < total_cells,
                 "Index {} out of bounds (max {}) for board_size {}", back_idx, total_cells - 1, board_size);
         }
+
+        /// Property: Coordinates built from a valid index always pass the bounds check.
+        #[test]
+        fn prop_coords_from_index_are_valid(board_size in 1u32..=20, idx_factor in 0.0f64..1.0) {
+            let total_cells = (board_size * (board_size + 1)) / 2;
+            let idx = ((idx_factor * total_cells as f64) as u32).min(total_cells - 1);
+            let coords = Coordinates::try_from_index(idx, board_size).unwrap();
+            prop_assert!(coords.is_valid_for(board_size));
+        }
     }
 }
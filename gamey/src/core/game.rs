@@ -1,9 +1,14 @@
 use crate::core::SetIdx;
 use crate::core::player_set::PlayerSet;
-use crate::{Coordinates, GameAction, GameYError, Movement, PlayerId, RenderOptions, YEN};
-use std::collections::HashMap;
+use crate::core::zobrist;
+use crate::{
+    Coordinates, GameAction, GameYError, Movement, PlayerId, RenderOptions, SwapRule, Symmetry,
+    Topology, YEN,
+};
+use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
 use std::path::Path;
+use std::sync::Arc;
 
 /// A Result type alias for game operations that may fail with a `GameYError`.
 pub type Result<T> = std::result::Result<T, crate::GameYError>;
@@ -18,20 +23,72 @@ pub struct GameY {
     // Size of the board (length of one side of the triangular board).
     board_size: u32,
 
-    // Mapping from coordinates to identifiers of players who placed stones there.
-    board_map: HashMap<Coordinates, (SetIdx, PlayerId)>,
+    // Neighbour and side tables shared by every game of this size.
+    topology: Arc<Topology>,
+
+    // Owner of the stone on each cell, indexed by cell index.
+    cells: Vec<Option<PlayerId>>,
+
+    // Number of stones on the board.
+    stone_count: usize,
 
     status: GameStatus,
 
     // History of moves made in the game.
     history: Vec<Movement>,
 
-    // Union-Find data structure to track connected components for each player
+    // Union-Find data structure to track connected components for each player,
+    // indexed by cell index. Entries of empty cells are singletons touching no side.
     sets: Vec<PlayerSet>,
 
     available_cells: Vec<u32>,
+
+    // Position of each cell inside `available_cells`, or `NOT_AVAILABLE` once filled.
+    available_pos: Vec<u32>,
+
+    // How the swap (pie) rule is applied after the opening stone.
+    swap_rule: SwapRule,
+
+    // Whether `add_move` enforces turn order and rejects moves after the game is over.
+    validation: MoveValidation,
+
+    // One entry per move in `history`, used to take moves back exactly.
+    undo_log: Vec<UndoRecord>,
+
+    // Moves that were taken back and can be replayed with `redo`.
+    redo_log: Vec<Movement>,
+
+    // Previous value of every union-find entry modified by the moves in `undo_log`.
+    set_journal: Vec<(SetIdx, PlayerSet)>,
+
+    // Zobrist hash of the stones on the board, kept up to date on every change.
+    stones_hash: u64,
+}
+
+/// Information needed to revert a single move.
+#[derive(Debug, Clone)]
+struct UndoRecord {
+    // Status before the move was applied.
+    status: GameStatus,
+    // Position inside `available_cells` and index of the cell that was filled, if any.
+    filled_cell: Option<(usize, u32)>,
+    // Length of `set_journal` before the move; later entries belong to this move.
+    journal_start: usize,
+    // The opening stone moved or re-owned by a swap.
+    swapped_stone: Option<SwappedStone>,
 }
 
+/// The opening stone before and after a swap, as cell indices.
+#[derive(Debug, Clone)]
+struct SwappedStone {
+    from: u32,
+    to: u32,
+    owner: PlayerId,
+}
+
+/// Marks a cell that is not in `available_cells`.
+const NOT_AVAILABLE: u32 = u32::MAX;
+
 /// Represents the state of a single cell on the board.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Cell {
@@ -41,19 +98,81 @@ pub enum Cell {
     Occupied(PlayerId),
 }
 
+/// A group of connected stones belonging to one player.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoneGroup {
+    /// The player owning the stones.
+    pub player: PlayerId,
+    /// The cells of the group, sorted by cell index.
+    pub cells: Vec<Coordinates>,
+    /// Whether the group touches side A (x == 0).
+    pub touches_side_a: bool,
+    /// Whether the group touches side B (y == 0).
+    pub touches_side_b: bool,
+    /// Whether the group touches side C (z == 0).
+    pub touches_side_c: bool,
+}
+
+impl StoneGroup {
+    /// Returns true if the group connects the three sides of the board.
+    pub fn is_winning(&self) -> bool {
+        self.touches_side_a && self.touches_side_b && self.touches_side_c
+    }
+}
+
 impl GameY {
     /// Creates a new game with the specified board size and number of players.
     pub fn new(board_size: u32) -> Self {
         let total_cells = (board_size * (board_size + 1)) / 2;
         Self {
             board_size,
-            board_map: HashMap::new(),
+            topology: Topology::for_size(board_size),
+            cells: vec![None; total_cells as usize],
+            stone_count: 0,
             history: Vec::new(),
-            sets: Vec::new(),
+            sets: (0..total_cells as usize).map(PlayerSet::empty).collect(),
             status: GameStatus::Ongoing {
                 next_player: PlayerId::new(0),
             },
             available_cells: (0..total_cells).collect(),
+            available_pos: (0..total_cells).collect(),
+            swap_rule: SwapRule::default(),
+            validation: MoveValidation::default(),
+            undo_log: Vec::new(),
+            redo_log: Vec::new(),
+            set_journal: Vec::new(),
+            stones_hash: 0,
+        }
+    }
+
+    /// Sets the swap (pie) rule used by this game.
+    pub fn with_swap_rule(mut self, swap_rule: SwapRule) -> Self {
+        self.swap_rule = swap_rule;
+        self
+    }
+
+    /// Returns the swap (pie) rule used by this game.
+    pub fn swap_rule(&self) -> SwapRule {
+        self.swap_rule
+    }
+
+    /// Sets how strictly `add_move` validates moves.
+    pub fn with_validation(mut self, validation: MoveValidation) -> Self {
+        self.validation = validation;
+        self
+    }
+
+    /// Returns how strictly `add_move` validates moves.
+    pub fn validation(&self) -> MoveValidation {
+        self.validation
+    }
+
+    /// Returns true if the player to move may invoke the swap rule now,
+    /// that is, right after the opponent placed the opening stone.
+    pub fn can_swap(&self) -> bool {
+        match self.next_player() {
+            Some(player) => self.check_swap(player).is_ok(),
+            None => false,
         }
     }
 
@@ -71,6 +190,9 @@ impl GameY {
     }
 
     /// Returns the list of available cell indices where pieces can be placed.
+    ///
+    /// The list starts in index order, but filling a cell moves the last entry
+    /// into its slot, so the order is not preserved as the game goes on.
     pub fn available_cells(&self) -> &Vec<u32> {
         &self.available_cells
     }
@@ -134,31 +256,152 @@ impl GameY {
     }
 
     /// Adds a move to the game.
+    ///
+    /// In [`MoveValidation::Strict`] mode, moves played after the game is over fail with
+    /// [`GameYError::GameOver`] and moves by the wrong player fail with
+    /// [`GameYError::InvalidPlayerTurn`].
+    /// Adding a move discards any moves that were previously taken back with [`GameY::undo`].
     pub fn add_move(&mut self, movement: Movement) -> Result<()> {
-        match &movement {
-            Movement::Placement { player, coords } => {
-                self.handle_placement(*player, *coords)?;
+        self.check_move_allowed(&movement)?;
+        self.apply_move(movement)?;
+        self.redo_log.clear();
+        Ok(())
+    }
+
+    /// Plays a placement in place so that search code can explore a position
+    /// and retract the move with [`GameY::unmake_move`] instead of cloning the game.
+    ///
+    /// Validation is the same as in [`GameY::add_move`], but moves taken back with
+    /// [`GameY::undo`] are kept, so a make/unmake pair leaves the game exactly as it was.
+    pub fn make_move(&mut self, player: PlayerId, coords: Coordinates) -> Result<()> {
+        let movement = Movement::Placement { player, coords };
+        self.check_move_allowed(&movement)?;
+        self.apply_move(movement)
+    }
+
+    /// Retracts the last move played, restoring the union-find, the available
+    /// cells and the status. Returns the retracted move, or None if there is none.
+    ///
+    /// Unlike [`GameY::undo`], the move is not offered to [`GameY::redo`].
+    pub fn unmake_move(&mut self) -> Option<Movement> {
+        self.revert_last_move()
+    }
+
+    /// Checks turn order and game over when validation is strict.
+    fn check_move_allowed(&self, movement: &Movement) -> Result<()> {
+        if self.validation == MoveValidation::Strict {
+            if self.check_game_over() {
+                return Err(GameYError::GameOver {
+                    movement: movement.clone(),
+                });
+            }
+            self.check_player_turn(movement)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the moves played so far, in order.
+    pub fn history(&self) -> &[Movement] {
+        &self.history
+    }
+
+    /// Returns true if there is a move that can be taken back.
+    pub fn can_undo(&self) -> bool {
+        !self.undo_log.is_empty()
+    }
+
+    /// Returns true if there is a previously undone move that can be replayed.
+    pub fn can_redo(&self) -> bool {
+        !self.redo_log.is_empty()
+    }
+
+    /// Takes back the last move, restoring the exact previous state.
+    ///
+    /// Returns the movement that was taken back, or None if there is nothing to undo.
+    /// Positions loaded from YEN have no history, so their stones cannot be taken back.
+    pub fn undo(&mut self) -> Option<Movement> {
+        let movement = self.revert_last_move()?;
+        self.redo_log.push(movement.clone());
+        Some(movement)
+    }
+
+    /// Reverts the last entry of `undo_log` and returns its movement.
+    fn revert_last_move(&mut self) -> Option<Movement> {
+        let record = self.undo_log.pop()?;
+        let movement = self.history.pop()?;
+
+        while self.set_journal.len() > record.journal_start {
+            if let Some((idx, previous)) = self.set_journal.pop() {
+                self.sets[idx] = previous;
             }
-            Movement::Action { player, action } => {
-                self.handle_action(*player, action);
+        }
+        if let Some(swapped) = record.swapped_stone {
+            self.remove_stone(swapped.to);
+            self.put_stone(swapped.from, swapped.owner);
+            if swapped.to != swapped.from {
+                self.replace_available(swapped.from, swapped.to);
             }
         }
+        if let Movement::Placement { coords, .. } = &movement {
+            self.remove_stone(coords.to_index(self.board_size));
+        }
+        if let Some((pos, cell)) = record.filled_cell {
+            self.restore_available(pos, cell);
+        }
+        self.status = record.status;
+        Some(movement)
+    }
+
+    /// Replays the last move taken back with [`GameY::undo`].
+    ///
+    /// Returns the movement that was replayed, or None if there is nothing to redo.
+    pub fn redo(&mut self) -> Option<Movement> {
+        let movement = self.redo_log.pop()?;
+        // The move was legal when it was first played from this same state
+        self.apply_move(movement.clone()).ok()?;
+        Some(movement)
+    }
+
+    /// Applies a move and records how to take it back.
+    fn apply_move(&mut self, movement: Movement) -> Result<()> {
+        let status = self.status.clone();
+        let journal_start = self.set_journal.len();
+        let (filled_cell, swapped_stone) = match &movement {
+            Movement::Placement { player, coords } => {
+                (self.handle_placement(*player, *coords)?, None)
+            }
+            Movement::Action { player, action } => (None, self.handle_action(*player, action)?),
+        };
+        self.undo_log.push(UndoRecord {
+            status,
+            filled_cell,
+            journal_start,
+            swapped_stone,
+        });
         self.history.push(movement);
         Ok(())
     }
 
     /// Orchestrates the placement logic
-    fn handle_placement(&mut self, player: PlayerId, coords: Coordinates) -> Result<()> {
+    /// Returns the position in `available_cells` and the index of the filled cell.
+    fn handle_placement(
+        &mut self,
+        player: PlayerId,
+        coords: Coordinates,
+    ) -> Result<Option<(usize, u32)>> {
         self.validate_placement(player, coords)?;
 
-        // Update board state (available cells, sets, board_map)
+        let cell_idx = coords.to_index(self.board_size);
+        let filled_cell = Some((self.available_pos[cell_idx as usize] as usize, cell_idx));
+
+        // Update board state (available cells, sets, cells)
         let set_idx = self.register_piece(player, coords);
 
         // Connect neighbors and determine if this move won the game
         let won = self.connect_neighbors_and_check_win(coords, player, set_idx);
 
         self.update_status_after_placement(player, won);
-        Ok(())
+        Ok(filled_cell)
     }
 
     /// Iterates over neighbors to union sets and checks for a win condition
@@ -171,16 +414,13 @@ impl GameY {
         // Base win condition: The piece itself touches all required sides
         let mut won = self.sets[current_set_idx].is_winning_configuration();
 
-        //
-        let neighbors = self.get_neighbors(&coords);
-
-        for neighbor in neighbors {
-            if let Some((neighbor_idx, neighbor_player)) = self.board_map.get(&neighbor)
-                && *neighbor_player == player
-            {
+        let topology = Arc::clone(&self.topology);
+        for &neighbor in topology.neighbors(coords.to_index(self.board_size)) {
+            let neighbor_idx = neighbor as SetIdx;
+            if self.cells[neighbor_idx] == Some(player) {
                 // Union returns true if the merge resulted in a winning connection
                 //
-                let connection_won = self.union(current_set_idx, *neighbor_idx);
+                let connection_won = self.union(current_set_idx, neighbor_idx);
                 won = won || connection_won;
             }
         }
@@ -203,28 +443,103 @@ impl GameY {
     }
 
     /// Handles non-placement actions (Resign, Swap, etc.)
-    fn handle_action(&mut self, player: PlayerId, action: &GameAction) {
+    /// Returns the opening stone affected by a swap, if any.
+    fn handle_action(
+        &mut self,
+        player: PlayerId,
+        action: &GameAction,
+    ) -> Result<Option<SwappedStone>> {
         match action {
             GameAction::Resign => {
                 self.status = GameStatus::Finished {
                     winner: other_player(player),
                 };
+                Ok(None)
             }
-            GameAction::Swap => {
-                self.status = GameStatus::Ongoing {
-                    next_player: other_player(player),
-                };
-            }
+            GameAction::Swap => self.handle_swap(player).map(Some),
+        }
+    }
+
+    /// Checks that `player` may swap and returns the opening stone and its owner.
+    fn check_swap(&self, player: PlayerId) -> Result<(u32, PlayerId)> {
+        let invalid = |reason: &str| GameYError::InvalidSwap {
+            player,
+            reason: reason.to_string(),
+        };
+        if self.swap_rule == SwapRule::Disabled {
+            return Err(invalid("the swap rule is disabled"));
+        }
+        if self.check_game_over() {
+            return Err(invalid("the game is over"));
+        }
+        if self.stone_count != 1 {
+            return Err(invalid("swap is only allowed right after the opening stone"));
+        }
+        let already_swapped = self.history.iter().any(|m| {
+            matches!(
+                m,
+                Movement::Action {
+                    action: GameAction::Swap,
+                    ..
+                }
+            )
+        });
+        if already_swapped {
+            return Err(invalid("the swap rule has already been used"));
+        }
+        let (cell_idx, owner) = self
+            .cells
+            .iter()
+            .enumerate()
+            .find_map(|(idx, cell)| cell.map(|owner| (idx as u32, owner)))
+            .ok_or_else(|| invalid("there is no opening stone"))?;
+        if owner == player {
+            return Err(invalid("the opening stone already belongs to this player"));
+        }
+        Ok((cell_idx, owner))
+    }
+
+    /// Applies the swap rule: the opening stone becomes `player`'s stone,
+    /// mirrored when the rule asks for it, and the opponent moves next.
+    fn handle_swap(&mut self, player: PlayerId) -> Result<SwappedStone> {
+        let (from, owner) = self.check_swap(player)?;
+        let from_coords = Coordinates::from_index(from, self.board_size);
+        let to = match self.swap_rule {
+            SwapRule::Mirror => Coordinates::new(from_coords.x(), from_coords.z(), from_coords.y())
+                .to_index(self.board_size),
+            _ => from,
+        };
+
+        if to != from {
+            self.replace_available(to, from);
+        }
+
+        for idx in [from, to] {
+            let idx = idx as SetIdx;
+            self.set_journal.push((idx, self.sets[idx]));
+            self.sets[idx] = PlayerSet::empty(idx);
         }
+        self.sets[to as SetIdx] =
+            PlayerSet::for_stone(to as SetIdx, Coordinates::from_index(to, self.board_size));
+
+        self.remove_stone(from);
+        self.put_stone(to, player);
+        self.status = GameStatus::Ongoing {
+            next_player: other_player(player),
+        };
+
+        Ok(SwappedStone { from, to, owner })
     }
 
-    /// Handles validation logic (Game Over checks and Occupancy)
+    /// Handles validation logic (Board bounds, Game Over checks and Occupancy)
     fn validate_placement(&self, player: PlayerId, coords: Coordinates) -> Result<()> {
+        coords.check_bounds(self.board_size)?;
+
         if self.check_game_over() {
             tracing::info!("Game is already over. Move at {} could be ignored", coords);
         }
 
-        if self.board_map.contains_key(&coords) {
+        if self.cells[coords.to_index(self.board_size) as usize].is_some() {
             return Err(GameYError::Occupied {
                 coordinates: coords,
                 player,
@@ -233,50 +548,172 @@ impl GameY {
         Ok(())
     }
 
-    /// Updates internal data structures (Available cells, Sets, Map)
-    /// Returns the index of the newly created set.
+    /// Updates internal data structures (Available cells, Sets, Cells)
+    /// Returns the index of the set of the new stone.
     fn register_piece(&mut self, player: PlayerId, coords: Coordinates) -> usize {
         let cell_idx = coords.to_index(self.board_size);
-        self.available_cells.retain(|&x| x != cell_idx);
-
-        let set_idx = self.sets.len();
-        let new_set = PlayerSet {
-            parent: set_idx,
-            touches_side_a: coords.touches_side_a(),
-            touches_side_b: coords.touches_side_b(),
-            touches_side_c: coords.touches_side_c(),
-        };
-        self.sets.push(new_set);
-        self.board_map.insert(coords, (set_idx, player));
+        self.remove_available(cell_idx);
+
+        let set_idx = cell_idx as SetIdx;
+        self.set_journal.push((set_idx, self.sets[set_idx]));
+        self.sets[set_idx] = PlayerSet::for_stone(set_idx, coords);
+        self.put_stone(cell_idx, player);
 
         set_idx
     }
 
-    /// Returns the size of the board (length of one side of the triangle).
-    pub fn board_size(&self) -> u32 {
-        self.board_size
+    /// Places a stone on an empty cell, keeping the stone count and hash up to date.
+    fn put_stone(&mut self, cell_idx: u32, player: PlayerId) {
+        self.cells[cell_idx as usize] = Some(player);
+        self.stone_count += 1;
+        self.stones_hash ^= zobrist::stone_key(cell_idx, player);
     }
 
-    /// Returns the neighboring coordinates for a given cell.
-    fn get_neighbors(&self, coords: &Coordinates) -> Vec<Coordinates> {
-        let mut neighbors = Vec::new();
-        let x = coords.x();
-        let y = coords.y();
-        let z = coords.z();
+    /// Removes the stone on a cell, if any, keeping the stone count and hash up to date.
+    fn remove_stone(&mut self, cell_idx: u32) {
+        if let Some(player) = self.cells[cell_idx as usize].take() {
+            self.stone_count -= 1;
+            self.stones_hash ^= zobrist::stone_key(cell_idx, player);
+        }
+    }
+
+    /// Removes a cell from `available_cells` in O(1), moving the last cell into its slot.
+    fn remove_available(&mut self, cell_idx: u32) {
+        let pos = self.available_pos[cell_idx as usize];
+        if pos == NOT_AVAILABLE {
+            return;
+        }
+        self.available_pos[cell_idx as usize] = NOT_AVAILABLE;
+        if let Some(last) = self.available_cells.pop()
+            && last != cell_idx
+        {
+            self.available_cells[pos as usize] = last;
+            self.available_pos[last as usize] = pos;
+        }
+    }
+
+    /// Reverts `remove_available`, putting `cell_idx` back at position `pos`.
+    fn restore_available(&mut self, pos: usize, cell_idx: u32) {
+        if pos < self.available_cells.len() {
+            let moved = self.available_cells[pos];
+            self.available_pos[moved as usize] = self.available_cells.len() as u32;
+            self.available_cells.push(moved);
+            self.available_cells[pos] = cell_idx;
+        } else {
+            self.available_cells.push(cell_idx);
+        }
+        self.available_pos[cell_idx as usize] = pos as u32;
+    }
 
-        if x > 0 {
-            neighbors.push(Coordinates::new(x - 1, y + 1, z));
-            neighbors.push(Coordinates::new(x - 1, y, z + 1));
+    /// Puts the empty cell `added` in the slot of the available cell `removed`.
+    fn replace_available(&mut self, removed: u32, added: u32) {
+        let pos = self.available_pos[removed as usize];
+        self.available_cells[pos as usize] = added;
+        self.available_pos[added as usize] = pos;
+        self.available_pos[removed as usize] = NOT_AVAILABLE;
+    }
+
+    /// Returns the state of the cell at `coords`.
+    pub fn cell(&self, coords: &Coordinates) -> Cell {
+        match self.cells.get(coords.to_index(self.board_size) as usize) {
+            Some(Some(player)) => Cell::Occupied(*player),
+            _ => Cell::Empty,
         }
-        if y > 0 {
-            neighbors.push(Coordinates::new(x + 1, y - 1, z));
-            neighbors.push(Coordinates::new(x, y - 1, z + 1));
+    }
+
+    /// Returns the owner of the stone on the cell with index `cell_idx`, if any.
+    pub fn stone_at(&self, cell_idx: u32) -> Option<PlayerId> {
+        self.cells.get(cell_idx as usize).copied().flatten()
+    }
+
+    /// Returns the 64-bit Zobrist hash of the position: the colour of every
+    /// stone plus the player to move.
+    ///
+    /// The hash is updated incrementally with every move (and undo), so it is
+    /// cheap to call. Equal positions on boards of the same size have equal hashes,
+    /// regardless of the order in which the stones were played.
+    pub fn zobrist_hash(&self) -> u64 {
+        match self.status {
+            GameStatus::Ongoing { next_player } => {
+                self.stones_hash ^ zobrist::side_key(next_player)
+            }
+            GameStatus::Finished { .. } => self.stones_hash,
         }
-        if z > 0 {
-            neighbors.push(Coordinates::new(x + 1, y, z - 1));
-            neighbors.push(Coordinates::new(x, y + 1, z - 1));
+    }
+
+    /// Returns the image of the position under `symmetry`.
+    ///
+    /// Stones, status, swap rule and move validation are carried over. As with
+    /// a position loaded from YEN, the result has no history to take back.
+    pub fn transformed(&self, symmetry: Symmetry) -> GameY {
+        let mut game = GameY::new(self.board_size)
+            .with_swap_rule(self.swap_rule)
+            .with_validation(self.validation);
+        for (cell_idx, cell) in self.cells.iter().enumerate() {
+            if let Some(player) = *cell {
+                let coords = symmetry.apply(Coordinates::from_index(cell_idx as u32, self.board_size));
+                let set_idx = game.register_piece(player, coords);
+                game.connect_neighbors_and_check_win(coords, player, set_idx);
+            }
         }
-        neighbors
+        game.set_journal.clear();
+        game.status = self.status.clone();
+        game
+    }
+
+    /// Returns the symmetry that takes the position to its canonical form:
+    /// the image with the smallest Zobrist hash.
+    pub fn canonical_symmetry(&self) -> Symmetry {
+        self.canonical().1
+    }
+
+    /// Returns the Zobrist hash of the canonical form of the position.
+    ///
+    /// Symmetric positions on boards of the same size share the same canonical
+    /// hash, so it can key tables that should treat them as one.
+    pub fn canonical_hash(&self) -> u64 {
+        self.canonical().0
+    }
+
+    /// Returns the canonical form of the position: the one image among its
+    /// six symmetric images that every one of them maps to.
+    pub fn canonical_form(&self) -> GameY {
+        self.transformed(self.canonical_symmetry())
+    }
+
+    /// Returns the canonical hash together with the canonical symmetry, in one pass.
+    ///
+    /// Moves stored against the canonical form are oriented to this position
+    /// with the inverse of the symmetry.
+    pub fn canonical(&self) -> (u64, Symmetry) {
+        let side = self.zobrist_hash() ^ self.stones_hash;
+        Symmetry::ALL
+            .into_iter()
+            .map(|symmetry| {
+                let hash = self.cells.iter().enumerate().fold(side, |hash, (cell_idx, cell)| {
+                    match cell {
+                        Some(player) => {
+                            let coords = Coordinates::from_index(cell_idx as u32, self.board_size);
+                            let image = symmetry.apply(coords).to_index(self.board_size);
+                            hash ^ zobrist::stone_key(image, *player)
+                        }
+                        None => hash,
+                    }
+                });
+                (hash, symmetry)
+            })
+            .min_by_key(|&(hash, _)| hash)
+            .expect("there is always a symmetry")
+    }
+
+    /// Returns the size of the board (length of one side of the triangle).
+    pub fn board_size(&self) -> u32 {
+        self.board_size
+    }
+
+    /// Returns the neighbour and side tables for this board size.
+    pub fn topology(&self) -> &Topology {
+        &self.topology
     }
 
     /// Renders the current state of the board as a text string.
@@ -314,16 +751,7 @@ impl GameY {
             GameStatus::Finished { winner } => winner,
         };
 
-        self.board_map
-            .iter()
-            .filter_map(|(coords, &(_, player))| {
-                if player == current_player {
-                    Some(*coords)
-                } else {
-                    None
-                }
-            })
-            .collect()
+        self.stones_of(current_player)
     }
 
     /// Returns the positions (as Coordinates) of the opponent's pieces
@@ -339,19 +767,104 @@ impl GameY {
             PlayerId::new(0)
         };
 
-        self.board_map
+        self.stones_of(opponent_id)
+    }
+
+    /// Returns the coordinates of every stone of `player`, ordered by cell index.
+    pub fn stones_of(&self, player: PlayerId) -> Vec<Coordinates> {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, cell)| **cell == Some(player))
+            .map(|(idx, _)| Coordinates::from_index(idx as u32, self.board_size))
+            .collect()
+    }
+
+
+
+    /// Returns every group of connected stones on the board, ordered by their first cell.
+    pub fn groups(&self) -> Vec<StoneGroup> {
+        let mut groups: Vec<StoneGroup> = Vec::new();
+        let mut group_of_root: HashMap<SetIdx, usize> = HashMap::new();
+        for (idx, cell) in self.cells.iter().enumerate() {
+            let Some(player) = *cell else {
+                continue;
+            };
+            let root = self.find_root(idx);
+            let pos = *group_of_root.entry(root).or_insert_with(|| {
+                groups.push(self.empty_group(root, player));
+                groups.len() - 1
+            });
+            groups[pos]
+                .cells
+                .push(Coordinates::from_index(idx as u32, self.board_size));
+        }
+        groups
+    }
+
+    /// Returns the group containing the stone at `coords`, or None if the cell is empty.
+    pub fn group_at(&self, coords: &Coordinates) -> Option<StoneGroup> {
+        let idx = coords.to_index(self.board_size);
+        let player = self.stone_at(idx)?;
+        let root = self.find_root(idx as SetIdx);
+        let mut group = self.empty_group(root, player);
+        group.cells = (0..self.cells.len())
+            .filter(|&i| self.cells[i].is_some() && self.find_root(i) == root)
+            .map(|i| Coordinates::from_index(i as u32, self.board_size))
+            .collect();
+        Some(group)
+    }
+
+    /// Returns the group of the winner connecting the three sides, if the game was won
+    /// on the board (a game finished by resignation has no winning group).
+    pub fn winning_group(&self) -> Option<StoneGroup> {
+        let GameStatus::Finished { winner } = self.status else {
+            return None;
+        };
+        self.groups()
+            .into_iter()
+            .find(|g| g.player == winner && g.is_winning())
+    }
+
+    /// Returns the pairs of adjacent cells inside a group, each pair once
+    /// and with the lower cell index first.
+    pub fn group_edges(&self, group: &StoneGroup) -> Vec<(Coordinates, Coordinates)> {
+        let cells: HashSet<u32> = group
+            .cells
             .iter()
-            .filter_map(|(coords, &(_, player))| {
-                if player == opponent_id {
-                    Some(*coords)
-                } else {
-                    None
+            .map(|c| c.to_index(self.board_size))
+            .collect();
+        let mut edges = Vec::new();
+        for &idx in &cells {
+            for &neighbor in self.topology.neighbors(idx) {
+                if idx < neighbor && cells.contains(&neighbor) {
+                    edges.push((idx, neighbor));
                 }
+            }
+        }
+        edges.sort_unstable();
+        edges
+            .into_iter()
+            .map(|(a, b)| {
+                (
+                    Coordinates::from_index(a, self.board_size),
+                    Coordinates::from_index(b, self.board_size),
+                )
             })
             .collect()
     }
 
-
+    /// Creates a group without cells carrying the sides touched by the set `root`.
+    fn empty_group(&self, root: SetIdx, player: PlayerId) -> StoneGroup {
+        let set = &self.sets[root];
+        StoneGroup {
+            player,
+            cells: Vec::new(),
+            touches_side_a: set.touches_side_a,
+            touches_side_b: set.touches_side_b,
+            touches_side_c: set.touches_side_c,
+        }
+    }
 
     /// Calculates manhattan distance for the bots
     pub fn manhattan_distance(&self, a: Coordinates, b: Coordinates) -> u32 {
@@ -437,7 +950,7 @@ impl GameY {
     }
 
     fn format_cell(&self, coords: Coordinates, options: &RenderOptions, width: usize) -> String {
-        let player = self.board_map.get(&coords).map(|(_, p)| *p);
+        let player = self.stone_at(coords.to_index(self.board_size));
 
         // 1. Base symbol
         let mut symbol = match player {
@@ -468,13 +981,25 @@ impl GameY {
         symbol
     }
 
+    /// Disjoint Set Union 'Find' without path compression, for read-only queries
+    fn find_root(&self, mut i: SetIdx) -> SetIdx {
+        while self.sets[i].parent != i {
+            i = self.sets[i].parent;
+        }
+        i
+    }
+
     /// Disjoint Set Union 'Find' with path compression
     fn find(&mut self, i: SetIdx) -> SetIdx {
         if self.sets[i].parent == i {
             i
         } else {
-            self.sets[i].parent = self.find(self.sets[i].parent);
-            self.sets[i].parent
+            let root = self.find(self.sets[i].parent);
+            if self.sets[i].parent != root {
+                self.set_journal.push((i, self.sets[i]));
+                self.sets[i].parent = root;
+            }
+            root
         }
     }
 
@@ -484,6 +1009,8 @@ impl GameY {
         let root_j = self.find(j);
 
         if root_i != root_j {
+            self.set_journal.push((root_i, self.sets[root_i]));
+            self.set_journal.push((root_j, self.sets[root_j]));
             self.sets[root_i].parent = root_j;
             // Merge side properties
             self.sets[root_j].touches_side_a |= self.sets[root_i].touches_side_a;
@@ -527,10 +1054,7 @@ impl TryFrom<YEN> for GameY {
             }
 
             for (col, cell) in cells.iter().enumerate() {
-                let x = game.size() - 1 - (row as u32);
-                let y = col as u32;
-                let z = game.size() - 1 - x - y;
-                let coords = Coordinates::new(x, y, z);
+                let coords = Coordinates::try_from_row_col(row as u32, col as u32, game.size())?;
 
                 match cell {
                     'B' => {
@@ -565,6 +1089,9 @@ impl TryFrom<YEN> for GameY {
             }
         }
 
+        // Loaded stones have no history, so they can never be taken back
+        ygame.set_journal.clear();
+
         if !ygame.check_game_over() {
             let next_player = match game.turn() {
                 0 => PlayerId::new(0),
@@ -590,7 +1117,7 @@ impl From<&GameY> for YEN {
     fn from(game: &GameY) -> Self {
         let size = game.board_size;
         let turn = match game.status {
-            GameStatus::Finished { winner } => other_player(winner).id() as u32,
+            GameStatus::Finished { winner } => other_player(winner).id(),
             GameStatus::Ongoing { next_player } => next_player.id(),
         };
         let mut layout = String::new();
@@ -598,9 +1125,9 @@ impl From<&GameY> for YEN {
         let players = vec!['B', 'R'];
         for idx in 0..total_cells {
             let coords = Coordinates::from_index(idx, game.board_size);
-            let cell_char = match game.board_map.get(&coords) {
-                Some((_, player)) if player.id() == 0 => 'B',
-                Some((_, player)) if player.id() == 1 => 'R',
+            let cell_char = match game.stone_at(idx) {
+                Some(player) if player.id() == 0 => 'B',
+                Some(player) if player.id() == 1 => 'R',
                 _ => '.',
             };
             layout.push(cell_char);
@@ -613,12 +1140,7 @@ impl From<&GameY> for YEN {
 }
 
 fn other_player(player: PlayerId) -> PlayerId {
-    // Assuming two players with IDs 0 and 1
-    if player.id() == 0 {
-        PlayerId::new(1)
-    } else {
-        PlayerId::new(0)
-    }
+    player.opponent()
 }
 
 fn apply_player_color(symbol: String, player: Option<PlayerId>) -> String {
@@ -629,8 +1151,19 @@ fn apply_player_color(symbol: String, player: Option<PlayerId>) -> String {
     }
 }
 
+/// How strictly [`GameY::add_move`] validates incoming moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MoveValidation {
+    /// Moves must follow turn order and the game must not be over.
+    #[default]
+    Strict,
+    /// Analysis/editor mode: any player may place stones at any time,
+    /// only occupied cells are rejected.
+    Permissive,
+}
+
 /// Represents the current status of a game.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum GameStatus {
     /// The game is still in progress with the specified player to move next.
     Ongoing { next_player: PlayerId },
@@ -669,12 +1202,21 @@ mod tests {
         assert_eq!(actual_set, expected_set);
     }
 
+    fn get_neighbors(board: &GameY, coords: &Coordinates) -> Vec<Coordinates> {
+        board
+            .topology()
+            .neighbors(coords.to_index(board.board_size()))
+            .iter()
+            .map(|&idx| Coordinates::from_index(idx, board.board_size()))
+            .collect()
+    }
+
     #[test]
     fn test_interior_cell_has_six_neighbors() {
         let board = GameY::new(5);
         let cell = Coordinates::new(2, 1, 1);
 
-        let neighbors = board.get_neighbors(&cell);
+        let neighbors = get_neighbors(&board, &cell);
 
         let expected = vec![
             Coordinates::new(1, 2, 1),
@@ -694,7 +1236,7 @@ mod tests {
         let board = GameY::new(5);
         let top_corner = Coordinates::new(4, 0, 0);
 
-        let neighbors = board.get_neighbors(&top_corner);
+        let neighbors = get_neighbors(&board, &top_corner);
 
         let expected = vec![Coordinates::new(3, 1, 0), Coordinates::new(3, 0, 1)];
 
@@ -707,7 +1249,7 @@ mod tests {
         let board = GameY::new(5);
         let edge_cell = Coordinates::new(0, 2, 2);
 
-        let neighbors = board.get_neighbors(&edge_cell);
+        let neighbors = get_neighbors(&board, &edge_cell);
 
         let expected = vec![
             Coordinates::new(1, 1, 2),
@@ -924,6 +1466,254 @@ mod tests {
         assert!(matches!(result, Err(GameYError::Occupied { .. })));
     }
 
+    #[test]
+    fn test_groups_track_sides_and_cells() {
+        let mut game = GameY::new(3);
+        play(&mut game, 0, Coordinates::new(0, 0, 2));
+        play(&mut game, 1, Coordinates::new(2, 0, 0));
+        play(&mut game, 0, Coordinates::new(0, 1, 1));
+
+        let groups = game.groups();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].player, PlayerId::new(1));
+        assert_eq!(groups[0].cells, vec![Coordinates::new(2, 0, 0)]);
+        assert!(!groups[0].touches_side_a);
+        assert!(groups[0].touches_side_b && groups[0].touches_side_c);
+
+        let group = game.group_at(&Coordinates::new(0, 1, 1)).unwrap();
+        assert_eq!(group, groups[1]);
+        assert_eq!(group.cells.len(), 2);
+        assert!(group.touches_side_a && group.touches_side_b);
+        assert!(!group.touches_side_c);
+        assert!(!group.is_winning());
+        assert!(game.group_at(&Coordinates::new(1, 1, 0)).is_none());
+    }
+
+    #[test]
+    fn test_winning_group_and_edges() {
+        let mut game = GameY::new(3);
+        play(&mut game, 0, Coordinates::new(0, 0, 2));
+        play(&mut game, 1, Coordinates::new(2, 0, 0));
+        play(&mut game, 0, Coordinates::new(0, 1, 1));
+        play(&mut game, 1, Coordinates::new(1, 1, 0));
+        assert!(game.winning_group().is_none());
+        play(&mut game, 0, Coordinates::new(0, 2, 0));
+
+        let group = game.winning_group().unwrap();
+        assert_eq!(group.player, PlayerId::new(0));
+        assert!(group.is_winning());
+        assert_eq!(
+            game.group_edges(&group),
+            vec![
+                (Coordinates::new(0, 0, 2), Coordinates::new(0, 1, 1)),
+                (Coordinates::new(0, 1, 1), Coordinates::new(0, 2, 0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_winning_group_after_resign() {
+        let mut game = GameY::new(3);
+        play(&mut game, 0, Coordinates::new(0, 0, 2));
+        game.add_move(Movement::Action {
+            player: PlayerId::new(1),
+            action: GameAction::Resign,
+        })
+        .unwrap();
+
+        assert!(game.check_game_over());
+        assert!(game.winning_group().is_none());
+    }
+
+    #[test]
+    fn test_zobrist_hash_ignores_move_order() {
+        let mut game1 = GameY::new(4);
+        play(&mut game1, 0, Coordinates::new(3, 0, 0));
+        play(&mut game1, 1, Coordinates::new(0, 3, 0));
+        play(&mut game1, 0, Coordinates::new(1, 1, 1));
+
+        let mut game2 = GameY::new(4);
+        play(&mut game2, 0, Coordinates::new(1, 1, 1));
+        play(&mut game2, 1, Coordinates::new(0, 3, 0));
+        play(&mut game2, 0, Coordinates::new(3, 0, 0));
+
+        assert_eq!(game1.zobrist_hash(), game2.zobrist_hash());
+    }
+
+    #[test]
+    fn test_zobrist_hash_distinguishes_colour_and_side_to_move() {
+        let permissive = || GameY::new(4).with_validation(MoveValidation::Permissive);
+        let mut by_first = permissive();
+        play(&mut by_first, 0, Coordinates::new(3, 0, 0));
+        let mut by_second = permissive();
+        play(&mut by_second, 1, Coordinates::new(3, 0, 0));
+        assert_ne!(by_first.zobrist_hash(), by_second.zobrist_hash());
+
+        // Same stones, different player to move
+        let mut passed = permissive();
+        play(&mut passed, 0, Coordinates::new(3, 0, 0));
+        play(&mut passed, 1, Coordinates::new(0, 3, 0));
+        let mut not_passed = permissive();
+        play(&mut not_passed, 1, Coordinates::new(0, 3, 0));
+        play(&mut not_passed, 0, Coordinates::new(3, 0, 0));
+        assert_ne!(passed.zobrist_hash(), not_passed.zobrist_hash());
+    }
+
+    #[test]
+    fn test_zobrist_hash_restored_by_undo_and_swap() {
+        for rule in [SwapRule::Transfer, SwapRule::Mirror] {
+            let mut game = GameY::new(4).with_swap_rule(rule);
+            let empty = game.zobrist_hash();
+            play(&mut game, 0, Coordinates::new(2, 1, 0));
+            let opened = game.zobrist_hash();
+
+            game.add_move(Movement::Action {
+                player: PlayerId::new(1),
+                action: GameAction::Swap,
+            })
+            .unwrap();
+            assert_ne!(game.zobrist_hash(), opened);
+
+            game.undo();
+            assert_eq!(game.zobrist_hash(), opened);
+            game.undo();
+            assert_eq!(game.zobrist_hash(), empty);
+        }
+    }
+
+    #[test]
+    fn test_zobrist_hash_matches_yen_round_trip() {
+        let mut game = GameY::new(4);
+        play(&mut game, 0, Coordinates::new(3, 0, 0));
+        play(&mut game, 1, Coordinates::new(1, 2, 0));
+        play(&mut game, 0, Coordinates::new(0, 1, 2));
+
+        let yen: YEN = (&game).into();
+        let loaded = GameY::try_from(yen).unwrap();
+        assert_eq!(loaded.zobrist_hash(), game.zobrist_hash());
+    }
+
+    #[test]
+    fn test_transformed_moves_every_stone() {
+        let mut game = GameY::new(4).with_swap_rule(SwapRule::Mirror);
+        play(&mut game, 0, Coordinates::new(3, 0, 0));
+        play(&mut game, 1, Coordinates::new(1, 2, 0));
+
+        let image = game.transformed(Symmetry::Rotate120);
+        assert_eq!(image.cell(&Coordinates::new(0, 0, 3)), Cell::Occupied(PlayerId::new(0)));
+        assert_eq!(image.cell(&Coordinates::new(2, 0, 1)), Cell::Occupied(PlayerId::new(1)));
+        assert_eq!(image.available_cells().len(), 8);
+        assert_eq!(image.next_player(), Some(PlayerId::new(0)));
+        assert_eq!(image.swap_rule(), SwapRule::Mirror);
+        assert!(image.history().is_empty());
+
+        let back = image.transformed(Symmetry::Rotate240);
+        assert_eq!(back.cells, game.cells);
+        assert_eq!(back.zobrist_hash(), game.zobrist_hash());
+    }
+
+    #[test]
+    fn test_transformed_keeps_groups_and_winner() {
+        let mut game = GameY::new(3);
+        play(&mut game, 0, Coordinates::new(2, 0, 0));
+        play(&mut game, 1, Coordinates::new(0, 2, 0));
+        play(&mut game, 0, Coordinates::new(1, 0, 1));
+        play(&mut game, 1, Coordinates::new(0, 1, 1));
+        play(&mut game, 0, Coordinates::new(0, 0, 2));
+        assert!(game.check_game_over());
+
+        for symmetry in Symmetry::ALL {
+            let image = game.transformed(symmetry);
+            assert_eq!(image.status(), game.status());
+            assert!(image.winning_group().is_some());
+            assert_eq!(image.groups().len(), game.groups().len());
+        }
+    }
+
+    #[test]
+    fn test_canonical_hash_is_shared_by_symmetric_positions() {
+        let mut game = GameY::new(5);
+        play(&mut game, 0, Coordinates::new(4, 0, 0));
+        play(&mut game, 1, Coordinates::new(2, 1, 1));
+        play(&mut game, 0, Coordinates::new(1, 3, 0));
+
+        let canonical = game.canonical_form();
+        for symmetry in Symmetry::ALL {
+            let image = game.transformed(symmetry);
+            assert_eq!(image.canonical_hash(), game.canonical_hash());
+            assert_eq!(image.canonical_form().cells, canonical.cells);
+            // The canonical symmetry takes the image to the canonical form
+            let (hash, found) = image.canonical();
+            assert_eq!(image.transformed(found).zobrist_hash(), hash);
+        }
+        assert_eq!(canonical.zobrist_hash(), game.canonical_hash());
+        assert_eq!(canonical.canonical_symmetry(), Symmetry::Identity);
+    }
+
+    #[test]
+    fn test_canonical_hash_keeps_side_to_move() {
+        let corner = |coords: Coordinates, turn: u32| {
+            let mut game = GameY::new(4);
+            play(&mut game, 0, coords);
+            game.status = GameStatus::Ongoing {
+                next_player: PlayerId::new(turn),
+            };
+            game
+        };
+
+        // A stone in any corner is the same position
+        let a = corner(Coordinates::new(3, 0, 0), 1);
+        assert_eq!(a.canonical_hash(), corner(Coordinates::new(0, 0, 3), 1).canonical_hash());
+        // But not if the player to move changes
+        assert_ne!(a.canonical_hash(), corner(Coordinates::new(0, 3, 0), 0).canonical_hash());
+    }
+
+    #[test]
+    fn test_default_validation_is_strict() {
+        assert_eq!(GameY::new(3).validation(), MoveValidation::Strict);
+    }
+
+    #[test]
+    fn test_strict_mode_rejects_move_after_win() {
+        let mut game = GameY::new(1);
+        play(&mut game, 0, Coordinates::new(0, 0, 0));
+
+        let result = game.add_move(Movement::Placement {
+            player: PlayerId::new(1),
+            coords: Coordinates::new(0, 0, 0),
+        });
+
+        assert!(matches!(result, Err(GameYError::GameOver { .. })));
+        assert_eq!(game.history.len(), 1);
+    }
+
+    #[test]
+    fn test_add_move_rejects_coordinates_outside_board() {
+        let mut game = GameY::new(5);
+
+        let result = game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::new(9, 9, 9),
+        });
+
+        assert!(matches!(result, Err(GameYError::CoordOutOfRange { .. })));
+        assert_eq!(game.stone_count, 0);
+        assert_eq!(game.available_cells.len(), 15);
+    }
+
+    #[test]
+    fn test_add_move_rejects_coordinates_with_wrong_sum() {
+        let mut game = GameY::new(5);
+
+        let result = game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::new(1, 1, 1),
+        });
+
+        assert!(matches!(result, Err(GameYError::InvalidCoordsSum { .. })));
+        assert!(game.history.is_empty());
+    }
+
     #[test]
     fn test_next_player_changes_after_valid_move() {
         let mut game = GameY::new(3);
@@ -958,16 +1748,73 @@ mod tests {
     }
 
     #[test]
-    fn test_swap_action_changes_next_player() {
+    fn test_swap_action_transfers_opening_stone() {
         let mut game = GameY::new(3);
+        let coords = Coordinates::new(2, 0, 0);
+        play(&mut game, 0, coords);
+        assert!(game.can_swap());
 
         game.add_move(Movement::Action {
+            player: PlayerId::new(1),
+            action: GameAction::Swap,
+        })
+        .unwrap();
+
+        assert_eq!(game.next_player(), Some(PlayerId::new(0)));
+        assert_eq!(game.cell(&coords), Cell::Occupied(PlayerId::new(1)));
+        assert!(!game.can_swap());
+    }
+
+    #[test]
+    fn test_swap_by_owner_of_opening_stone_is_rejected() {
+        let mut game = GameY::new(3).with_validation(MoveValidation::Permissive);
+        play(&mut game, 0, Coordinates::new(2, 0, 0));
+
+        let result = game.add_move(Movement::Action {
             player: PlayerId::new(0),
             action: GameAction::Swap,
+        });
+
+        assert!(matches!(result, Err(GameYError::InvalidSwap { .. })));
+    }
+
+    #[test]
+    fn test_mirror_swap_updates_touched_sides() {
+        let mut game = GameY::new(3).with_swap_rule(SwapRule::Mirror);
+        // Touches sides A and B
+        play(&mut game, 0, Coordinates::new(0, 0, 2));
+
+        game.add_move(Movement::Action {
+            player: PlayerId::new(1),
+            action: GameAction::Swap,
         })
         .unwrap();
 
-        assert_eq!(game.next_player(), Some(PlayerId::new(1)));
+        let mirrored = Coordinates::new(0, 2, 0);
+        assert_eq!(game.cell(&mirrored), Cell::Occupied(PlayerId::new(1)));
+        assert_eq!(game.cell(&Coordinates::new(0, 0, 2)), Cell::Empty);
+        let set = game.sets[mirrored.to_index(3) as usize];
+        assert!(set.touches_side_a);
+        assert!(!set.touches_side_b);
+        assert!(set.touches_side_c);
+    }
+
+    #[test]
+    fn test_undo_swap_restores_opening_stone() {
+        for rule in [SwapRule::Transfer, SwapRule::Mirror] {
+            let mut game = GameY::new(4).with_swap_rule(rule);
+            play(&mut game, 0, Coordinates::new(1, 0, 2));
+            let before = game.clone();
+
+            game.add_move(Movement::Action {
+                player: PlayerId::new(1),
+                action: GameAction::Swap,
+            })
+            .unwrap();
+            game.undo();
+
+            assert_same_state(&game, &before);
+        }
     }
 
     #[test]
@@ -1014,6 +1861,253 @@ mod tests {
         assert!(matches!(result, Err(GameYError::InvalidCharInLayout { .. })));
     }
 
+    fn play(game: &mut GameY, player: u32, coords: Coordinates) {
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(player),
+            coords,
+        })
+        .unwrap();
+    }
+
+    fn assert_same_state(a: &GameY, b: &GameY) {
+        assert_eq!(a.cells, b.cells);
+        assert_eq!(a.stone_count, b.stone_count);
+        assert_eq!(a.zobrist_hash(), b.zobrist_hash());
+        assert_eq!(a.status, b.status);
+        assert_eq!(a.history, b.history);
+        assert_eq!(a.sets, b.sets);
+        assert_eq!(a.available_cells, b.available_cells);
+        assert_eq!(a.available_pos, b.available_pos);
+    }
+
+    fn assert_available_cells_consistent(game: &GameY) {
+        for (pos, &cell) in game.available_cells.iter().enumerate() {
+            assert_eq!(game.available_pos[cell as usize], pos as u32);
+            assert!(game.cells[cell as usize].is_none());
+        }
+        let filled = game.cells.iter().filter(|c| c.is_some()).count();
+        assert_eq!(filled, game.stone_count);
+        assert_eq!(
+            game.available_cells.len() + game.stone_count,
+            game.total_cells() as usize
+        );
+    }
+
+    #[test]
+    fn test_cell_reports_owner() {
+        let mut game = GameY::new(3);
+        let coords = Coordinates::new(1, 1, 0);
+        assert_eq!(game.cell(&coords), Cell::Empty);
+
+        play(&mut game, 0, coords);
+        assert_eq!(game.cell(&coords), Cell::Occupied(PlayerId::new(0)));
+        assert_eq!(game.stone_at(coords.to_index(3)), Some(PlayerId::new(0)));
+        assert_eq!(game.stone_at(99), None);
+    }
+
+    #[test]
+    fn test_clone_is_independent() {
+        let mut game = GameY::new(4);
+        play(&mut game, 0, Coordinates::new(3, 0, 0));
+        let snapshot = game.clone();
+
+        play(&mut game, 1, Coordinates::new(0, 3, 0));
+        assert_eq!(snapshot.available_cells().len(), 9);
+        assert_eq!(snapshot.cell(&Coordinates::new(0, 3, 0)), Cell::Empty);
+        assert_available_cells_consistent(&snapshot);
+        assert_available_cells_consistent(&game);
+    }
+
+    proptest::proptest! {
+        /// Property: random games keep the board arrays consistent and undo restores them.
+        #[test]
+        fn prop_random_games_undo_to_empty_board(
+            board_size in 1u32..=8,
+            choices in proptest::collection::vec(0usize..100, 0..40),
+        ) {
+            let mut game = GameY::new(board_size);
+            let fresh = game.clone();
+            for choice in choices {
+                let Some(player) = game.next_player() else { break };
+                let cells = game.available_cells();
+                let cell = cells[choice % cells.len()];
+                play(&mut game, player.id(), Coordinates::from_index(cell, board_size));
+                assert_available_cells_consistent(&game);
+            }
+            while game.undo().is_some() {
+                assert_available_cells_consistent(&game);
+            }
+            assert_same_state(&game, &fresh);
+        }
+    }
+
+    #[test]
+    fn test_undo_on_new_game_returns_none() {
+        let mut game = GameY::new(3);
+        assert!(!game.can_undo());
+        assert!(game.undo().is_none());
+        assert!(game.redo().is_none());
+    }
+
+    #[test]
+    fn test_undo_restores_exact_state_after_unions() {
+        let mut game = GameY::new(4);
+        play(&mut game, 0, Coordinates::new(0, 0, 3));
+        play(&mut game, 1, Coordinates::new(3, 0, 0));
+        play(&mut game, 0, Coordinates::new(0, 2, 1));
+        play(&mut game, 1, Coordinates::new(2, 1, 0));
+        let before = game.clone();
+
+        // Joins both groups of player 0, compressing paths in the union-find
+        play(&mut game, 0, Coordinates::new(0, 1, 2));
+        assert_eq!(
+            game.undo(),
+            Some(Movement::Placement {
+                player: PlayerId::new(0),
+                coords: Coordinates::new(0, 1, 2),
+            })
+        );
+
+        assert_same_state(&game, &before);
+    }
+
+    #[test]
+    fn test_undo_winning_move_restores_ongoing_status() {
+        let mut game = GameY::new(2);
+        play(&mut game, 0, Coordinates::new(0, 0, 1));
+        play(&mut game, 1, Coordinates::new(1, 0, 0));
+        play(&mut game, 0, Coordinates::new(0, 1, 0));
+        assert!(game.check_game_over());
+
+        game.undo();
+
+        assert_eq!(game.next_player(), Some(PlayerId::new(0)));
+        assert_eq!(game.available_cells(), &vec![2]);
+    }
+
+    #[test]
+    fn test_undo_action_restores_status() {
+        let mut game = GameY::new(3);
+        game.add_move(Movement::Action {
+            player: PlayerId::new(0),
+            action: GameAction::Resign,
+        })
+        .unwrap();
+
+        game.undo();
+
+        assert_eq!(game.next_player(), Some(PlayerId::new(0)));
+        assert!(game.history.is_empty());
+    }
+
+    #[test]
+    fn test_redo_replays_undone_moves() {
+        let mut game = GameY::new(3);
+        play(&mut game, 0, Coordinates::new(0, 1, 1));
+        play(&mut game, 1, Coordinates::new(2, 0, 0));
+        play(&mut game, 0, Coordinates::new(0, 2, 0));
+        let after = game.clone();
+
+        game.undo();
+        game.undo();
+        assert!(game.can_redo());
+        game.redo();
+        game.redo();
+
+        assert!(!game.can_redo());
+        assert_same_state(&game, &after);
+    }
+
+    #[test]
+    fn test_new_move_clears_redo() {
+        let mut game = GameY::new(3);
+        play(&mut game, 0, Coordinates::new(0, 1, 1));
+        game.undo();
+        play(&mut game, 0, Coordinates::new(2, 0, 0));
+
+        assert!(!game.can_redo());
+        assert!(game.redo().is_none());
+    }
+
+    #[test]
+    fn test_make_unmake_restores_exact_state() {
+        let mut game = GameY::new(4);
+        play(&mut game, 0, Coordinates::new(0, 0, 3));
+        play(&mut game, 1, Coordinates::new(3, 0, 0));
+        play(&mut game, 0, Coordinates::new(0, 2, 1));
+        let before = game.clone();
+
+        game.make_move(PlayerId::new(1), Coordinates::new(2, 1, 0))
+            .unwrap();
+        game.make_move(PlayerId::new(0), Coordinates::new(0, 1, 2))
+            .unwrap();
+        assert_eq!(game.history.len(), 5);
+
+        assert_eq!(
+            game.unmake_move(),
+            Some(Movement::Placement {
+                player: PlayerId::new(0),
+                coords: Coordinates::new(0, 1, 2),
+            })
+        );
+        game.unmake_move();
+
+        assert_same_state(&game, &before);
+    }
+
+    #[test]
+    fn test_unmake_winning_move_restores_ongoing_status() {
+        let mut game = GameY::new(2);
+        play(&mut game, 0, Coordinates::new(0, 0, 1));
+        play(&mut game, 1, Coordinates::new(1, 0, 0));
+        game.make_move(PlayerId::new(0), Coordinates::new(0, 1, 0))
+            .unwrap();
+        assert!(game.check_game_over());
+
+        game.unmake_move();
+
+        assert_eq!(game.next_player(), Some(PlayerId::new(0)));
+        assert!(game.winning_group().is_none());
+    }
+
+    #[test]
+    fn test_make_move_validates_like_add_move() {
+        let mut game = GameY::new(3);
+        let result = game.make_move(PlayerId::new(1), Coordinates::new(2, 0, 0));
+        assert!(matches!(result, Err(GameYError::InvalidPlayerTurn { .. })));
+
+        game.make_move(PlayerId::new(0), Coordinates::new(2, 0, 0))
+            .unwrap();
+        let result = game.make_move(PlayerId::new(1), Coordinates::new(2, 0, 0));
+        assert!(matches!(result, Err(GameYError::Occupied { .. })));
+    }
+
+    #[test]
+    fn test_make_unmake_keeps_redo_log() {
+        let mut game = GameY::new(3);
+        play(&mut game, 0, Coordinates::new(0, 1, 1));
+        game.undo();
+
+        game.make_move(PlayerId::new(0), Coordinates::new(2, 0, 0))
+            .unwrap();
+        game.unmake_move();
+
+        assert!(game.can_redo());
+        assert_eq!(
+            game.redo(),
+            Some(Movement::Placement {
+                player: PlayerId::new(0),
+                coords: Coordinates::new(0, 1, 1),
+            })
+        );
+    }
+
+    #[test]
+    fn test_unmake_on_new_game_returns_none() {
+        let mut game = GameY::new(3);
+        assert!(game.unmake_move().is_none());
+    }
+
     #[test]
     fn test_try_from_invalid_turn_value() {
         let yen_str = r#"{
@@ -8,6 +8,9 @@
 //! - [`Movement`]: A move (placement or action) in the game
 //! - [`GameAction`]: Special actions like swap or resign
 //! - [`RenderOptions`]: Configuration for board rendering
+//! - [`Topology`]: Precomputed neighbour and side tables per board size
+//! - [`Symmetry`]: The six symmetries of the board, for canonical positions
+//! - [`zobrist`]: Keys used to hash positions
 
 pub mod action;
 pub mod coord;
@@ -16,6 +19,9 @@ pub mod movement;
 pub mod player;
 mod player_set;
 pub mod render_options;
+pub mod symmetry;
+pub mod topology;
+pub mod zobrist;
 
 pub use action::*;
 pub use coord::*;
@@ -23,5 +29,7 @@ pub use game::*;
 pub use movement::*;
 pub use player::*;
 pub use render_options::*;
+pub use symmetry::Symmetry;
+pub use topology::Topology;
 
 type SetIdx = usize;
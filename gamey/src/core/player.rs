@@ -47,6 +47,11 @@ impl PlayerId {
     pub fn id(&self) -> u32 {
         self.0
     }
+
+    /// Returns the identifier of the other player in a two-player game.
+    pub fn opponent(&self) -> PlayerId {
+        if self.0 == 0 { Self(1) } else { Self(0) }
+    }
 }
 
 impl Display for PlayerId {
@@ -71,6 +76,12 @@ mod tests {
         assert_eq!(format!("{}", id), "1");
     }
 
+    #[test]
+    fn test_player_id_opponent() {
+        assert_eq!(PlayerId::new(0).opponent(), PlayerId::new(1));
+        assert_eq!(PlayerId::new(1).opponent(), PlayerId::new(0));
+    }
+
     #[test]
     fn test_player_id_equality() {
         let id1 = PlayerId::new(1);
@@ -1,7 +1,8 @@
+use crate::Coordinates;
 use crate::core::SetIdx;
 
 // Struct to track connected components in the Union-Find structure
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) struct PlayerSet {
     pub parent: SetIdx,
     // We track which sides this specific set of pieces is touching
@@ -11,6 +12,26 @@ pub(crate) struct PlayerSet {
 }
 
 impl PlayerSet {
+    /// Creates the singleton set of an empty cell.
+    pub fn empty(idx: SetIdx) -> Self {
+        PlayerSet {
+            parent: idx,
+            touches_side_a: false,
+            touches_side_b: false,
+            touches_side_c: false,
+        }
+    }
+
+    /// Creates the singleton set of a stone placed at `coords`.
+    pub fn for_stone(idx: SetIdx, coords: Coordinates) -> Self {
+        PlayerSet {
+            parent: idx,
+            touches_side_a: coords.touches_side_a(),
+            touches_side_b: coords.touches_side_b(),
+            touches_side_c: coords.touches_side_c(),
+        }
+    }
+
     /// Checks if this set connects all three sides of the board.
     pub fn is_winning_configuration(&self) -> bool {
         self.touches_side_a && self.touches_side_b && self.touches_side_c
@@ -0,0 +1,167 @@
+//! Symmetries of the triangular board.
+//!
+//! The board looks the same after any permutation of the three barycentric
+//! axes: the three rotations and the three reflections that keep one corner
+//! in place. Positions that are images of each other under a symmetry have the
+//! same value, so opening books, solved tables and caches can share one entry
+//! for all of them through [`GameY::canonical_hash`](crate::GameY::canonical_hash).
+
+use crate::{Coordinates, Movement};
+
+/// One of the six symmetries of the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Symmetry {
+    /// Leaves every cell in place.
+    #[default]
+    Identity,
+    /// Exchanges `y` and `z`, keeping the corner where `x` is largest.
+    ReflectX,
+    /// Exchanges `x` and `z`, keeping the corner where `y` is largest.
+    ReflectY,
+    /// Exchanges `x` and `y`, keeping the corner where `z` is largest.
+    ReflectZ,
+    /// Rotates a third of a turn: `(x, y, z)` becomes `(y, z, x)`.
+    Rotate120,
+    /// Rotates two thirds of a turn: `(x, y, z)` becomes `(z, x, y)`.
+    Rotate240,
+}
+
+impl Symmetry {
+    /// All the symmetries, starting with the identity.
+    pub const ALL: [Symmetry; 6] = [
+        Symmetry::Identity,
+        Symmetry::ReflectX,
+        Symmetry::ReflectZ,
+        Symmetry::Rotate120,
+        Symmetry::Rotate240,
+        Symmetry::ReflectY,
+    ];
+
+    /// The axis each component of the image is taken from: the image of
+    /// `c = [x, y, z]` is `[c[p[0]], c[p[1]], c[p[2]]]`.
+    fn axes(self) -> [usize; 3] {
+        match self {
+            Symmetry::Identity => [0, 1, 2],
+            Symmetry::ReflectX => [0, 2, 1],
+            Symmetry::ReflectY => [2, 1, 0],
+            Symmetry::ReflectZ => [1, 0, 2],
+            Symmetry::Rotate120 => [1, 2, 0],
+            Symmetry::Rotate240 => [2, 0, 1],
+        }
+    }
+
+    /// Returns the symmetry that undoes this one.
+    pub fn inverse(self) -> Symmetry {
+        match self {
+            Symmetry::Rotate120 => Symmetry::Rotate240,
+            Symmetry::Rotate240 => Symmetry::Rotate120,
+            other => other,
+        }
+    }
+
+    /// Returns the symmetry that applies `self` and then `next`.
+    pub fn then(self, next: Symmetry) -> Symmetry {
+        let first = self.axes();
+        let second = next.axes();
+        let axes = [first[second[0]], first[second[1]], first[second[2]]];
+        Symmetry::ALL
+            .into_iter()
+            .find(|s| s.axes() == axes)
+            .expect("symmetries are closed under composition")
+    }
+
+    /// Returns the image of `coords`, a cell of the same board.
+    pub fn apply(self, coords: Coordinates) -> Coordinates {
+        let c = [coords.x(), coords.y(), coords.z()];
+        let [a, b, d] = self.axes();
+        Coordinates::new(c[a], c[b], c[d])
+    }
+
+    /// Returns the image of a move: placements move to the image cell and
+    /// actions are left as they are.
+    pub fn apply_movement(self, movement: &Movement) -> Movement {
+        match movement {
+            Movement::Placement { player, coords } => Movement::Placement {
+                player: *player,
+                coords: self.apply(*coords),
+            },
+            Movement::Action { .. } => movement.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameAction, PlayerId};
+
+    #[test]
+    fn test_images_are_cells_of_the_same_board() {
+        for symmetry in Symmetry::ALL {
+            let mut images: Vec<u32> = (0..15)
+                .map(|idx| symmetry.apply(Coordinates::from_index(idx, 5)).to_index(5))
+                .collect();
+            images.sort_unstable();
+            assert_eq!(images, (0..15).collect::<Vec<_>>(), "{:?}", symmetry);
+        }
+    }
+
+    #[test]
+    fn test_inverse_undoes_apply() {
+        let coords = Coordinates::new(3, 1, 0);
+        for symmetry in Symmetry::ALL {
+            assert_eq!(symmetry.inverse().apply(symmetry.apply(coords)), coords);
+            assert_eq!(symmetry.then(symmetry.inverse()), Symmetry::Identity);
+        }
+    }
+
+    #[test]
+    fn test_then_applies_both_in_order() {
+        let coords = Coordinates::new(4, 2, 0);
+        for first in Symmetry::ALL {
+            for second in Symmetry::ALL {
+                assert_eq!(first.then(second).apply(coords), second.apply(first.apply(coords)));
+            }
+        }
+        assert_eq!(Symmetry::Rotate120.then(Symmetry::Rotate120), Symmetry::Rotate240);
+    }
+
+    #[test]
+    fn test_each_symmetry_is_distinct() {
+        let coords = Coordinates::new(3, 1, 0);
+        for (i, a) in Symmetry::ALL.iter().enumerate() {
+            for b in &Symmetry::ALL[i + 1..] {
+                assert_ne!(a.apply(coords), b.apply(coords), "{:?} {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_reflections_keep_their_corner() {
+        assert_eq!(Symmetry::ReflectX.apply(Coordinates::new(4, 0, 0)), Coordinates::new(4, 0, 0));
+        assert_eq!(Symmetry::ReflectY.apply(Coordinates::new(0, 4, 0)), Coordinates::new(0, 4, 0));
+        assert_eq!(Symmetry::ReflectZ.apply(Coordinates::new(0, 0, 4)), Coordinates::new(0, 0, 4));
+        assert_eq!(Symmetry::Rotate120.apply(Coordinates::new(4, 0, 0)), Coordinates::new(0, 0, 4));
+    }
+
+    #[test]
+    fn test_apply_movement() {
+        let placement = Movement::Placement {
+            player: PlayerId::new(1),
+            coords: Coordinates::new(2, 1, 0),
+        };
+        assert_eq!(
+            Symmetry::ReflectZ.apply_movement(&placement),
+            Movement::Placement {
+                player: PlayerId::new(1),
+                coords: Coordinates::new(1, 2, 0),
+            }
+        );
+
+        let swap = Movement::Action {
+            player: PlayerId::new(1),
+            action: GameAction::Swap,
+        };
+        assert_eq!(Symmetry::Rotate120.apply_movement(&swap), swap);
+    }
+}
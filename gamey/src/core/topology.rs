@@ -0,0 +1,252 @@
+//! Precomputed board topology shared by every game of the same size.
+//!
+//! A [`Topology`] holds, for each cell index, its direct neighbours, its bridge
+//! (two-step) neighbours and the sides it touches. Tables are built once per
+//! board size on first use and handed out as `Arc`s, so lookups during play and
+//! search are plain slice reads without allocations.
+
+use crate::Coordinates;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Side membership bit for side A (x == 0).
+pub const SIDE_A: u8 = 0b001;
+/// Side membership bit for side B (y == 0).
+pub const SIDE_B: u8 = 0b010;
+/// Side membership bit for side C (z == 0).
+pub const SIDE_C: u8 = 0b100;
+/// Side membership bits of a cell touching the three sides.
+pub const ALL_SIDES: u8 = SIDE_A | SIDE_B | SIDE_C;
+
+/// Offsets of the direct neighbours of a cell, in barycentric coordinates.
+const NEIGHBOR_OFFSETS: [(i32, i32, i32); 6] = [
+    (-1, 1, 0),
+    (-1, 0, 1),
+    (1, -1, 0),
+    (0, -1, 1),
+    (1, 0, -1),
+    (0, 1, -1),
+];
+
+/// Offsets of the bridge neighbours of a cell: cells two steps away that share
+/// two direct neighbours with it.
+const BRIDGE_OFFSETS: [(i32, i32, i32); 6] = [
+    (2, -1, -1),
+    (1, -2, 1),
+    (-1, -1, 2),
+    (-2, 1, 1),
+    (-1, 2, -1),
+    (1, 1, -2),
+];
+
+/// Adjacency and side tables for one board size.
+pub struct Topology {
+    board_size: u32,
+    // Neighbours of cell `i` are `neighbors[neighbor_offsets[i]..neighbor_offsets[i + 1]]`.
+    neighbors: Vec<u32>,
+    neighbor_offsets: Vec<usize>,
+    // Bridge neighbours, laid out like `neighbors`.
+    bridges: Vec<u32>,
+    bridge_offsets: Vec<usize>,
+    // Side membership bits of each cell.
+    sides: Vec<u8>,
+    // Cells on each side, in index order.
+    side_cells: [Vec<u32>; 3],
+}
+
+impl Topology {
+    /// Returns the shared topology for `board_size`, building it on first use.
+    pub fn for_size(board_size: u32) -> Arc<Topology> {
+        static CACHE: OnceLock<Mutex<HashMap<u32, Arc<Topology>>>> = OnceLock::new();
+        let cache = CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+        let mut cache = cache.lock().unwrap_or_else(|e| e.into_inner());
+        cache
+            .entry(board_size)
+            .or_insert_with(|| Arc::new(Topology::build(board_size)))
+            .clone()
+    }
+
+    /// Builds the tables for `board_size`.
+    fn build(board_size: u32) -> Self {
+        let total_cells = (board_size * (board_size + 1)) / 2;
+        let mut topology = Topology {
+            board_size,
+            neighbors: Vec::new(),
+            neighbor_offsets: vec![0],
+            bridges: Vec::new(),
+            bridge_offsets: vec![0],
+            sides: Vec::with_capacity(total_cells as usize),
+            side_cells: [Vec::new(), Vec::new(), Vec::new()],
+        };
+
+        for idx in 0..total_cells {
+            let coords = Coordinates::from_index(idx, board_size);
+            topology
+                .neighbors
+                .extend(offset_cells(coords, &NEIGHBOR_OFFSETS, board_size));
+            topology.neighbor_offsets.push(topology.neighbors.len());
+            topology
+                .bridges
+                .extend(offset_cells(coords, &BRIDGE_OFFSETS, board_size));
+            topology.bridge_offsets.push(topology.bridges.len());
+
+            let mut sides = 0;
+            for (side, (bit, touches)) in [
+                (SIDE_A, coords.touches_side_a()),
+                (SIDE_B, coords.touches_side_b()),
+                (SIDE_C, coords.touches_side_c()),
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                if touches {
+                    sides |= bit;
+                    topology.side_cells[side].push(idx);
+                }
+            }
+            topology.sides.push(sides);
+        }
+        topology
+    }
+
+    /// Returns the board size these tables were built for.
+    pub fn board_size(&self) -> u32 {
+        self.board_size
+    }
+
+    /// Returns the total number of cells on the board.
+    pub fn total_cells(&self) -> u32 {
+        self.sides.len() as u32
+    }
+
+    /// Returns the indices of the cells adjacent to `cell_idx`.
+    pub fn neighbors(&self, cell_idx: u32) -> &[u32] {
+        let i = cell_idx as usize;
+        &self.neighbors[self.neighbor_offsets[i]..self.neighbor_offsets[i + 1]]
+    }
+
+    /// Returns the indices of the bridge (two-step) neighbours of `cell_idx`.
+    pub fn bridges(&self, cell_idx: u32) -> &[u32] {
+        let i = cell_idx as usize;
+        &self.bridges[self.bridge_offsets[i]..self.bridge_offsets[i + 1]]
+    }
+
+    /// Returns the side membership bits ([`SIDE_A`], [`SIDE_B`], [`SIDE_C`]) of `cell_idx`.
+    pub fn sides(&self, cell_idx: u32) -> u8 {
+        self.sides[cell_idx as usize]
+    }
+
+    /// Returns true if `cell_idx` lies on at least one side of the board.
+    pub fn is_edge(&self, cell_idx: u32) -> bool {
+        self.sides(cell_idx) != 0
+    }
+
+    /// Returns the cells on side A (x == 0), in index order.
+    pub fn side_a(&self) -> &[u32] {
+        &self.side_cells[0]
+    }
+
+    /// Returns the cells on side B (y == 0), in index order.
+    pub fn side_b(&self) -> &[u32] {
+        &self.side_cells[1]
+    }
+
+    /// Returns the cells on side C (z == 0), in index order.
+    pub fn side_c(&self) -> &[u32] {
+        &self.side_cells[2]
+    }
+}
+
+impl fmt::Debug for Topology {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Topology")
+            .field("board_size", &self.board_size)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns the indices of the cells at the given offsets of `coords` that lie on the board.
+fn offset_cells(
+    coords: Coordinates,
+    offsets: &[(i32, i32, i32)],
+    board_size: u32,
+) -> impl Iterator<Item = u32> {
+    let n = board_size as i32 - 1;
+    let (x, y, z) = (coords.x() as i32, coords.y() as i32, coords.z() as i32);
+    offsets.iter().filter_map(move |&(dx, dy, dz)| {
+        let (nx, ny, nz) = (x + dx, y + dy, z + dz);
+        let inside = [nx, ny, nz].iter().all(|&c| (0..=n).contains(&c));
+        inside.then(|| Coordinates::new(nx as u32, ny as u32, nz as u32).to_index(board_size))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_size_is_shared() {
+        let a = Topology::for_size(6);
+        let b = Topology::for_size(6);
+        assert!(Arc::ptr_eq(&a, &b));
+        assert_eq!(a.board_size(), 6);
+        assert_eq!(a.total_cells(), 21);
+    }
+
+    #[test]
+    fn test_neighbor_counts() {
+        let topology = Topology::for_size(5);
+        let corner = Coordinates::new(4, 0, 0).to_index(5);
+        let edge = Coordinates::new(0, 2, 2).to_index(5);
+        let interior = Coordinates::new(2, 1, 1).to_index(5);
+
+        assert_eq!(topology.neighbors(corner).len(), 2);
+        assert_eq!(topology.neighbors(edge).len(), 4);
+        assert_eq!(topology.neighbors(interior).len(), 6);
+    }
+
+    #[test]
+    fn test_neighbors_are_symmetric() {
+        let topology = Topology::for_size(7);
+        for idx in 0..topology.total_cells() {
+            for &n in topology.neighbors(idx) {
+                assert!(topology.neighbors(n).contains(&idx));
+            }
+            for &b in topology.bridges(idx) {
+                assert!(topology.bridges(b).contains(&idx));
+            }
+        }
+    }
+
+    #[test]
+    fn test_bridges_share_two_neighbors() {
+        let topology = Topology::for_size(7);
+        for idx in 0..topology.total_cells() {
+            for &b in topology.bridges(idx) {
+                let common = topology
+                    .neighbors(idx)
+                    .iter()
+                    .filter(|n| topology.neighbors(b).contains(n))
+                    .count();
+                assert_eq!(common, 2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sides() {
+        let topology = Topology::for_size(4);
+        assert_eq!(topology.side_a().len(), 4);
+        assert_eq!(topology.side_b().len(), 4);
+        assert_eq!(topology.side_c().len(), 4);
+
+        let top = Coordinates::new(3, 0, 0).to_index(4);
+        assert_eq!(topology.sides(top), SIDE_B | SIDE_C);
+        let center = Coordinates::new(1, 1, 1).to_index(4);
+        assert!(!topology.is_edge(center));
+
+        let single = Topology::for_size(1);
+        assert_eq!(single.sides(0), ALL_SIDES);
+    }
+}
@@ -0,0 +1,60 @@
+//! Zobrist keys used to hash game positions.
+//!
+//! Every (cell, player) pair and every side to move has a fixed pseudo-random
+//! 64-bit key. The hash of a position is the XOR of the keys of its stones and
+//! of the player to move, so it can be updated incrementally as stones are
+//! placed or taken back. Keys are derived from the cell index and do not
+//! depend on the board size, so positions should be compared together with
+//! their size.
+
+use crate::PlayerId;
+
+/// Seed of the key sequence. Changing it changes every stored hash.
+const ZOBRIST_SEED: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// Key offset reserved for the side to move, above any stone key.
+const SIDE_TO_MOVE_OFFSET: u64 = 1 << 40;
+
+/// SplitMix64 finalizer, used to turn a counter into a well mixed key.
+fn splitmix64(mut z: u64) -> u64 {
+    z = z.wrapping_add(ZOBRIST_SEED);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Returns the key of a stone of `player` on the cell with index `cell_idx`.
+pub fn stone_key(cell_idx: u32, player: PlayerId) -> u64 {
+    splitmix64(((cell_idx as u64) << 1) | (player.id() as u64 & 1))
+}
+
+/// Returns the key added to the hash when `player` is the one to move.
+pub fn side_key(player: PlayerId) -> u64 {
+    splitmix64(SIDE_TO_MOVE_OFFSET | player.id() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_keys_are_distinct() {
+        let mut keys = HashSet::new();
+        for cell in 0..500 {
+            assert!(keys.insert(stone_key(cell, PlayerId::new(0))));
+            assert!(keys.insert(stone_key(cell, PlayerId::new(1))));
+        }
+        assert!(keys.insert(side_key(PlayerId::new(0))));
+        assert!(keys.insert(side_key(PlayerId::new(1))));
+    }
+
+    #[test]
+    fn test_keys_are_stable() {
+        assert_eq!(
+            stone_key(7, PlayerId::new(1)),
+            stone_key(7, PlayerId::new(1))
+        );
+        assert_ne!(stone_key(7, PlayerId::new(0)), 0);
+    }
+}
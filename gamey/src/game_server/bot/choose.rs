@@ -1,10 +1,11 @@
-use crate::{Coordinates, GameY, YEN};
+use crate::{Coordinates, GameY, SearchLimits, SearchResult, YBot, YBotRegistry, YEN};
 use crate::game_server::{version::check_api_version, error::ErrorResponse, state::AppState};
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
 };
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 /// Path parameters extracted from the choose endpoint URL.
 #[derive(Deserialize)]
@@ -15,11 +16,94 @@ pub struct ChooseParams {
     bot_id: String,
 }
 
+/// Largest limits a client can ask for: one request cannot keep a bot
+/// searching for longer than this.
+pub const MAX_SEARCH_LIMITS: SearchLimits = SearchLimits {
+    max_time_ms: Some(10_000),
+    max_nodes: Some(10_000_000),
+    max_depth: Some(32),
+};
+
+/// Node budget of a seeded search that does not set `max_nodes`.
+pub const REPLAY_NODES: u64 = 20_000;
+
+/// Optional `seed` query parameter to make a bot move replayable.
+#[derive(Deserialize, Debug, Default, Clone, Copy)]
+pub struct SeedParams {
+    /// Seed to play (or replay) a move with. Without it the shared bot plays.
+    pub seed: Option<u64>,
+}
+
+impl SeedParams {
+    /// Returns the bot that plays the request.
+    ///
+    /// With a seed, a fresh single-threaded copy drawing from it (see
+    /// [`YBotRegistry::find_seeded`]). Without one, the registered bot, which
+    /// keeps its search trees and tables from one request to the next.
+    pub fn find_bot(&self, bots: &YBotRegistry, name: &str) -> Option<Arc<dyn YBot>> {
+        match self.seed {
+            Some(seed) => bots.find_seeded(name, seed),
+            None => bots.find(name),
+        }
+    }
+
+    /// Returns the limits to search with.
+    ///
+    /// A seeded search is bounded by nodes: `max_nodes` if given, or
+    /// [`REPLAY_NODES`]. Unless the request sets a time, it gets the largest
+    /// time of [`MAX_SEARCH_LIMITS`], so the search stops on its node budget and
+    /// the same seed replays the same move. Searches without a seed keep `limits`.
+    pub fn limits(&self, limits: SearchLimits) -> SearchLimits {
+        if self.seed.is_none() {
+            return limits;
+        }
+        SearchLimits {
+            max_time_ms: limits.max_time_ms.or(MAX_SEARCH_LIMITS.max_time_ms),
+            max_nodes: Some(limits.max_nodes.unwrap_or(REPLAY_NODES)),
+            max_depth: limits.max_depth,
+        }
+    }
+}
+
+/// What the bot reported about its move, as returned by the choose endpoint.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MoveAnalysis {
+    /// Evaluation of the position for the bot, in the bot's own units.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<i32>,
+    /// Estimated probability that the bot wins, between 0 and 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub win_probability: Option<f64>,
+    /// Expected continuation, starting with the chosen move.
+    #[serde(default)]
+    pub pv: Vec<Coordinates>,
+    /// Depth reached by the search, for bots that search by depth.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depth: Option<u32>,
+    /// Nodes (or iterations) searched; 0 if the bot does not count them.
+    pub nodes: u64,
+    /// Time the bot spent thinking, in milliseconds.
+    pub elapsed_ms: u64,
+}
+
+impl From<SearchResult> for MoveAnalysis {
+    fn from(result: SearchResult) -> Self {
+        MoveAnalysis {
+            score: result.score,
+            win_probability: result.win_probability,
+            pv: result.principal_variation,
+            depth: result.depth,
+            nodes: result.nodes,
+            elapsed_ms: result.elapsed_ms,
+        }
+    }
+}
+
 /// Response returned by the choose endpoint on success.
 ///
 /// Contains the bot's chosen move coordinates along with context
 /// about which API version and bot were used.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct MoveResponse {
     /// The API version used for this request.
     pub api_version: String,
@@ -27,6 +111,13 @@ pub struct MoveResponse {
     pub bot_id: String,
     /// The coordinates where the bot chooses to place its piece.
     pub coords: Coordinates,
+    /// The bot's evaluation of the move, when available.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub analysis: Option<MoveAnalysis>,
+    /// The seed of the request, if one was sent. Sending the same seed again
+    /// replays the same move.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
 }
 
 /// Handler for the bot move selection endpoint.
@@ -37,16 +128,26 @@ pub struct MoveResponse {
 /// # Route
 /// `POST /{api_version}/ybot/choose/{bot_id}`
 ///
+/// # Query Parameters
+/// Optional search limits: `max_time_ms`, `max_nodes` and `max_depth`.
+/// Limits that are not given use the bot's defaults, and larger ones are
+/// lowered to [`MAX_SEARCH_LIMITS`].
+/// Optional `seed` to make the move replayable: the search then runs on a
+/// fresh copy of the bot with a node budget (see [`SeedParams`]).
+///
 /// # Request Body
 /// A JSON object in YEN format representing the current game state.
 ///
 /// # Response
-/// On success, returns a `MoveResponse` with the chosen coordinates.
+/// On success, returns a `MoveResponse` with the chosen coordinates and
+/// the bot's analysis.
 /// On failure, returns an `ErrorResponse` with details about what went wrong.
 #[axum::debug_handler]
 pub async fn choose(
     State(state): State<AppState>,
     Path(params): Path<ChooseParams>,
+    Query(limits): Query<SearchLimits>,
+    Query(seed): Query<SeedParams>,
     Json(yen): Json<YEN>,
 ) -> Result<Json<MoveResponse>, Json<ErrorResponse>> {
     check_api_version(&params.api_version)?;
@@ -60,7 +161,7 @@ pub async fn choose(
             )));
         }
     };
-    let bot = match state.bots().find(&params.bot_id) {
+    let bot = match seed.find_bot(&state.bots(), &params.bot_id) {
         Some(bot) => bot,
         None => {
             let available_bots = state.bots().names().join(", ");
@@ -74,7 +175,8 @@ pub async fn choose(
             )));
         }
     };
-    let coords = match bot.choose_move(&game_y) {
+    let result = bot.search(&game_y, &seed.limits(limits).clamped_to(&MAX_SEARCH_LIMITS));
+    let coords = match result.best_move {
         Some(coords) => coords,
         None => {
             // Handle the case where the bot has no valid moves
@@ -89,6 +191,8 @@ pub async fn choose(
         api_version: params.api_version,
         bot_id: params.bot_id,
         coords,
+        analysis: Some(result.into()),
+        seed: seed.seed,
     };
     Ok(Json(response))
 }
@@ -103,6 +207,8 @@ mod tests {
             api_version: "v1".to_string(),
             bot_id: "random".to_string(),
             coords: Coordinates::new(1, 2, 3),
+            analysis: None,
+            seed: None,
         };
         assert_eq!(response.api_version, "v1");
         assert_eq!(response.bot_id, "random");
@@ -115,6 +221,8 @@ mod tests {
             api_version: "v1".to_string(),
             bot_id: "random".to_string(),
             coords: Coordinates::new(1, 2, 3),
+            analysis: None,
+            seed: None,
         };
         let json = serde_json::to_string(&response).unwrap();
         assert!(json.contains("\"api_version\":\"v1\""));
@@ -127,6 +235,33 @@ mod tests {
         let response: MoveResponse = serde_json::from_str(json).unwrap();
         assert_eq!(response.api_version, "v1");
         assert_eq!(response.bot_id, "test");
+        assert_eq!(response.analysis, None);
+    }
+
+    #[test]
+    fn test_move_response_serializes_analysis() {
+        let coords = Coordinates::new(1, 0, 1);
+        let result = SearchResult {
+            score: Some(120),
+            nodes: 500,
+            ..SearchResult::from_move(Some(coords), std::time::Duration::from_millis(7))
+        };
+        let response = MoveResponse {
+            api_version: "v1".to_string(),
+            bot_id: "alfa_beta_bot".to_string(),
+            coords,
+            analysis: Some(result.into()),
+            seed: Some(5),
+        };
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(json.contains("\"seed\":5"));
+        assert!(json.contains("\"score\":120"));
+        assert!(json.contains("\"nodes\":500"));
+        assert!(json.contains("\"elapsed_ms\":7"));
+        assert!(!json.contains("win_probability"));
+
+        let parsed: MoveResponse = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, response);
     }
 
     #[test]
@@ -135,6 +270,8 @@ mod tests {
             api_version: "v1".to_string(),
             bot_id: "random".to_string(),
             coords: Coordinates::new(0, 0, 0),
+            analysis: None,
+            seed: None,
         };
         let cloned = response.clone();
         assert_eq!(response, cloned);
@@ -146,16 +283,22 @@ mod tests {
             api_version: "v1".to_string(),
             bot_id: "random".to_string(),
             coords: Coordinates::new(1, 1, 1),
+            analysis: None,
+            seed: None,
         };
         let r2 = MoveResponse {
             api_version: "v1".to_string(),
             bot_id: "random".to_string(),
             coords: Coordinates::new(1, 1, 1),
+            analysis: None,
+            seed: None,
         };
         let r3 = MoveResponse {
             api_version: "v2".to_string(),
             bot_id: "random".to_string(),
             coords: Coordinates::new(1, 1, 1),
+            analysis: None,
+            seed: None,
         };
         assert_eq!(r1, r2);
         assert_ne!(r1, r3);
@@ -9,3 +9,4 @@ pub mod new;
 pub mod pvb;
 pub mod pvp;
 pub mod check;
+pub mod outcome;
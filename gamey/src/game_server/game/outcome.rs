@@ -0,0 +1,148 @@
+//! Game outcome shared by the game endpoints.
+//!
+//! The winner and its winning chain come from the engine's union-find,
+//! so the handlers never recompute connectivity on the raw layout.
+
+use crate::{Coordinates, GameStatus, GameY, GameYError, YEN};
+
+/// An edge of the winning chain in the frontend format: `[[r1, c1], [r2, c2]]`.
+pub type Edge = [[usize; 2]; 2];
+
+/// Default symbols for each player when the YEN does not provide them.
+const DEFAULT_SYMBOLS: [char; 2] = ['B', 'R'];
+
+/// Whether a game is finished, who won and the chain that connects the three sides.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameOutcome {
+    pub finished: bool,
+    pub winner: Option<char>,
+    pub winning_edges: Vec<Edge>,
+}
+
+/// Returns the symbol used for a player in a YEN, falling back to B/R.
+fn player_symbol(players: &[char], index: usize) -> char {
+    players
+        .get(index)
+        .copied()
+        .unwrap_or(DEFAULT_SYMBOLS[index])
+}
+
+/// Builds a game from a YEN whose players may use custom symbols.
+///
+/// The symbols of the YEN are mapped to the engine's B/R and a trailing
+/// '/' in the layout is tolerated.
+pub fn game_from_yen(yen: &YEN) -> Result<GameY, GameYError> {
+    let p0 = player_symbol(yen.players(), 0);
+    let p1 = player_symbol(yen.players(), 1);
+    let layout: String = yen
+        .layout()
+        .trim_end_matches('/')
+        .chars()
+        .map(|c| match c {
+            c if c == p0 => DEFAULT_SYMBOLS[0],
+            c if c == p1 => DEFAULT_SYMBOLS[1],
+            c => c,
+        })
+        .collect();
+
+    GameY::try_from(YEN::new(
+        yen.size(),
+        yen.turn(),
+        DEFAULT_SYMBOLS.to_vec(),
+        layout,
+    ))
+}
+
+/// Converts the row/column sent by the frontend into board coordinates.
+pub fn row_col_to_coords(size: u32, row: usize, col: usize) -> Result<Coordinates, String> {
+    let row = u32::try_from(row).map_err(|_| format!("row out of bounds: {}", row))?;
+    let col = u32::try_from(col).map_err(|_| format!("col out of bounds: {}", col))?;
+    Coordinates::try_from_row_col(row, col, size).map_err(|e| e.to_string())
+}
+
+/// Computes the outcome of a game, naming the winner with the given player symbols.
+pub fn game_outcome(game: &GameY, players: &[char]) -> GameOutcome {
+    let GameStatus::Finished { winner } = game.status() else {
+        return GameOutcome {
+            finished: false,
+            winner: None,
+            winning_edges: vec![],
+        };
+    };
+
+    let size = game.board_size();
+    let winning_edges = game
+        .winning_group()
+        .map(|group| {
+            game.group_edges(&group)
+                .into_iter()
+                .map(|(a, b)| {
+                    let (r1, c1) = a.to_row_col(size);
+                    let (r2, c2) = b.to_row_col(size);
+                    [[r1 as usize, c1 as usize], [r2 as usize, c2 as usize]]
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    GameOutcome {
+        finished: true,
+        winner: Some(player_symbol(players, winner.id() as usize)),
+        winning_edges,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome_of(yen: &YEN) -> GameOutcome {
+        let game = game_from_yen(yen).unwrap();
+        game_outcome(&game, yen.players())
+    }
+
+    #[test]
+    fn test_ongoing_game_has_no_outcome() {
+        let yen = YEN::new(3, 0, vec!['B', 'R'], "./../...".to_string());
+        let outcome = outcome_of(&yen);
+
+        assert!(!outcome.finished);
+        assert_eq!(outcome.winner, None);
+        assert!(outcome.winning_edges.is_empty());
+    }
+
+    #[test]
+    fn test_winning_edges_follow_the_chain() {
+        // B holds the left column: (0,0), (1,0), (2,0)
+        let yen = YEN::new(3, 1, vec!['B', 'R'], "B/BR/B..".to_string());
+        let outcome = outcome_of(&yen);
+
+        assert!(outcome.finished);
+        assert_eq!(outcome.winner, Some('B'));
+        assert_eq!(
+            outcome.winning_edges,
+            vec![[[0, 0], [1, 0]], [[1, 0], [2, 0]]]
+        );
+    }
+
+    #[test]
+    fn test_custom_symbols_are_mapped() {
+        let yen = YEN::new(3, 0, vec!['X', 'O'], "O/OX/OX.".to_string());
+        let outcome = outcome_of(&yen);
+
+        assert!(outcome.finished);
+        assert_eq!(outcome.winner, Some('O'));
+    }
+
+    #[test]
+    fn test_invalid_symbol_is_rejected() {
+        let yen = YEN::new(1, 0, vec!['B', 'R'], "X".to_string());
+        assert!(game_from_yen(&yen).is_err());
+    }
+
+    #[test]
+    fn test_trailing_slash_is_tolerated() {
+        let yen = YEN::new(3, 0, vec!['B', 'R'], "./../.../".to_string());
+        assert!(game_from_yen(&yen).is_ok());
+    }
+}
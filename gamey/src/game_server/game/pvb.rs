@@ -1,19 +1,19 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     Json,
 };
 use axum::http::StatusCode;
 use serde::{Deserialize, Serialize};
 
-use crate::{Coordinates, GameY, Movement, PlayerId, YEN};
+use crate::{GameAction, GameY, Movement, SearchLimits, YEN};
 use crate::game_server::{
+    bot::choose::SeedParams,
     error::ErrorResponse,
+    game::outcome::{game_outcome, row_col_to_coords, Edge},
     state::AppState,
     version::check_api_version,
 };
 
-use std::collections::{HashSet, VecDeque};
-
 #[derive(Deserialize)]
 pub struct PvbParams {
     pub api_version: String,
@@ -34,205 +34,21 @@ pub struct PvbMoveResponse {
     pub yen: YEN,
     pub finished: bool,
     pub winner: Option<char>,
-    pub winning_edges: Vec<[[usize; 2]; 2]>,
-}
-
-fn row_col_to_coords(
-    layout: &str,
-    size: u32,
-    row: usize,
-    col: usize,
-) -> Result<Coordinates, String> {
-    let rows: Vec<&str> = layout.split('/').collect();
-
-    if row >= rows.len() {
-        return Err(format!("row out of bounds: {} (rows={})", row, rows.len()));
-    }
-
-    let row_len = rows[row].chars().count();
-    if col >= row_len {
-        return Err(format!("col out of bounds: {} (row_len={})", col, row_len));
-    }
-
-    let mut index: usize = 0;
-    for r in 0..row {
-        index += rows[r].chars().count();
-    }
-    index += col;
-
-    let total_cells: usize = rows.iter().map(|r| r.chars().count()).sum();
-    if index >= total_cells {
-        return Err("Invalid coordinate conversion".to_string());
-    }
-
-    let coords = Coordinates::from_index(index as u32, size);
-
-    Ok(coords)
-}
-
-/// Parses YEN layout string into a 2D matrix of chars.
-/// Rows are split by '/', each row is a Vec<char>.
-fn parse_layout(layout: &str) -> Vec<Vec<char>> {
-    if layout.is_empty() {
-        return vec![];
-    }
-    layout.split('/').map(|row| row.chars().collect()).collect()
-}
-
-/// Returns neighbors for the "triangular/hex-like" adjacency used by the frontend.
-fn neighbors(layout: &[Vec<char>], r: isize, c: isize) -> Vec<(usize, usize)> {
-    let n = layout.len() as isize;
-    let in_bounds = |rr: isize, cc: isize| -> bool {
-        if rr < 0 || rr >= n { return false; }
-        let row_len = layout[rr as usize].len() as isize;
-        cc >= 0 && cc < row_len
-    };
-
-    let candidates = [
-        (r, c - 1),
-        (r, c + 1),
-        (r - 1, c - 1),
-        (r - 1, c),
-        (r + 1, c),
-        (r + 1, c + 1),
-    ];
-
-    candidates
-        .iter()
-        .copied()
-        .filter(|(rr, cc)| in_bounds(*rr, *cc))
-        .map(|(rr, cc)| (rr as usize, cc as usize))
-        .collect()
-}
-
-/// Computes the winning connected component for a token, if any.
-/// Win condition is the same as your frontend: touches left + right + bottom.
-fn compute_winner_component(layout: &[Vec<char>], token: char) -> Option<HashSet<(usize, usize)>> {
-    let n = layout.len();
-    if n == 0 {
-        return None;
-    }
-
-    let mut visited: HashSet<(usize, usize)> = HashSet::new();
-
-    for r in 0..n {
-        for c in 0..layout[r].len() {
-            if layout[r][c] != token {
-                continue;
-            }
-            if visited.contains(&(r, c)) {
-                continue;
-            }
-
-            let mut touches_left = false;
-            let mut touches_right = false;
-            let mut touches_bottom = false;
-
-            let mut queue: VecDeque<(usize, usize)> = VecDeque::new();
-            let mut component: HashSet<(usize, usize)> = HashSet::new();
-
-            visited.insert((r, c));
-            component.insert((r, c));
-            queue.push_back((r, c));
-
-            while let Some((rr, cc)) = queue.pop_front() {
-                if cc == 0 {
-                    touches_left = true;
-                }
-                if cc == layout[rr].len().saturating_sub(1) {
-                    touches_right = true;
-                }
-                if rr == n - 1 {
-                    touches_bottom = true;
-                }
-
-                if touches_left && touches_right && touches_bottom {
-                    return Some(component);
-                }
-
-                for (nr, nc) in neighbors(layout, rr as isize, cc as isize) {
-                    if layout[nr][nc] != token {
-                        continue;
-                    }
-                    if visited.contains(&(nr, nc)) {
-                        continue;
-                    }
-                    visited.insert((nr, nc));
-                    component.insert((nr, nc));
-                    queue.push_back((nr, nc));
-                }
-            }
-        }
-    }
-
-    None
-}
-
-/// Builds unique edges between adjacent cells within a component.
-/// The edge format matches the frontend expectation: [[[r1,c1],[r2,c2]], ...]
-fn build_edges(layout: &[Vec<char>], component: &HashSet<(usize, usize)>) -> Vec<[[usize; 2]; 2]> {
-    let mut edges: Vec<[[usize; 2]; 2]> = vec![];
-    let mut seen: HashSet<((usize, usize), (usize, usize))> = HashSet::new();
-
-    for &(r, c) in component.iter() {
-        for (nr, nc) in neighbors(layout, r as isize, c as isize) {
-            if !component.contains(&(nr, nc)) {
-                continue;
-            }
-
-            // Avoid duplicates by sorting endpoints
-            let a = (r, c);
-            let b = (nr, nc);
-            let (p, q) = if a <= b { (a, b) } else { (b, a) };
-
-            if seen.contains(&(p, q)) {
-                continue;
-            }
-            seen.insert((p, q));
-
-            edges.push([[p.0, p.1], [q.0, q.1]]);
-        }
-    }
-
-    edges
-}
-
-/// Computes finished/winner/edges from a YEN state.
-/// Winner detection is UI-oriented; engine remains unchanged.
-fn compute_result_from_yen(yen: &YEN) -> (bool, Option<char>, Vec<[[usize; 2]; 2]>) {
-    let layout = parse_layout(yen.layout());
-    if layout.is_empty() {
-        return (false, None, vec![]);
-    }
-
-    // Finished: no '.' left OR there is a winner
-    let any_empty = layout.iter().any(|row| row.iter().any(|&ch| ch == '.'));
-
-    let players = yen.players();
-    let p0 = players.get(0).copied().unwrap_or('B');
-    let p1 = players.get(1).copied().unwrap_or('R');
-
-    if let Some(comp) = compute_winner_component(&layout, p0) {
-        let edges = build_edges(&layout, &comp);
-        return (true, Some(p0), edges);
-    }
-
-    if let Some(comp) = compute_winner_component(&layout, p1) {
-        let edges = build_edges(&layout, &comp);
-        return (true, Some(p1), edges);
-    }
-
-    if !any_empty {
-        return (true, None, vec![]);
-    }
-
-    (false, None, vec![])
+    pub winning_edges: Vec<Edge>,
+    /// Set when the bot did not place a stone: "swap" or "resign".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bot_action: Option<String>,
+    /// The seed of the request, if one was sent and the bot moved. Sending
+    /// the same seed again replays the same bot move.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
 }
 
 #[axum::debug_handler]
 pub async fn pvb_move(
     State(state): State<AppState>,
     Path(params): Path<PvbParams>,
+    Query(seed): Query<SeedParams>,
     Json(req): Json<PvbMoveRequest>,
 ) -> Result<Json<PvbMoveResponse>, (StatusCode, Json<ErrorResponse>)> {
     // 1) API version is checked
@@ -241,7 +57,6 @@ pub async fn pvb_move(
     }
 
     // 2) Save game size
-    let layout_str = req.yen.layout().to_string();
     let size = req.yen.size();
 
     // 3) Parse YEN -> Game
@@ -272,7 +87,7 @@ pub async fn pvb_move(
     }
 
     // We convert to coords
-    let coords = match row_col_to_coords(&layout_str, size, req.row, req.col) {
+    let coords = match row_col_to_coords(size, req.row, req.col) {
         Ok(c) => c,
         Err(msg) => {
             return Err((
@@ -286,8 +101,8 @@ pub async fn pvb_move(
         }
     };
 
-    // Human move
-    let human_player = PlayerId::new(0);
+    // Human move, for whichever player the YEN says is to move
+    let human_player = game.next_player().expect("the game is not over");
 
     let human_move = Movement::Placement {
         player: human_player,
@@ -308,18 +123,20 @@ pub async fn pvb_move(
     // If we finished the game, we return
     if game.check_game_over() {
         let new_yen: YEN = (&game).into();
-        let (finished, winner, winning_edges) = compute_result_from_yen(&new_yen);
+        let outcome = game_outcome(&game, new_yen.players());
 
         return Ok(Json(PvbMoveResponse {
             yen: new_yen,
-            finished,
-            winner,
-            winning_edges,
+            finished: outcome.finished,
+            winner: outcome.winner,
+            winning_edges: outcome.winning_edges,
+            bot_action: None,
+            seed: None,
         }));
     }
 
     // We search for the bot
-    let bot = match state.bots().find(&params.bot_id) {
+    let bot = match seed.find_bot(&state.bots(), &params.bot_id) {
         Some(b) => b,
         None => {
             let available = state.bots().names().join(", ");
@@ -337,9 +154,9 @@ pub async fn pvb_move(
         }
     };
 
-    // We choose the bot move
-    let bot_coords = match bot.choose_move(&game) {
-        Some(c) => c,
+    // The bot decides whether to place a stone, swap or resign
+    let decision = match bot.decide(&game, &seed.limits(SearchLimits::default())) {
+        Some(d) => d,
         None => {
             return Err((
                 StatusCode::BAD_REQUEST,
@@ -351,28 +168,9 @@ pub async fn pvb_move(
             ));
         }
     };
+    let bot_action = decision.action().map(action_name);
 
-    // Bot's turn
-    let bot_player = match game.next_player() {
-        Some(p) => p,
-        None => {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse::error(
-                    "No next player available for bot move",
-                    Some(params.api_version),
-                    Some(params.bot_id),
-                )),
-            ));
-        }
-    };
-
-    let bot_move = Movement::Placement {
-        player: bot_player,
-        coords: bot_coords,
-    };
-
-    if let Err(e) = game.add_move(bot_move) {
+    if let Err(e) = game.add_move(decision.movement) {
         return Err((
             StatusCode::BAD_REQUEST,
             Json(ErrorResponse::error(
@@ -385,16 +183,26 @@ pub async fn pvb_move(
 
     // New state is returned
     let new_yen: YEN = (&game).into();
-    let (finished, winner, winning_edges) = compute_result_from_yen(&new_yen);
+    let outcome = game_outcome(&game, new_yen.players());
 
     Ok(Json(PvbMoveResponse {
         yen: new_yen,
-        finished,
-        winner,
-        winning_edges,
+        finished: outcome.finished,
+        winner: outcome.winner,
+        winning_edges: outcome.winning_edges,
+        bot_action,
+        seed: seed.seed,
     }))
 }
 
+/// Name of a bot action in the response.
+fn action_name(action: &GameAction) -> String {
+    match action {
+        GameAction::Swap => "swap".to_string(),
+        GameAction::Resign => "resign".to_string(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -408,7 +216,7 @@ mod tests {
     #[tokio::test]
     async fn test_pvb_valid_request() {
         let registry =
-            YBotRegistry::new().with_bot(std::sync::Arc::new(RandomBot));
+            YBotRegistry::new().with_bot(std::sync::Arc::new(RandomBot::new(None)));
         let state = AppState::new(registry);
         let app = create_router(state);
 
@@ -436,6 +244,8 @@ mod tests {
         let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
         let parsed: PvbMoveResponse = serde_json::from_slice(&bytes).unwrap();
         assert!(parsed.yen.size() > 0);
+        // Without a seed the shared bot plays and no seed is reported
+        assert_eq!(parsed.seed, None);
     }
 
     #[tokio::test]
@@ -467,7 +277,7 @@ mod tests {
     #[tokio::test]
     async fn test_invalid_api_version() {
         let registry =
-            YBotRegistry::new().with_bot(std::sync::Arc::new(RandomBot));
+            YBotRegistry::new().with_bot(std::sync::Arc::new(RandomBot::new(None)));
         let state = AppState::new(registry);
         let app = create_router(state);
 
@@ -494,7 +304,7 @@ mod tests {
     #[tokio::test]
     async fn test_pvb_row_out_of_bounds() {
         let registry =
-            YBotRegistry::new().with_bot(std::sync::Arc::new(RandomBot));
+            YBotRegistry::new().with_bot(std::sync::Arc::new(RandomBot::new(None)));
         let state = AppState::new(registry);
         let app = create_router(state);
 
@@ -521,7 +331,7 @@ mod tests {
     #[tokio::test]
     async fn test_pvb_col_out_of_bounds() {
         let registry =
-            YBotRegistry::new().with_bot(std::sync::Arc::new(RandomBot));
+            YBotRegistry::new().with_bot(std::sync::Arc::new(RandomBot::new(None)));
         let state = AppState::new(registry);
         let app = create_router(state);
 
@@ -548,7 +358,7 @@ mod tests {
     #[tokio::test]
     async fn test_pvb_rejects_finished_game() {
         let registry =
-            YBotRegistry::new().with_bot(std::sync::Arc::new(RandomBot));
+            YBotRegistry::new().with_bot(std::sync::Arc::new(RandomBot::new(None)));
         let state = AppState::new(registry);
         let app = create_router(state);
 
@@ -580,7 +390,7 @@ mod tests {
     #[tokio::test]
     async fn test_pvb_rejects_invalid_yen_format() {
         let registry =
-            YBotRegistry::new().with_bot(std::sync::Arc::new(RandomBot));
+            YBotRegistry::new().with_bot(std::sync::Arc::new(RandomBot::new(None)));
         let state = AppState::new(registry);
         let app = create_router(state);
 
@@ -607,4 +417,176 @@ mod tests {
 
         assert_eq!(response.status(), StatusCode::BAD_REQUEST);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_pvb_human_winning_move_returns_chain() {
+        let registry =
+            YBotRegistry::new().with_bot(std::sync::Arc::new(RandomBot::new(None)));
+        let state = AppState::new(registry);
+        let app = create_router(state);
+
+        let yen = crate::YEN::new(3, 0, vec!['B', 'R'], "B/BR/.R.".to_string());
+        let body = PvbMoveRequest { yen, row: 2, col: 0 };
+
+        let response = app
+            .oneshot(
+                Request::post("/v1/game/pvb/random_bot")
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        serde_json::to_string(&body).unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let parsed: PvbMoveResponse = serde_json::from_slice(&bytes).unwrap();
+        assert!(parsed.finished);
+        assert_eq!(parsed.winner, Some('B'));
+        assert_eq!(parsed.winning_edges, vec![[[0, 0], [1, 0]], [[1, 0], [2, 0]]]);
+    }
+
+    /// Bot that takes the opening stone when it can and resigns otherwise.
+    struct ActionBot;
+
+    impl crate::YBot for ActionBot {
+        fn name(&self) -> &str {
+            "action_bot"
+        }
+
+        fn choose_move(&self, _board: &crate::GameY) -> Option<crate::Coordinates> {
+            None
+        }
+
+        fn decide(&self, board: &crate::GameY, _limits: &SearchLimits) -> Option<crate::BotDecision> {
+            let player = board.next_player()?;
+            let action = if board.can_swap() { GameAction::Swap } else { GameAction::Resign };
+            Some(crate::BotDecision {
+                movement: Movement::Action { player, action },
+                analysis: crate::SearchResult::from_move(None, std::time::Duration::ZERO),
+            })
+        }
+    }
+
+    async fn post_action_bot_move(yen: crate::YEN, row: usize, col: usize) -> PvbMoveResponse {
+        let registry = YBotRegistry::new_empty().with_bot(std::sync::Arc::new(ActionBot));
+        let app = create_router(AppState::new(registry));
+        let body = PvbMoveRequest { yen, row, col };
+
+        let response = app
+            .oneshot(
+                Request::post("/v1/game/pvb/action_bot")
+                    .header("content-type", "application/json")
+                    .body(Body::from(serde_json::to_string(&body).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_pvb_bot_swaps_opening_stone() {
+        let yen: crate::YEN = (&crate::GameY::new(3)).into();
+
+        let parsed = post_action_bot_move(yen, 1, 0).await;
+
+        assert_eq!(parsed.bot_action.as_deref(), Some("swap"));
+        assert!(!parsed.finished);
+        // The opening stone becomes the bot's and the human moves again
+        assert_eq!(parsed.yen.layout(), "./R./...");
+        assert_eq!(parsed.yen.turn(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_pvb_bot_resigns() {
+        let yen = crate::YEN::new(3, 0, vec!['B', 'R'], "./BR/...".to_string());
+
+        let parsed = post_action_bot_move(yen, 2, 0).await;
+
+        assert_eq!(parsed.bot_action.as_deref(), Some("resign"));
+        assert!(parsed.finished);
+        assert_eq!(parsed.winner, Some('B'));
+        assert!(parsed.winning_edges.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pvb_placement_has_no_bot_action() {
+        let registry = YBotRegistry::new().with_bot(std::sync::Arc::new(RandomBot::new(None)));
+        let app = create_router(AppState::new(registry));
+        let yen: crate::YEN = (&crate::GameY::new(4)).into();
+        let body = PvbMoveRequest { yen, row: 1, col: 0 };
+
+        let response = app
+            .oneshot(
+                Request::post("/v1/game/pvb/random_bot")
+                    .header("content-type", "application/json")
+                    .body(Body::from(serde_json::to_string(&body).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert!(!String::from_utf8_lossy(&bytes).contains("bot_action"));
+    }
+    #[tokio::test]
+    async fn test_pvb_human_can_play_second() {
+        let registry = YBotRegistry::new().with_bot(std::sync::Arc::new(RandomBot::new(None)));
+        let app = create_router(AppState::new(registry));
+        // The bot opened with B: the human plays R
+        let yen = crate::YEN::new(4, 1, vec!['B', 'R'], "B/../.../....".to_string());
+        let body = PvbMoveRequest { yen, row: 3, col: 3 };
+
+        let response = app
+            .oneshot(
+                Request::post("/v1/game/pvb/random_bot")
+                    .header("content-type", "application/json")
+                    .body(Body::from(serde_json::to_string(&body).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let parsed: PvbMoveResponse = serde_json::from_slice(&bytes).unwrap();
+        assert!(parsed.yen.layout().ends_with('R'));
+        assert_eq!(parsed.yen.turn(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_pvb_replays_seed() {
+        let registry = YBotRegistry::new().with_bot(std::sync::Arc::new(RandomBot::new(None)));
+        let app = create_router(AppState::new(registry));
+        let yen: crate::YEN = (&crate::GameY::new(5)).into();
+
+        let mut layouts = Vec::new();
+        for _ in 0..2 {
+            let body = PvbMoveRequest { yen: yen.clone(), row: 2, col: 1 };
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::post("/v1/game/pvb/random_bot?seed=9")
+                        .header("content-type", "application/json")
+                        .body(Body::from(serde_json::to_string(&body).unwrap()))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let parsed: PvbMoveResponse = serde_json::from_slice(&bytes).unwrap();
+            assert_eq!(parsed.seed, Some(9));
+            layouts.push(parsed.yen.layout().to_string());
+        }
+
+        assert_eq!(layouts[0], layouts[1]);
+    }
+}
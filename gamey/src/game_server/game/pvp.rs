@@ -1,9 +1,11 @@
 use axum::{extract::Path, http::StatusCode, Json};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashSet, VecDeque};
-
-use crate::game_server::{error::ErrorResponse, version::check_api_version};
-use crate::{Coordinates, GameY, Movement, YEN};
+use crate::game_server::{
+    error::ErrorResponse,
+    game::outcome::{game_outcome, row_col_to_coords, Edge},
+    version::check_api_version,
+};
+use crate::{GameY, Movement, YEN};
 
 #[derive(Deserialize)]
 pub struct PvpParams {
@@ -22,12 +24,10 @@ pub struct PvpMoveResponse {
     pub yen: YEN,
     pub finished: bool,
     pub winner: Option<char>,
-    pub winning_edges: Vec<[[usize; 2]; 2]>,
+    pub winning_edges: Vec<Edge>,
 }
 
 type HandlerError = (StatusCode, Json<ErrorResponse>);
-type Cell = (usize, usize);
-type Edge = [[usize; 2]; 2];
 
 fn bad_request(message: &str, api_version: &str) -> HandlerError {
     (
@@ -36,205 +36,6 @@ fn bad_request(message: &str, api_version: &str) -> HandlerError {
     )
 }
 
-fn row_col_to_coords(layout: &str, size: u32, row: usize, col: usize) -> Result<Coordinates, String> {
-    let rows: Vec<&str> = layout.split('/').collect();
-
-    let selected_row = rows
-        .get(row)
-        .ok_or_else(|| format!("row out of bounds: {} (rows={})", row, rows.len()))?;
-
-    let row_len = selected_row.chars().count();
-    if col >= row_len {
-        return Err(format!("col out of bounds: {} (row_len={})", col, row_len));
-    }
-
-    let index = rows
-        .iter()
-        .take(row)
-        .map(|row_str| row_str.chars().count())
-        .sum::<usize>()
-        + col;
-
-    let total_cells = rows.iter().map(|row_str| row_str.chars().count()).sum::<usize>();
-    if index >= total_cells {
-        return Err("Invalid coordinate conversion".to_string());
-    }
-
-    Ok(Coordinates::from_index(index as u32, size))
-}
-
-fn parse_layout(layout: &str) -> Vec<Vec<char>> {
-    if layout.is_empty() {
-        return vec![];
-    }
-
-    layout.split('/').map(|row| row.chars().collect()).collect()
-}
-
-fn is_cell_in_bounds(layout: &[Vec<char>], row: isize, col: isize) -> bool {
-    if row < 0 || row >= layout.len() as isize {
-        return false;
-    }
-
-    let row_len = layout[row as usize].len() as isize;
-    col >= 0 && col < row_len
-}
-
-fn neighbors(layout: &[Vec<char>], row: isize, col: isize) -> Vec<Cell> {
-    [
-        (row, col - 1),
-        (row, col + 1),
-        (row - 1, col - 1),
-        (row - 1, col),
-        (row + 1, col),
-        (row + 1, col + 1),
-    ]
-    .into_iter()
-    .filter(|(candidate_row, candidate_col)| {
-        is_cell_in_bounds(layout, *candidate_row, *candidate_col)
-    })
-    .map(|(candidate_row, candidate_col)| (candidate_row as usize, candidate_col as usize))
-    .collect()
-}
-
-fn touches_all_winning_edges(layout: &[Vec<char>], row: usize, col: usize) -> (bool, bool, bool) {
-    (
-        col == 0,
-        col == layout[row].len().saturating_sub(1),
-        row == layout.len().saturating_sub(1),
-    )
-}
-
-fn merge_touched_edges(
-    current: (bool, bool, bool),
-    next: (bool, bool, bool),
-) -> (bool, bool, bool) {
-    (current.0 || next.0, current.1 || next.1, current.2 || next.2)
-}
-
-fn has_won(touched_edges: (bool, bool, bool)) -> bool {
-    touched_edges.0 && touched_edges.1 && touched_edges.2
-}
-
-fn explore_component(
-    layout: &[Vec<char>],
-    token: char,
-    start: Cell,
-    visited: &mut HashSet<Cell>,
-) -> Option<HashSet<Cell>> {
-    let mut queue = VecDeque::from([start]);
-    let mut component = HashSet::from([start]);
-    let mut touched_edges = (false, false, false);
-
-    visited.insert(start);
-
-    while let Some((row, col)) = queue.pop_front() {
-        touched_edges = merge_touched_edges(
-            touched_edges,
-            touches_all_winning_edges(layout, row, col),
-        );
-
-        if has_won(touched_edges) {
-            return Some(component);
-        }
-
-        for neighbor in neighbors(layout, row as isize, col as isize) {
-            let (next_row, next_col) = neighbor;
-
-            if layout[next_row][next_col] != token || visited.contains(&neighbor) {
-                continue;
-            }
-
-            visited.insert(neighbor);
-            component.insert(neighbor);
-            queue.push_back(neighbor);
-        }
-    }
-
-    None
-}
-
-fn compute_winner_component(layout: &[Vec<char>], token: char) -> Option<HashSet<Cell>> {
-    let mut visited = HashSet::new();
-
-    for row in 0..layout.len() {
-        for col in 0..layout[row].len() {
-            let cell = (row, col);
-
-            if layout[row][col] != token || visited.contains(&cell) {
-                continue;
-            }
-
-            if let Some(component) = explore_component(layout, token, cell, &mut visited) {
-                return Some(component);
-            }
-        }
-    }
-
-    None
-}
-
-fn ordered_pair(a: Cell, b: Cell) -> (Cell, Cell) {
-    if a <= b {
-        (a, b)
-    } else {
-        (b, a)
-    }
-}
-
-fn build_edges(layout: &[Vec<char>], component: &HashSet<Cell>) -> Vec<Edge> {
-    let mut edges = vec![];
-    let mut seen = HashSet::new();
-
-    for &(row, col) in component {
-        for neighbor in neighbors(layout, row as isize, col as isize) {
-            if !component.contains(&neighbor) {
-                continue;
-            }
-
-            let pair = ordered_pair((row, col), neighbor);
-            if !seen.insert(pair) {
-                continue;
-            }
-
-            edges.push([[pair.0 .0, pair.0 .1], [pair.1 .0, pair.1 .1]]);
-        }
-    }
-
-    edges
-}
-
-fn result_for_token(layout: &[Vec<char>], token: char) -> Option<(bool, Option<char>, Vec<Edge>)> {
-    compute_winner_component(layout, token)
-        .map(|component| (true, Some(token), build_edges(layout, &component)))
-}
-
-fn compute_result_from_yen(yen: &YEN) -> (bool, Option<char>, Vec<Edge>) {
-    let layout = parse_layout(yen.layout());
-    if layout.is_empty() {
-        return (false, None, vec![]);
-    }
-
-    let players = yen.players();
-    let player_tokens = [
-        players.first().copied().unwrap_or('B'),
-        players.get(1).copied().unwrap_or('R'),
-    ];
-
-    for token in player_tokens {
-        if let Some(result) = result_for_token(&layout, token) {
-            return result;
-        }
-    }
-
-    let any_empty = layout.iter().flatten().any(|&cell| cell == '.');
-    if any_empty {
-        (false, None, vec![])
-    } else {
-        (true, None, vec![])
-    }
-}
-
 #[axum::debug_handler]
 pub async fn pvp_move(
     Path(params): Path<PvpParams>,
@@ -246,7 +47,6 @@ pub async fn pvp_move(
         return Err((StatusCode::BAD_REQUEST, Json(err)));
     }
 
-    let layout = req.yen.layout().to_string();
     let size = req.yen.size();
 
     let mut game = GameY::try_from(req.yen)
@@ -256,7 +56,7 @@ pub async fn pvp_move(
         return Err(bad_request("Game is already over", version));
     }
 
-    let coords = row_col_to_coords(&layout, size, req.row, req.col)
+    let coords = row_col_to_coords(size, req.row, req.col)
         .map_err(|msg| bad_request(&format!("Invalid coordinates: {}", msg), version))?;
 
     let current_player = game
@@ -270,13 +70,13 @@ pub async fn pvp_move(
     .map_err(|err| bad_request(&format!("Invalid move: {}", err), version))?;
 
     let new_yen: YEN = (&game).into();
-    let (finished, winner, winning_edges) = compute_result_from_yen(&new_yen);
+    let outcome = game_outcome(&game, new_yen.players());
 
     Ok(Json(PvpMoveResponse {
         yen: new_yen,
-        finished,
-        winner,
-        winning_edges,
+        finished: outcome.finished,
+        winner: outcome.winner,
+        winning_edges: outcome.winning_edges,
     }))
 }
 
@@ -385,28 +185,44 @@ mod tests {
         expect_bad_request((&game).into(), 0, 0, API_VERSION).await;
     }
 
-    #[test]
-    fn test_parse_layout_empty() {
-        assert!(parse_layout("").is_empty());
-    }
+    #[tokio::test]
+    async fn test_pvp_winning_move_returns_chain() {
+        let yen = crate::YEN::new(3, 0, vec!['B', 'R'], "B/BR/.R.".to_string());
+        let response = send_pvp(PvpMoveRequest { yen, row: 2, col: 0 }, API_VERSION).await;
+        assert_eq!(response.status(), StatusCode::OK);
 
-    #[test]
-    fn test_compute_result_from_yen_ongoing_game() {
-        let yen = crate::YEN::new(3, 0, vec!['B', 'R'], "./../...".to_string());
-        let (finished, winner, winning_edges) = compute_result_from_yen(&yen);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let parsed: PvpMoveResponse = serde_json::from_slice(&bytes).unwrap();
 
-        assert!(!finished);
-        assert_eq!(winner, None);
-        assert!(winning_edges.is_empty());
+        assert!(parsed.finished);
+        assert_eq!(parsed.winner, Some('B'));
+        assert_eq!(
+            parsed.winning_edges,
+            vec![[[0, 0], [1, 0]], [[1, 0], [2, 0]]]
+        );
     }
 
-    #[test]
-    fn test_compute_result_from_yen_finished_with_winner() {
-        let yen = crate::YEN::new(3, 1, vec!['B', 'R'], "B/BB/BBR".to_string());
-        let (finished, winner, winning_edges) = compute_result_from_yen(&yen);
+    #[tokio::test]
+    async fn test_pvp_ongoing_game_is_not_finished() {
+        let response = send_pvp(
+            PvpMoveRequest {
+                yen: new_yen(3),
+                row: 1,
+                col: 1,
+            },
+            API_VERSION,
+        )
+        .await;
 
-        assert!(finished);
-        assert_eq!(winner, Some('B'));
-        assert!(!winning_edges.is_empty());
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let parsed: PvpMoveResponse = serde_json::from_slice(&bytes).unwrap();
+
+        assert!(!parsed.finished);
+        assert_eq!(parsed.winner, None);
+        assert!(parsed.winning_edges.is_empty());
     }
-}
\ No newline at end of file
+}
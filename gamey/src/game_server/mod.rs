@@ -23,19 +23,20 @@ pub mod game {
     pub mod pvb;
     pub mod pvp;
     pub mod check;
+    pub mod outcome;
 }
 
 use axum::response::IntoResponse;
 use axum_prometheus::metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
 use std::sync::{Arc, OnceLock};
 
-pub use bot::choose::MoveResponse;
+pub use bot::choose::{MoveAnalysis, MoveResponse, SeedParams, MAX_SEARCH_LIMITS, REPLAY_NODES};
 pub use error::ErrorResponse;
 pub use version::*;
 
 use crate::{
     game_server::state::AppState, AlfaBetaBot, GameYError, HeuristicBot, MinimaxBot,
-    MonteCarloBot, RandomBot, YBotRegistry,
+    MonteCarloBot, OpeningBook, RandomBot, YBotRegistry,
 };
 
 use crate::bot_implementations::MonteCarloDifficulty;
@@ -85,20 +86,43 @@ pub fn create_router(state: AppState) -> axum::Router {
 }
 
 pub fn create_default_state() -> AppState {
-    let bots = YBotRegistry::new()
+    AppState::new(default_bots(None))
+}
+
+/// The bots the server offers. The Monte Carlo bots search on
+/// `search_threads` threads each, or on their default number when `None`.
+fn default_bots(search_threads: Option<usize>) -> YBotRegistry {
+    let monte_carlo = |difficulty| {
+        let bot = MonteCarloBot::new(difficulty);
+        Arc::new(match search_threads {
+            Some(threads) => bot.with_threads(threads),
+            None => bot,
+        })
+    };
+    YBotRegistry::new()
         .with_bot(Arc::new(HeuristicBot))
-        .with_bot(Arc::new(RandomBot))
+        .with_bot(Arc::new(RandomBot::new(None)))
         .with_bot(Arc::new(AlfaBetaBot::new(None)))
         .with_bot(Arc::new(MinimaxBot::new(None)))
-        .with_bot(Arc::new(MonteCarloBot::new(MonteCarloDifficulty::Hard)))
-        .with_bot(Arc::new(MonteCarloBot::new(MonteCarloDifficulty::Extreme)));
-
-    AppState::new(bots)
+        .with_bot(monte_carlo(MonteCarloDifficulty::Hard))
+        .with_bot(monte_carlo(MonteCarloDifficulty::Extreme))
+        .with_bot(monte_carlo(MonteCarloDifficulty::Rave))
 }
 
-pub async fn run_bot_server(port: u16) -> Result<(), GameYError> {
-    let state = create_default_state();
-    let app = create_router(state);
+/// Runs the bot server on `port`. With an opening `book`, every bot plays
+/// its moves while the game is in the book. `search_threads` sets the
+/// threads of each Monte Carlo search, which otherwise use one per core,
+/// up to four.
+pub async fn run_bot_server(
+    port: u16,
+    book: Option<OpeningBook>,
+    search_threads: Option<usize>,
+) -> Result<(), GameYError> {
+    let mut bots = default_bots(search_threads);
+    if let Some(book) = book {
+        bots = bots.with_opening_book(Arc::new(book));
+    }
+    let app = create_router(AppState::new(bots));
 
     let addr = format!("0.0.0.0:{}", port);
     let listener = tokio::net::TcpListener::bind(&addr)
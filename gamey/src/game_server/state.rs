@@ -29,14 +29,13 @@ impl AppState {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{RandomBot, HeuristicBot, MinimaxBot, AlfaBetaBot, MonteCarloBot};
-    use crate::bot_implementations::MonteCarloDifficulty;
+    use crate::{RandomBot, HeuristicBot, MinimaxBot};
     
     #[test]
     fn test_state_with_bot() {
         // Creamos un registro vacío y añadimos solo RandomBot
         let registry = YBotRegistry::new_empty()
-            .with_bot(Arc::new(RandomBot));
+            .with_bot(Arc::new(RandomBot::new(None)));
         let state = AppState::new(registry);
 
         // Verificamos que tiene al menos un bot
@@ -48,7 +47,7 @@ mod tests {
     fn test_state_with_multiple_bots() {
         // Creamos un registro con varios bots específicos
         let registry = YBotRegistry::new_empty()
-            .with_bot(Arc::new(RandomBot))
+            .with_bot(Arc::new(RandomBot::new(None)))
             .with_bot(Arc::new(HeuristicBot))
             .with_bot(Arc::new(MinimaxBot::new(None)));
 
@@ -59,6 +58,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::len_zero)]
     fn test_state_with_all_bots() {
         // Verificamos que el registro por defecto tiene bots
         let registry = YBotRegistry::new();
@@ -72,7 +72,7 @@ mod tests {
     #[test]
     fn test_state_clone() {
         let registry = YBotRegistry::new_empty()
-            .with_bot(Arc::new(RandomBot));
+            .with_bot(Arc::new(RandomBot::new(None)));
         let state = AppState::new(registry);
         let cloned = state.clone();
 
@@ -83,7 +83,7 @@ mod tests {
     #[test]
     fn test_bots_arc_clone() {
         let registry = YBotRegistry::new_empty()
-            .with_bot(Arc::new(RandomBot));
+            .with_bot(Arc::new(RandomBot::new(None)));
         let state = AppState::new(registry);
         let bots1 = state.bots();
         let bots2 = state.bots();
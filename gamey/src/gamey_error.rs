@@ -43,7 +43,8 @@ pub enum GameYError {
     /// A coordinate value is outside the valid range for the board.
     #[error("Coordinate {id_coord}={coord} is out of range for board size {board_size}")]
     CoordOutOfRange {
-        /// Which coordinate is out of range ('x', 'y', or 'z').
+        /// Which coordinate is out of range ('x', 'y', or 'z'), or
+        /// 'i', 'r', 'c' for a cell index, row or column.
         id_coord: char,
         /// The invalid coordinate value.
         coord: u32,
@@ -51,6 +52,15 @@ pub enum GameYError {
         board_size: u32,
     },
 
+    /// The coordinates do not add up to `board_size - 1`.
+    #[error("Coordinates {coordinates} must add up to {expected}")]
+    InvalidCoordsSum {
+        /// The invalid coordinates.
+        coordinates: Coordinates,
+        /// The expected sum (board size minus one).
+        expected: u32,
+    },
+
     /// Attempted to place a piece on an already occupied cell.
     #[error("Player {player} tries to place a stone on an occupied position: {coordinates}")]
     Occupied {
@@ -87,6 +97,15 @@ pub enum GameYError {
         found: PlayerId,
     },
 
+    /// A swap was requested when the swap rule does not allow it.
+    #[error("Player {player} cannot swap: {reason}")]
+    InvalidSwap {
+        /// The player who attempted the swap.
+        player: PlayerId,
+        /// Why the swap is not allowed.
+        reason: String,
+    },
+
     /// Invalid number of players specified.
     #[error("Invalid number of players: {num_players}, expected {expected}")]
     InvalidNumPlayers {
@@ -122,6 +141,20 @@ pub enum GameYError {
         /// Description of what went wrong.
         message: String,
     },
+
+    /// A solved-position table could not be built or read.
+    #[error("Solved table error: {message}")]
+    SolvedTableError {
+        /// Description of what went wrong.
+        message: String,
+    },
+
+    /// A bot arena could not be set up.
+    #[error("Arena error: {message}")]
+    ArenaError {
+        /// Description of what went wrong.
+        message: String,
+    },
 }
 
 #[cfg(test)]
@@ -163,6 +196,17 @@ mod tests {
         assert!(msg.contains("board size 5"));
     }
 
+    #[test]
+    fn test_invalid_coords_sum_display() {
+        let err = GameYError::InvalidCoordsSum {
+            coordinates: Coordinates::new(1, 1, 1),
+            expected: 4,
+        };
+        let msg = format!("{}", err);
+        assert!(msg.contains("(1, 1, 1)"));
+        assert!(msg.contains("add up to 4"));
+    }
+
     #[test]
     fn test_occupied_display() {
         let err = GameYError::Occupied {
@@ -243,6 +287,37 @@ mod tests {
         assert!(msg.contains("Failed to bind to port 3000"));
     }
 
+    #[test]
+    fn test_solved_table_error_display() {
+        let err = GameYError::SolvedTableError {
+            message: "bad magic number".to_string(),
+        };
+        let msg = format!("{}", err);
+        assert!(msg.contains("Solved table error"));
+        assert!(msg.contains("bad magic number"));
+    }
+
+    #[test]
+    fn test_arena_error_display() {
+        let err = GameYError::ArenaError {
+            message: "unknown bot: foo".to_string(),
+        };
+        let msg = format!("{}", err);
+        assert!(msg.contains("Arena error"));
+        assert!(msg.contains("unknown bot: foo"));
+    }
+
+    #[test]
+    fn test_invalid_swap_display() {
+        let err = GameYError::InvalidSwap {
+            player: PlayerId::new(1),
+            reason: "the swap rule is disabled".to_string(),
+        };
+        let msg = format!("{}", err);
+        assert!(msg.contains("Player 1 cannot swap"));
+        assert!(msg.contains("the swap rule is disabled"));
+    }
+
     #[test]
     fn test_error_is_debug() {
         let err = GameYError::IoError {
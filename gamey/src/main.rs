@@ -1,10 +1,14 @@
 //! GameY binary entry point.
 //!
-//! This is the main executable for the GameY application. It supports three modes:
+//! This is the main executable for the GameY application. It supports five modes:
 //!
 //! - **Human mode** (default): Two players take turns at the terminal
 //! - **Computer mode**: Play against a bot
 //! - **Server mode**: Run as an HTTP server exposing the bot API
+//! - **Book mode**: Build an opening book from bot self-play
+//! - **Solve mode**: Build a table of solved positions for small boards
+//!
+//! The `arena` subcommand plays matches between bots and reports their strength.
 //!
 //! # Usage
 //!
@@ -17,10 +21,23 @@
 //!
 //! # Start the bot server on port 3000
 //! gamey --mode server --port 3000
+//!
+//! # Add 50 self-play games to an opening book, and serve the bots with it
+//! gamey --mode book --bot monte_carlo_hard --nodes 5000 --games 50 --book book.json
+//! gamey --mode server --book book.json
+//!
+//! # Solve every board up to size 5
+//! gamey --mode solve --size 5 --table solved.bin
+//!
+//! # Play 20 games per board size between two bots, 100 ms per move
+//! gamey arena --bots alfa_beta_bot,monte_carlo_hard --sizes 5,7 --games 20 --time-ms 100 --records games.jsonl
 //! ```
 
 use clap::Parser;
-use gamey::{self, CliArgs, Mode, run_cli_game};
+use gamey::{
+    self, CliArgs, Mode, OpeningBook, Tool, run_arena_tool, run_book_builder, run_cli_game,
+    run_table_solver,
+};
 use gamey::game_server::run_bot_server;
 use tracing_subscriber::prelude::*;
 
@@ -33,8 +50,30 @@ async fn main() {
     tracing_subscriber::registry().init();
     let args = CliArgs::parse();
 
-    if args.mode == Mode::Server {
-        if let Err(e) = run_bot_server(args.port).await {
+    if let Some(Tool::Arena(arena)) = &args.tool {
+        if let Err(e) = run_arena_tool(&args, arena) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    } else if args.mode == Mode::Server {
+        let book = match args.book.as_ref().map(OpeningBook::load_from_file).transpose() {
+            Ok(book) => book,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        };
+        if let Err(e) = run_bot_server(args.port, book, args.search_threads).await {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    } else if args.mode == Mode::Book {
+        if let Err(e) = run_book_builder(&args) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    } else if args.mode == Mode::Solve {
+        if let Err(e) = run_table_solver(&args) {
             eprintln!("Error: {}", e);
             std::process::exit(1);
         }
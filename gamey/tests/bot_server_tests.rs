@@ -2,8 +2,10 @@ use axum::{
     body::Body,
     http::{Request, StatusCode},
 };
-use gamey::{YBotRegistry, YEN, RandomBot};
-use gamey::game_server::{create_default_state, create_router, state::AppState, MoveResponse, ErrorResponse};
+use gamey::{Coordinates, GameY, RandomBot, SearchLimits, SearchResult, YBot, YBotRegistry, YEN};
+use gamey::game_server::{
+    create_default_state, create_router, state::AppState, ErrorResponse, MoveResponse, MAX_SEARCH_LIMITS, REPLAY_NODES,
+};
 use http_body_util::BodyExt;
 use std::sync::Arc;
 use tower::ServiceExt;
@@ -104,6 +106,146 @@ async fn test_choose_endpoint_with_partially_filled_board() {
     assert_eq!(move_response.bot_id, "random_bot");
 }
 
+#[tokio::test]
+async fn test_choose_endpoint_with_search_limits() {
+    let app = test_app();
+
+    let yen = YEN::new(4, 0, vec!['B', 'R'], "./../.../....".to_string());
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/ybot/choose/alfa_beta_bot?max_depth=2&max_time_ms=1000")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_string(&yen).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let move_response: MoveResponse = serde_json::from_slice(&body).unwrap();
+    let analysis = move_response.analysis.expect("analysis should be reported");
+
+    assert_eq!(analysis.depth, Some(2));
+    assert!(analysis.score.is_some());
+    assert!(analysis.nodes > 0);
+    assert_eq!(analysis.pv.first(), Some(&move_response.coords));
+}
+
+#[tokio::test]
+async fn test_choose_endpoint_replays_seed() {
+    let yen = YEN::new(4, 0, vec!['B', 'R'], "./../.../....".to_string());
+
+    let mut replies = Vec::new();
+    for _ in 0..2 {
+        let response = test_app()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/v1/ybot/choose/random_bot?seed=42")
+                    .header("content-type", "application/json")
+                    .body(Body::from(serde_json::to_string(&yen).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let move_response: MoveResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(move_response.seed, Some(42));
+        replies.push(move_response.coords);
+    }
+
+    assert_eq!(replies[0], replies[1]);
+}
+
+/// Bot that plays the first free cell, while its reseeded copies play the last one.
+struct ReseedMarkerBot {
+    copy: bool,
+}
+
+impl YBot for ReseedMarkerBot {
+    fn name(&self) -> &str {
+        "reseed_marker"
+    }
+
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        let cells = board.available_cells();
+        let idx = if self.copy { cells.last()? } else { cells.first()? };
+        Some(Coordinates::from_index(*idx, board.board_size()))
+    }
+
+    fn reseeded(&self, _seed: u64) -> Option<Arc<dyn YBot>> {
+        Some(Arc::new(ReseedMarkerBot { copy: true }))
+    }
+}
+
+async fn choose_marker(uri: &str) -> MoveResponse {
+    let bots = YBotRegistry::new_empty().with_bot(Arc::new(ReseedMarkerBot { copy: false }));
+    let yen = YEN::new(3, 0, vec!['B', 'R'], "./../...".to_string());
+
+    let response = test_app_with_state(AppState::new(bots))
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_string(&yen).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn test_choose_endpoint_without_seed_uses_shared_bot() {
+    let move_response = choose_marker("/v1/ybot/choose/reseed_marker").await;
+
+    assert_eq!(move_response.coords, Coordinates::from_index(0, 3));
+    assert_eq!(move_response.seed, None);
+}
+
+#[tokio::test]
+async fn test_choose_endpoint_with_seed_uses_fresh_copy() {
+    let move_response = choose_marker("/v1/ybot/choose/reseed_marker?seed=7").await;
+
+    assert_eq!(move_response.coords, Coordinates::from_index(5, 3));
+    assert_eq!(move_response.seed, Some(7));
+}
+
+#[tokio::test]
+async fn test_choose_endpoint_replays_monte_carlo_seed() {
+    let yen = YEN::new(5, 0, vec!['B', 'R'], "./../.../..../.....".to_string());
+
+    let mut replies = Vec::new();
+    for _ in 0..2 {
+        let response = test_app()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/v1/ybot/choose/monte_carlo_hard?seed=5")
+                    .header("content-type", "application/json")
+                    .body(Body::from(serde_json::to_string(&yen).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let move_response: MoveResponse = serde_json::from_slice(&body).unwrap();
+        let analysis = move_response.analysis.expect("analysis should be reported");
+        assert_eq!(analysis.nodes, REPLAY_NODES);
+        replies.push((move_response.coords, analysis.pv, analysis.win_probability));
+    }
+
+    assert_eq!(replies[0], replies[1]);
+}
+
 // ============================================================================
 // Choose endpoint tests - Error cases
 // ============================================================================
@@ -212,7 +354,7 @@ async fn test_choose_endpoint_with_missing_content_type() {
 #[tokio::test]
 async fn test_choose_with_custom_bot_registry() {
     // Create a custom registry with only the random bot
-    let bots = YBotRegistry::new().with_bot(Arc::new(RandomBot));
+    let bots = YBotRegistry::new().with_bot(Arc::new(RandomBot::new(None)));
     let state = AppState::new(bots);
     let app = test_app_with_state(state);
 
@@ -233,6 +375,62 @@ async fn test_choose_with_custom_bot_registry() {
     assert_eq!(response.status(), StatusCode::OK);
 }
 
+/// Bot that reports the limits it was given as its depth and node count.
+struct LimitsEchoBot;
+
+impl YBot for LimitsEchoBot {
+    fn name(&self) -> &str {
+        "limits_echo"
+    }
+
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        let idx = *board.available_cells().first()?;
+        Some(Coordinates::from_index(idx, board.board_size()))
+    }
+
+    fn search(&self, board: &GameY, limits: &SearchLimits) -> SearchResult {
+        SearchResult {
+            best_move: self.choose_move(board),
+            score: None,
+            win_probability: None,
+            principal_variation: Vec::new(),
+            depth: limits.max_depth,
+            nodes: limits.max_nodes.unwrap_or(0),
+            elapsed_ms: limits.max_time_ms.unwrap_or(0),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_choose_clamps_search_limits() {
+    let bots = YBotRegistry::new_empty().with_bot(Arc::new(LimitsEchoBot));
+    let app = test_app_with_state(AppState::new(bots));
+
+    let yen = YEN::new(3, 0, vec!['B', 'R'], "./../...".to_string());
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/ybot/choose/limits_echo?max_depth=4000000000&max_nodes=18000000000000000000&max_time_ms=86400000")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_string(&yen).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let move_response: MoveResponse = serde_json::from_slice(&body).unwrap();
+    let analysis = move_response.analysis.expect("analysis should be reported");
+
+    assert_eq!(analysis.depth, MAX_SEARCH_LIMITS.max_depth);
+    assert_eq!(Some(analysis.nodes), MAX_SEARCH_LIMITS.max_nodes);
+    assert_eq!(Some(analysis.elapsed_ms), MAX_SEARCH_LIMITS.max_time_ms);
+}
+
 #[tokio::test]
 async fn test_choose_with_empty_bot_registry() {
     // Create an empty registry
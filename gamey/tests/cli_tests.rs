@@ -263,6 +263,8 @@ fn test_mode_equality() {
     assert_eq!(Mode::Server, Mode::Server);
     assert_ne!(Mode::Computer, Mode::Human);
     assert_ne!(Mode::Human, Mode::Server);
+    assert_ne!(Mode::Server, Mode::Book);
+    assert_ne!(Mode::Book, Mode::Solve);
 }
 
 // =============================================================================
@@ -311,6 +313,156 @@ fn test_cli_args_mode_server() {
     assert_eq!(args.mode, Mode::Server);
 }
 
+#[test]
+fn test_cli_args_mode_book() {
+    let args = CliArgs::try_parse_from([
+        "gamey", "--mode", "book", "--book", "book.json", "--games", "5", "--plies", "3",
+    ])
+    .unwrap();
+    assert_eq!(args.mode, Mode::Book);
+    assert_eq!(args.book, Some(std::path::PathBuf::from("book.json")));
+    assert_eq!(args.games, 5);
+    assert_eq!(args.plies, 3);
+    assert_eq!(format!("{}", args.mode), "book");
+
+    let defaults = CliArgs::try_parse_from(["gamey"]).unwrap();
+    assert_eq!(defaults.book, None);
+    assert_eq!(defaults.games, 20);
+    assert_eq!(defaults.plies, 6);
+}
+
+#[test]
+fn test_run_book_builder_writes_a_book() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("book.json");
+    let path_arg = path.to_str().unwrap();
+    let args = CliArgs::try_parse_from([
+        "gamey", "--mode", "book", "--bot", "random_bot", "--size", "4", "--games", "3",
+        "--seed", "1", "--book", path_arg,
+    ])
+    .unwrap();
+
+    gamey::run_book_builder(&args).unwrap();
+    let book = gamey::OpeningBook::load_from_file(&path).unwrap();
+    let total: u32 = book.lookup(&gamey::GameY::new(4)).iter().map(|m| m.games).sum();
+    assert_eq!(total, 3);
+
+    // A second batch extends the existing book
+    gamey::run_book_builder(&args).unwrap();
+    let book = gamey::OpeningBook::load_from_file(&path).unwrap();
+    let total: u32 = book.lookup(&gamey::GameY::new(4)).iter().map(|m| m.games).sum();
+    assert_eq!(total, 6);
+}
+
+#[test]
+fn test_run_book_builder_needs_a_file() {
+    let args = CliArgs::try_parse_from(["gamey", "--mode", "book"]).unwrap();
+    assert!(gamey::run_book_builder(&args).is_err());
+}
+
+#[test]
+fn test_cli_args_mode_solve() {
+    let args = CliArgs::try_parse_from([
+        "gamey", "--mode", "solve", "--size", "4", "--table", "solved.bin",
+    ])
+    .unwrap();
+    assert_eq!(args.mode, Mode::Solve);
+    assert_eq!(args.table, Some(std::path::PathBuf::from("solved.bin")));
+    assert_eq!(format!("{}", args.mode), "solve");
+    assert_eq!(CliArgs::try_parse_from(["gamey"]).unwrap().table, None);
+}
+
+#[test]
+fn test_run_table_solver_writes_a_table() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("solved.bin");
+    let path_arg = path.to_str().unwrap();
+    let args = CliArgs::try_parse_from([
+        "gamey", "--mode", "solve", "--size", "3", "--table", path_arg,
+    ])
+    .unwrap();
+
+    gamey::run_table_solver(&args).unwrap();
+    let table = gamey::SolvedTable::load_from_file(&path).unwrap();
+    assert_eq!(table.sizes(), vec![1, 2, 3]);
+    let solved = gamey::GameY::new(3).perfect_play(&table).unwrap();
+    assert_eq!(solved.outcome, gamey::SolveOutcome::Win);
+}
+
+#[test]
+fn test_run_table_solver_needs_a_file() {
+    let args = CliArgs::try_parse_from(["gamey", "--mode", "solve", "--size", "2"]).unwrap();
+    assert!(gamey::run_table_solver(&args).is_err());
+}
+
+#[test]
+fn test_run_table_solver_rejects_large_boards() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("solved.bin");
+    let size = (gamey::MAX_TABLE_SIZE + 1).to_string();
+    let args = CliArgs::try_parse_from([
+        "gamey", "--mode", "solve", "--size", &size, "--table", path.to_str().unwrap(),
+    ])
+    .unwrap();
+
+    assert!(gamey::run_table_solver(&args).is_err());
+    assert!(!path.exists());
+}
+
+#[test]
+fn test_cli_args_arena() {
+    let args = CliArgs::try_parse_from([
+        "gamey", "arena", "--bots", "random_bot,heuristic_bot", "--sizes", "4,5", "--games", "6",
+        "--time-ms", "50", "--seed", "3",
+    ])
+    .unwrap();
+    let Some(gamey::Tool::Arena(arena)) = &args.tool else {
+        panic!("expected the arena subcommand");
+    };
+    assert_eq!(arena.bots, vec!["random_bot", "heuristic_bot"]);
+    assert_eq!(arena.sizes, vec![4, 5]);
+    assert_eq!(arena.games, 6);
+    assert_eq!(arena.threads, None);
+    assert_eq!(arena.records, None);
+    assert_eq!(args.time_ms, Some(50));
+    assert_eq!(args.seed, Some(3));
+
+    assert_eq!(CliArgs::try_parse_from(["gamey"]).unwrap().tool, None);
+    assert!(CliArgs::try_parse_from(["gamey", "arena"]).is_err());
+}
+
+#[test]
+fn test_run_arena_tool_writes_records() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("games.jsonl");
+    let path_arg = path.to_str().unwrap();
+    let args = CliArgs::try_parse_from([
+        "gamey", "arena", "--bots", "random_bot,heuristic_bot", "--sizes", "3,4", "--games", "2",
+        "--threads", "2", "--records", path_arg,
+    ])
+    .unwrap();
+    let Some(gamey::Tool::Arena(arena)) = &args.tool else {
+        panic!("expected the arena subcommand");
+    };
+
+    gamey::run_arena_tool(&args, arena).unwrap();
+    let content = std::fs::read_to_string(&path).unwrap();
+    let records: Vec<gamey::GameRecord> =
+        content.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert_eq!(records.len(), 4);
+    assert_eq!(records[0].board_size, 3);
+    assert_eq!(records[3].board_size, 4);
+}
+
+#[test]
+fn test_run_arena_tool_rejects_unknown_bots() {
+    let args = CliArgs::try_parse_from(["gamey", "arena", "--bots", "random_bot,nobody"]).unwrap();
+    let Some(gamey::Tool::Arena(arena)) = &args.tool else {
+        panic!("expected the arena subcommand");
+    };
+    assert!(gamey::run_arena_tool(&args, arena).is_err());
+}
+
 #[test]
 fn test_cli_args_mode_short() {
     let args = CliArgs::try_parse_from(["gamey", "-m", "computer"]).unwrap();
@@ -335,6 +487,14 @@ fn test_cli_args_custom_port() {
     assert_eq!(args.port, 8080);
 }
 
+#[test]
+fn test_cli_args_search_threads() {
+    let args =
+        CliArgs::try_parse_from(["gamey", "--mode", "server", "--search-threads", "2"]).unwrap();
+    assert_eq!(args.search_threads, Some(2));
+    assert_eq!(CliArgs::try_parse_from(["gamey"]).unwrap().search_threads, None);
+}
+
 #[test]
 fn test_cli_args_custom_port_short() {
     let args = CliArgs::try_parse_from(["gamey", "-p", "9000"]).unwrap();
@@ -361,6 +521,39 @@ fn test_cli_args_combined_options() {
     assert_eq!(args.port, 5000);
 }
 
+#[test]
+fn test_cli_args_search_limits() {
+    let args = CliArgs::try_parse_from([
+        "gamey",
+        "--time-ms",
+        "500",
+        "--nodes",
+        "10000",
+        "--depth",
+        "3",
+    ])
+    .unwrap();
+    assert_eq!(
+        args.search_limits(),
+        gamey::SearchLimits::new()
+            .with_max_time_ms(500)
+            .with_max_nodes(10000)
+            .with_max_depth(3)
+    );
+
+    let defaults = CliArgs::try_parse_from(["gamey"]).unwrap();
+    assert_eq!(defaults.search_limits(), gamey::SearchLimits::default());
+}
+
+#[test]
+fn test_cli_args_seed() {
+    let args = CliArgs::try_parse_from(["gamey", "--seed", "1234"]).unwrap();
+    assert_eq!(args.seed, Some(1234));
+
+    let defaults = CliArgs::try_parse_from(["gamey"]).unwrap();
+    assert_eq!(defaults.seed, None);
+}
+
 #[test]
 fn test_cli_args_invalid_mode() {
     let result = CliArgs::try_parse_from(["gamey", "--mode", "invalid"]);
@@ -1,5 +1,6 @@
 use gamey::{
-    Coordinates, GameAction, GameStatus, GameY, GameYError, Movement, PlayerId, RenderOptions, YEN,
+    Coordinates, GameAction, GameStatus, GameY, GameYError, MoveValidation, Movement, PlayerId,
+    RenderOptions, SwapRule, YEN,
 };
 use std::fs;
 use tempfile::tempdir;
@@ -357,6 +358,68 @@ fn test_check_player_turn_correct_player() {
     assert!(game.check_player_turn(&movement).is_ok());
 }
 
+#[test]
+fn test_cannot_move_after_game_over() {
+    let mut game = GameY::new(1);
+    game.add_move(Movement::Placement {
+        player: PlayerId::new(0),
+        coords: Coordinates::new(0, 0, 0),
+    })
+    .unwrap();
+
+    let result = game.add_move(Movement::Action {
+        player: PlayerId::new(1),
+        action: GameAction::Resign,
+    });
+
+    assert!(matches!(result, Err(GameYError::GameOver { .. })));
+    match game.status() {
+        GameStatus::Finished { winner } => assert_eq!(*winner, PlayerId::new(0)),
+        _ => panic!("Game should still be finished"),
+    }
+}
+
+#[test]
+fn test_add_move_rejects_wrong_player() {
+    let mut game = GameY::new(5);
+
+    let result = game.add_move(Movement::Placement {
+        player: PlayerId::new(1),
+        coords: Coordinates::new(2, 1, 1),
+    });
+
+    match result {
+        Err(GameYError::InvalidPlayerTurn { expected, found }) => {
+            assert_eq!(expected, PlayerId::new(0));
+            assert_eq!(found, PlayerId::new(1));
+        }
+        other => panic!("Expected InvalidPlayerTurn error, got {:?}", other),
+    }
+    assert_eq!(game.available_cells().len(), 15);
+}
+
+#[test]
+fn test_permissive_mode_allows_free_placement() {
+    let mut game = GameY::new(2).with_validation(MoveValidation::Permissive);
+
+    for coords in [Coordinates::new(0, 0, 1), Coordinates::new(0, 1, 0)] {
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(1),
+            coords,
+        })
+        .unwrap();
+    }
+    assert!(game.check_game_over());
+
+    // Stones can still be placed on a finished board
+    game.add_move(Movement::Placement {
+        player: PlayerId::new(0),
+        coords: Coordinates::new(1, 0, 0),
+    })
+    .unwrap();
+    assert!(game.available_cells().is_empty());
+}
+
 // ============================================================================
 // Game Actions Tests (Resign, Swap)
 // ============================================================================
@@ -408,17 +471,18 @@ fn test_player_1_resign_makes_player_0_win() {
 }
 
 #[test]
-fn test_swap_changes_next_player() {
+fn test_swap_rejected_before_opening_move() {
     let mut game = GameY::new(5);
 
-    game.add_move(Movement::Action {
+    let result = game.add_move(Movement::Action {
         player: PlayerId::new(0),
         action: GameAction::Swap,
-    })
-    .unwrap();
+    });
 
+    assert!(matches!(result, Err(GameYError::InvalidSwap { .. })));
     assert!(!game.check_game_over());
-    assert_eq!(game.next_player(), Some(PlayerId::new(1)));
+    assert_eq!(game.next_player(), Some(PlayerId::new(0)));
+    assert!(game.history().is_empty());
 }
 
 #[test]
@@ -442,6 +506,71 @@ fn test_swap_after_opening_move() {
     // Now it's player 0's turn again
     assert_eq!(game.next_player(), Some(PlayerId::new(0)));
     assert!(!game.check_game_over());
+
+    // The opening stone now belongs to player 1
+    let yen: YEN = (&game).into();
+    assert_eq!(yen.layout(), "./../.R./..../.....");
+    assert_eq!(yen.turn(), 0);
+    assert_eq!(game.history().len(), 2);
+}
+
+#[test]
+fn test_swap_not_allowed_after_second_stone() {
+    let mut game = GameY::new(5);
+    game.add_move(Movement::Placement {
+        player: PlayerId::new(0),
+        coords: Coordinates::new(2, 1, 1),
+    })
+    .unwrap();
+    game.add_move(Movement::Placement {
+        player: PlayerId::new(1),
+        coords: Coordinates::new(4, 0, 0),
+    })
+    .unwrap();
+
+    let result = game.add_move(Movement::Action {
+        player: PlayerId::new(0),
+        action: GameAction::Swap,
+    });
+
+    assert!(matches!(result, Err(GameYError::InvalidSwap { .. })));
+}
+
+#[test]
+fn test_mirror_swap_moves_opening_stone() {
+    let mut game = GameY::new(3).with_swap_rule(SwapRule::Mirror);
+    game.add_move(Movement::Placement {
+        player: PlayerId::new(0),
+        coords: Coordinates::new(1, 1, 0),
+    })
+    .unwrap();
+    game.add_move(Movement::Action {
+        player: PlayerId::new(1),
+        action: GameAction::Swap,
+    })
+    .unwrap();
+
+    let yen: YEN = (&game).into();
+    assert_eq!(yen.layout(), "./R./...");
+    assert_eq!(game.available_cells().len(), 5);
+    assert!(!game.available_cells().contains(&1));
+}
+
+#[test]
+fn test_swap_rejected_when_disabled() {
+    let mut game = GameY::new(3).with_swap_rule(SwapRule::Disabled);
+    game.add_move(Movement::Placement {
+        player: PlayerId::new(0),
+        coords: Coordinates::new(1, 1, 0),
+    })
+    .unwrap();
+
+    assert!(!game.can_swap());
+    let result = game.add_move(Movement::Action {
+        player: PlayerId::new(1),
+        action: GameAction::Swap,
+    });
+    assert!(matches!(result, Err(GameYError::InvalidSwap { .. })));
 }
 
 // ============================================================================
@@ -820,6 +949,10 @@ fn test_full_game_on_size_4_board() {
     ];
 
     for (player_id, coords) in &moves {
+        // Moves after the game is over are rejected
+        if game.check_game_over() {
+            break;
+        }
         game.add_move(Movement::Placement {
             player: PlayerId::new(*player_id),
             coords: *coords,
@@ -891,3 +1024,172 @@ fn test_union_find_correctly_merges_components() {
         _ => panic!("Player 0 should have won"),
     }
 }
+
+// ============================================================================
+// Undo / Redo Tests
+// ============================================================================
+
+#[test]
+fn test_undo_restores_yen_and_available_cells() {
+    let mut game = GameY::new(4);
+    game.add_move(Movement::Placement {
+        player: PlayerId::new(0),
+        coords: Coordinates::new(3, 0, 0),
+    })
+    .unwrap();
+    let yen_before: YEN = (&game).into();
+    let cells_before = game.available_cells().clone();
+
+    game.add_move(Movement::Placement {
+        player: PlayerId::new(1),
+        coords: Coordinates::new(1, 1, 1),
+    })
+    .unwrap();
+    game.undo().unwrap();
+
+    let yen_after: YEN = (&game).into();
+    assert_eq!(yen_before.layout(), yen_after.layout());
+    assert_eq!(yen_before.turn(), yen_after.turn());
+    assert_eq!(&cells_before, game.available_cells());
+    assert_eq!(game.history().len(), 1);
+}
+
+#[test]
+fn test_undo_all_moves_then_redo_reaches_same_winner() {
+    let mut game = GameY::new(3);
+    let moves = vec![
+        (0, Coordinates::new(0, 0, 2)),
+        (1, Coordinates::new(2, 0, 0)),
+        (0, Coordinates::new(0, 1, 1)),
+        (1, Coordinates::new(1, 1, 0)),
+        (0, Coordinates::new(0, 2, 0)),
+    ];
+    for (player, coords) in moves {
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(player),
+            coords,
+        })
+        .unwrap();
+    }
+
+    while game.undo().is_some() {}
+    assert_eq!(game.available_cells().len(), 6);
+    assert_eq!(game.next_player(), Some(PlayerId::new(0)));
+
+    while game.redo().is_some() {}
+    match game.status() {
+        GameStatus::Finished { winner } => assert_eq!(*winner, PlayerId::new(0)),
+        _ => panic!("Game should be finished after redoing the winning move"),
+    }
+}
+
+#[test]
+fn test_make_unmake_search_leaves_game_untouched() {
+    let mut game = GameY::new(3);
+    game.add_move(Movement::Placement {
+        player: PlayerId::new(0),
+        coords: Coordinates::new(0, 0, 2),
+    })
+    .unwrap();
+    let yen_before: YEN = (&game).into();
+    let hash_before = game.zobrist_hash();
+
+    // Try every reply and every answer to it, as a depth-2 search would
+    for reply in game.available_cells().clone() {
+        let reply = Coordinates::from_index(reply, 3);
+        game.make_move(PlayerId::new(1), reply).unwrap();
+        for answer in game.available_cells().clone() {
+            let answer = Coordinates::from_index(answer, 3);
+            game.make_move(PlayerId::new(0), answer).unwrap();
+            game.unmake_move().unwrap();
+        }
+        game.unmake_move().unwrap();
+    }
+
+    let yen_after: YEN = (&game).into();
+    assert_eq!(yen_before.layout(), yen_after.layout());
+    assert_eq!(game.zobrist_hash(), hash_before);
+    assert_eq!(game.next_player(), Some(PlayerId::new(1)));
+    assert_eq!(game.history().len(), 1);
+}
+
+// ============================================================================
+// Coordinate Validation Tests
+// ============================================================================
+
+#[test]
+fn test_add_move_out_of_range_coordinates_is_typed_error() {
+    let mut game = GameY::new(5);
+
+    let result = game.add_move(Movement::Placement {
+        player: PlayerId::new(0),
+        coords: Coordinates::new(9, 9, 9),
+    });
+
+    match result {
+        Err(GameYError::CoordOutOfRange {
+            id_coord,
+            coord,
+            board_size,
+        }) => {
+            assert_eq!(id_coord, 'x');
+            assert_eq!(coord, 9);
+            assert_eq!(board_size, 5);
+        }
+        other => panic!("Expected CoordOutOfRange error, got {:?}", other),
+    }
+    assert_eq!(game.available_cells().len(), 15);
+}
+
+#[test]
+fn test_coordinates_try_from_vec_and_index() {
+    let coords = Coordinates::try_from_vec(&[2, 1, 1], 5).unwrap();
+    let idx = coords.to_index(5);
+    assert_eq!(Coordinates::try_from_index(idx, 5).unwrap(), coords);
+
+    assert!(matches!(
+        Coordinates::try_from_vec(&[1, 2, 3, 4], 5),
+        Err(GameYError::BadCoordsNumber { .. })
+    ));
+}
+
+// ============================================================================
+// Winning Group Tests
+// ============================================================================
+
+#[test]
+fn test_winning_group_from_yen_matches_union_find() {
+    let yen = YEN::new(4, 1, vec!['B', 'R'], "B/RB/RB./RB.R".to_string());
+    let game = GameY::try_from(yen).unwrap();
+
+    let group = game.winning_group().expect("B connects the three sides");
+    assert_eq!(group.player, PlayerId::new(0));
+    assert_eq!(group.cells.len(), 4);
+    assert_eq!(game.group_edges(&group).len(), 3);
+
+    let red = game.group_at(&Coordinates::new(2, 0, 1)).unwrap();
+    assert_eq!(red.player, PlayerId::new(1));
+    assert!(red.touches_side_b);
+    assert!(!red.is_winning());
+}
+
+#[test]
+fn test_undo_removes_winning_group() {
+    let mut game = GameY::new(2);
+    for (player, coords) in [
+        (0, Coordinates::new(1, 0, 0)),
+        (1, Coordinates::new(0, 1, 0)),
+        (0, Coordinates::new(0, 0, 1)),
+    ] {
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(player),
+            coords,
+        })
+        .unwrap();
+    }
+    assert!(game.winning_group().is_some());
+
+    game.undo();
+    assert!(game.winning_group().is_none());
+    assert_eq!(game.groups().len(), 2);
+}